@@ -0,0 +1,2 @@
+-- Free-form notes written by the user for each instance visit
+ALTER TABLE instances ADD COLUMN note TEXT;
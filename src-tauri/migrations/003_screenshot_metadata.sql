@@ -0,0 +1,5 @@
+-- Metadata read from screenshot files (PNG chunks and file name)
+ALTER TABLE screenshots ADD COLUMN width INTEGER;
+ALTER TABLE screenshots ADD COLUMN height INTEGER;
+ALTER TABLE screenshots ADD COLUMN metadata TEXT;             -- JSON (ScreenshotMetadata)
+ALTER TABLE screenshots ADD COLUMN metadata_read_at INTEGER;  -- Unix timestamp, NULL if never read
@@ -0,0 +1,11 @@
+-- Screenshots found outside the VRChat log (folder scanner, manual attachment)
+ALTER TABLE screenshots ADD COLUMN source TEXT NOT NULL DEFAULT 'log';  -- 'log', 'auto_matched', 'manual'
+
+CREATE INDEX idx_screenshots_file_path ON screenshots(file_path);
+
+-- Picture folders watched by the screenshot scanner
+CREATE TABLE screenshot_folders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    path TEXT NOT NULL UNIQUE,
+    added_at INTEGER NOT NULL  -- Unix timestamp
+);
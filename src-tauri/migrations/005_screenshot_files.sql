@@ -0,0 +1,6 @@
+-- Content fingerprints for finding moved screenshot files
+ALTER TABLE screenshots ADD COLUMN file_size INTEGER;
+ALTER TABLE screenshots ADD COLUMN content_hash TEXT;      -- SHA-256 (hex), NULL until hashed
+ALTER TABLE screenshots ADD COLUMN missing_since INTEGER;  -- Unix timestamp (ms), NULL while the file exists
+
+CREATE INDEX idx_screenshots_content_hash ON screenshots(content_hash);
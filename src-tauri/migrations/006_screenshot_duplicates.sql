@@ -0,0 +1,4 @@
+-- Near-duplicate detection
+ALTER TABLE screenshots ADD COLUMN perceptual_hash INTEGER;  -- 64-bit dHash, NULL until computed
+ALTER TABLE screenshots ADD COLUMN hidden INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE screenshots ADD COLUMN duplicate_of INTEGER REFERENCES screenshots(id) ON DELETE SET NULL;
@@ -0,0 +1,8 @@
+-- Favorites, captions and cover photos
+ALTER TABLE screenshots ADD COLUMN favorite INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE screenshots ADD COLUMN caption TEXT;
+
+ALTER TABLE instances ADD COLUMN cover_screenshot_id INTEGER REFERENCES screenshots(id) ON DELETE SET NULL;
+ALTER TABLE worlds ADD COLUMN cover_screenshot_id INTEGER REFERENCES screenshots(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_screenshots_favorite ON screenshots(taken_at) WHERE favorite = 1;
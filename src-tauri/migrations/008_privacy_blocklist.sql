@@ -0,0 +1,9 @@
+-- People the user asked to be erased from the journal and never recorded again
+CREATE TABLE blocked_users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id TEXT NOT NULL UNIQUE,  -- VRChat user ID (usr_xxx)
+    blocked_at INTEGER NOT NULL    -- Unix timestamp
+);
+
+-- Participants erased by a privacy purge, still included in participant counts
+ALTER TABLE instances ADD COLUMN purged_participants INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,18 @@
+-- Labels the user gives to people; tagged people are exempt from retention rules
+CREATE TABLE user_tags (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    tag TEXT NOT NULL,
+    UNIQUE (user_id, tag),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_user_tags_tag ON user_tags(tag);
+
+-- State of the retention rules for remote player data (single row; the rules are in the settings)
+CREATE TABLE retention_policy (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    last_pruned_at INTEGER  -- Unix timestamp
+);
+
+INSERT INTO retention_policy (id) VALUES (1);
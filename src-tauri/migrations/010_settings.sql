@@ -0,0 +1,5 @@
+-- User settings, one JSON value per top-level key (missing keys use defaults)
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL  -- JSON
+);
@@ -0,0 +1,16 @@
+-- One row per VRChat launch (each launch writes its own log file)
+CREATE TABLE game_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    log_file TEXT NOT NULL UNIQUE,            -- Path of the log file
+    version TEXT,                             -- VRChat build
+    vr_mode INTEGER,                          -- 1 = VR, 0 = desktop, NULL = unknown
+    started_at INTEGER NOT NULL,              -- Unix timestamp of the first log line
+    ended_at INTEGER,                         -- Unix timestamp (NULL while running or unknown)
+    quit_cleanly INTEGER NOT NULL DEFAULT 0   -- OnApplicationQuit was logged
+);
+
+CREATE INDEX idx_game_sessions_started_at ON game_sessions(started_at DESC);
+
+ALTER TABLE instances ADD COLUMN game_session_id INTEGER REFERENCES game_sessions(id) ON DELETE SET NULL;
+
+CREATE INDEX idx_instances_game_session_id ON instances(game_session_id);
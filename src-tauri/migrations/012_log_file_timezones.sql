@@ -0,0 +1,2 @@
+-- Time zone a log file was written in, for logs copied from another machine
+ALTER TABLE log_files ADD COLUMN timezone TEXT;  -- IANA name (NULL uses the settings' time zone)
@@ -0,0 +1,96 @@
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Periodic background threads (backups, folder scans, file checks) and their stop signal
+#[derive(Default)]
+pub struct BackgroundTasks {
+    stopping: Mutex<bool>,
+    stopped: Condvar,
+    threads: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+}
+
+impl BackgroundTasks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a thread that `stop` waits for. It should return once `sleep` returns false.
+    pub fn spawn(&self, name: &'static str, run: impl FnOnce() + Send + 'static) {
+        let thread = std::thread::spawn(run);
+        self.threads
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name, thread));
+    }
+
+    /// Sleep for `timeout` unless a stop is requested first. Returns whether to keep running.
+    pub fn sleep(&self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut stopping = self.lock();
+
+        while !*stopping {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                break;
+            }
+            stopping = self
+                .stopped
+                .wait_timeout(stopping, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+
+        !*stopping
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        *self.lock()
+    }
+
+    /// Ask the threads to stop and wait until the current run of each has finished
+    pub fn stop(&self) {
+        *self.lock() = true;
+        self.stopped.notify_all();
+
+        let threads = std::mem::take(&mut *self.threads.lock().unwrap_or_else(|e| e.into_inner()));
+        for (name, thread) in threads {
+            if thread.join().is_err() {
+                tracing::error!("Background thread {} panicked", name);
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, bool> {
+        self.stopping.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_stop_wakes_sleeping_threads_and_joins_them() {
+        let tasks = Arc::new(BackgroundTasks::new());
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let (thread_tasks, thread_runs) = (tasks.clone(), runs.clone());
+        tasks.spawn("test", move || loop {
+            thread_runs.fetch_add(1, Ordering::SeqCst);
+            if !thread_tasks.sleep(Duration::from_secs(60 * 60)) {
+                break;
+            }
+        });
+        assert!(tasks.sleep(Duration::from_millis(10)));
+
+        let started = Instant::now();
+        tasks.stop();
+        assert!(started.elapsed() < Duration::from_secs(60));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(tasks.is_stopping());
+        assert!(!tasks.sleep(Duration::from_secs(60 * 60)));
+    }
+}
@@ -1,59 +1,370 @@
-use crate::{db, log_monitor::Monitor};
-use std::time::Duration;
+use super::{AppState, BackgroundTasks};
+use crate::{
+    db::{self, backup, operations},
+    log_monitor::{LogWatcher, Monitor, MonitorGate, MonitorHandle, PollBackoff},
+    logging::Logging,
+    screenshot::{self, ThumbnailConfig, ThumbnailWorker},
+    settings::{self, SettingsStore},
+    types::VRChatEvent,
+};
+use chrono::Utc;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{App, Emitter, Manager};
 
+/// Delay before retrying a failed scheduled backup
+const BACKUP_RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Interval between scans of the watched picture folders
+const SCREENSHOT_SCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Interval between checks that screenshot files still exist
+const SCREENSHOT_FILE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Interval between checks whether retention rules are due
+const RETENTION_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// How often threads waiting for a settings change check for commands or a stop request
+const CONTROL_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Initialize database and start log monitoring
 pub fn setup(app: &mut App) -> Result<(), Box<dyn std::error::Error>> {
-    let database = setup_database(app)?;
-    start_log_monitor(database, app.handle().clone());
+    // Default levels until the settings are loaded
+    let logging = Logging::init(&app.path().app_log_dir()?, &Default::default())?;
+
+    let db_path = setup_database(app)?;
+    let default_backup_dir = app.path().app_data_dir()?.join("backups");
+    let thumbnail_worker = start_thumbnail_worker(app)?;
+
+    // Commands and the monitor thread use separate connections
+    let database = db::Database::open(db_path.clone())?;
+    let settings = Arc::new(SettingsStore::new(
+        settings::load(database.connection()).unwrap_or_else(|e| {
+            tracing::warn!("{}, using defaults", e);
+            Default::default()
+        }),
+    ));
+    if let Err(e) = logging.apply(&settings.get().logging) {
+        tracing::warn!("{}, using default log levels", e);
+    }
+
+    let monitor = start_log_monitor(
+        db::Database::open(db_path.clone())?,
+        settings.clone(),
+        thumbnail_worker.clone(),
+        app.handle().clone(),
+    );
+    app.manage(AppState::new(
+        database,
+        db_path,
+        monitor,
+        settings,
+        default_backup_dir,
+        thumbnail_worker,
+        logging,
+    ));
+    let state = app.state::<AppState>();
+    start_backup_scheduler(state.background(), app.handle().clone());
+    start_screenshot_scanner(state.background(), app.handle().clone());
+    start_screenshot_file_checker(state.background(), app.handle().clone());
     Ok(())
 }
 
 /// Setup database: create app data directory and run migrations
-fn setup_database(app: &App) -> Result<db::Database, Box<dyn std::error::Error>> {
+fn setup_database(app: &App) -> Result<PathBuf, Box<dyn std::error::Error>> {
     let app_data_dir = app.path().app_data_dir()?;
     std::fs::create_dir_all(&app_data_dir)?;
     let db_path = app_data_dir.join("vrcjournal.db");
 
-    let database = db::Database::open(db_path)?;
+    let database = db::Database::open(db_path.clone())?;
     database.migrate()?;
 
-    Ok(database)
+    Ok(db_path)
 }
 
 /// Start log monitor in a background thread
-fn start_log_monitor(database: db::Database, app_handle: tauri::AppHandle) {
-    std::thread::spawn(move || {
-        let mut monitor = Monitor::new(database);
+fn start_log_monitor(
+    database: db::Database,
+    settings: Arc<SettingsStore>,
+    thumbnail_worker: ThumbnailWorker,
+    app_handle: tauri::AppHandle,
+) -> MonitorHandle {
+    let gate = Arc::new(MonitorGate::new());
+    let mut watcher = LogWatcher::new();
+    let waker = watcher.waker();
+    let monitor_gate = gate.clone();
+
+    MonitorHandle::spawn(gate, waker, move |mut control| {
+        let mut settings_version = settings.version();
+        let mut monitor = Monitor::new(database, &settings.get());
 
-        // Initialize monitor
-        if let Err(e) = monitor.initialize() {
-            eprintln!("Failed to initialize monitor: {}", e);
-            return;
+        // Initialize monitor, retrying when the settings change (e.g. a log folder is set)
+        while let Err(e) = monitor.initialize() {
+            tracing::error!("Failed to initialize monitor: {}", e);
+            while !settings.wait_for_change(settings_version, CONTROL_CHECK_INTERVAL) {
+                control.receive();
+                if control.shutdown_requested() {
+                    return;
+                }
+            }
+            settings_version = settings.version();
+            monitor.configure(&settings.get());
         }
 
         // Signal that backend is ready
         if let Err(e) = app_handle.emit("backend-ready", ()) {
-            eprintln!("Failed to emit backend-ready event: {}", e);
-            return;
+            tracing::error!("Failed to emit backend-ready event: {}", e);
         }
 
+        control.publish(monitor.diagnostics());
+        watcher.watch(monitor.log_dirs());
+        let mut backoff = PollBackoff::default();
+
+        // Checked on the first pass after startup
+        let mut next_retention_check = Instant::now();
+
         // Real-time monitoring loop
         loop {
-            std::thread::sleep(Duration::from_millis(1000));
+            // Woken early when a log file changes or a command arrives
+            let interval =
+                backoff.interval(settings.get().poll_interval(), monitor.is_in_instance());
+            watcher.wait(interval);
+
+            control.receive();
+            if control.shutdown_requested() {
+                break;
+            }
+
+            // Waits here while a restore holds the gate; skips the poll while paused
+            let Some(_pass) = monitor_gate.enter() else {
+                continue;
+            };
+
+            if monitor_gate.take_reload_request() {
+                if let Err(e) = monitor.reload() {
+                    tracing::error!("Failed to reload monitor: {}", e);
+                }
+            }
+
+            if settings.version() != settings_version {
+                settings_version = settings.version();
+                if let Err(e) = monitor.apply_settings(&settings.get()) {
+                    tracing::error!("Failed to apply settings to monitor: {}", e);
+                }
+                watcher.watch(monitor.log_dirs());
+            }
+
+            if control.take_rescan_request() {
+                match monitor.rescan() {
+                    Ok(()) => backoff = PollBackoff::default(),
+                    Err(e) => tracing::error!("Failed to rescan logs: {}", e),
+                }
+            }
 
             match monitor.fetch_new_events() {
                 Ok(events) => {
                     for event in events {
+                        if let VRChatEvent::ScreenshotTaken {
+                            screenshot_id,
+                            file_path,
+                            ..
+                        } = &event
+                        {
+                            thumbnail_worker.enqueue(*screenshot_id, file_path.into());
+                        }
+
                         if let Err(e) = app_handle.emit("vrchat-event", &event) {
-                            eprintln!("Failed to emit event: {}", e);
+                            tracing::error!("Failed to emit event: {}", e);
                         }
                     }
                 }
                 Err(e) => {
-                    eprintln!("Failed to fetch new events: {}", e);
+                    tracing::error!("Failed to fetch new events: {}", e);
                 }
             }
+            backoff.record(monitor.found_new_data());
+            control.publish(monitor.diagnostics());
+
+            if Instant::now() >= next_retention_check {
+                next_retention_check = Instant::now() + RETENTION_CHECK_INTERVAL;
+                let policy = settings.get().retention;
+                match monitor.apply_retention_if_due(&policy, Utc::now().timestamp_millis()) {
+                    Ok(Some(summary)) => tracing::info!(?summary, "Applied retention rules"),
+                    Ok(None) => {}
+                    Err(e) => tracing::error!("{}", e),
+                }
+            }
+        }
+
+        match monitor.shutdown() {
+            Ok(()) => tracing::info!("Log monitor stopped"),
+            Err(e) => tracing::error!("Failed to stop log monitor cleanly: {}", e),
+        }
+    })
+}
+
+/// Stop background work before the app exits
+pub fn shutdown(app_handle: &tauri::AppHandle) {
+    let state = app_handle.state::<AppState>();
+    state.monitor().shutdown();
+    // Scans queue thumbnails, so they stop before the thumbnail worker
+    state.background().stop();
+    state.thumbnail_worker().shutdown();
+
+    // Wait for a command or scheduled job that is still writing
+    let _database = state.db();
+}
+
+/// Start the thumbnail worker, notifying the frontend as thumbnails become available
+fn start_thumbnail_worker(app: &App) -> Result<ThumbnailWorker, Box<dyn std::error::Error>> {
+    let config = ThumbnailConfig::new(app.path().app_data_dir()?.join("thumbnails"));
+    let app_handle = app.handle().clone();
+
+    Ok(ThumbnailWorker::spawn(
+        config,
+        move |screenshot_id, path| {
+            let payload = ThumbnailReady {
+                screenshot_id,
+                path: path.to_string_lossy().to_string(),
+            };
+            if let Err(e) = app_handle.emit("thumbnail-ready", payload) {
+                tracing::error!("Failed to emit thumbnail-ready event: {}", e);
+            }
+        },
+    ))
+}
+
+/// Payload of the `thumbnail-ready` event
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ThumbnailReady {
+    screenshot_id: i64,
+    path: String,
+}
+
+/// Create backups periodically in a background thread
+fn start_backup_scheduler(background: &BackgroundTasks, app_handle: tauri::AppHandle) {
+    background.spawn("backup scheduler", move || {
+        let state = app_handle.state::<AppState>();
+
+        loop {
+            // A new backup interval or folder is picked up right away
+            let version = state.settings().version();
+            let config = state.backup_config();
+            let changed =
+                wait_for_settings_change(&state, version, backup::time_until_next_backup(&config));
+            if state.background().is_stopping() {
+                break;
+            }
+            if changed {
+                continue;
+            }
+
+            // VACUUM INTO on a separate connection leaves commands unblocked
+            let result = state
+                .open_db()
+                .and_then(|database| database.create_backup(&config));
+
+            match result {
+                Ok(info) => tracing::info!("Scheduled backup created: {}", info.file_path),
+                Err(e) => {
+                    tracing::error!("Scheduled backup failed: {}", e);
+                    if !state.background().sleep(BACKUP_RETRY_INTERVAL) {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Like `SettingsStore::wait_for_change`, but returns early when the app is stopping
+fn wait_for_settings_change(state: &AppState, version: u64, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+
+    while !state.background().is_stopping() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return false;
+        }
+        if state
+            .settings()
+            .wait_for_change(version, remaining.min(CONTROL_CHECK_INTERVAL))
+        {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Link images in the watched picture folders periodically in a background thread
+fn start_screenshot_scanner(background: &BackgroundTasks, app_handle: tauri::AppHandle) {
+    background.spawn("screenshot scanner", move || {
+        let state = app_handle.state::<AppState>();
+
+        loop {
+            match state.scan_screenshot_folders() {
+                Ok(attached) if !attached.is_empty() => {
+                    for screenshot in &attached {
+                        state
+                            .thumbnail_worker()
+                            .enqueue(screenshot.screenshot_id, (&screenshot.file_path).into());
+                    }
+                    if let Err(e) = app_handle.emit("screenshots-attached", &attached) {
+                        tracing::error!("Failed to emit screenshots-attached event: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Failed to scan screenshot folders: {}", e),
+            }
+
+            if !state.background().sleep(SCREENSHOT_SCAN_INTERVAL) {
+                break;
+            }
+        }
+    });
+}
+
+/// Flag missing screenshot files and hash new ones periodically in a background thread
+fn start_screenshot_file_checker(background: &BackgroundTasks, app_handle: tauri::AppHandle) {
+    background.spawn("screenshot file checker", move || {
+        let state = app_handle.state::<AppState>();
+
+        loop {
+            let files = state.db().and_then(|database| {
+                operations::get_screenshot_files(database.connection(), false)
+                    .map_err(|e| e.to_string())
+            });
+
+            let summary = files.and_then(|files| {
+                // Reading and hashing files happens without holding the database lock
+                let checks = screenshot::check_files(&files);
+                state.db().and_then(|database| {
+                    screenshot::apply_file_checks(database.connection(), &checks)
+                        .map_err(|e| e.to_string())
+                })
+            });
+
+            match summary {
+                Ok(summary) if summary.missing > 0 || summary.found_again > 0 => {
+                    tracing::info!(
+                        "Screenshot files: {} missing, {} found again",
+                        summary.missing,
+                        summary.found_again
+                    );
+                    if let Err(e) = app_handle.emit("screenshot-files-changed", &summary) {
+                        tracing::error!("Failed to emit screenshot-files-changed event: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::error!("Failed to check screenshot files: {}", e),
+            }
+
+            if !state.background().sleep(SCREENSHOT_FILE_CHECK_INTERVAL) {
+                break;
+            }
         }
     });
 }
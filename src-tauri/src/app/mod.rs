@@ -1,3 +1,7 @@
+mod background;
 mod lifecycle;
+mod state;
 
-pub use lifecycle::setup;
+pub use background::BackgroundTasks;
+pub use lifecycle::{setup, shutdown};
+pub use state::AppState;
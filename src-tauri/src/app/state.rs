@@ -0,0 +1,127 @@
+use super::BackgroundTasks;
+use crate::db::{self, operations};
+use crate::log_monitor::{MonitorGate, MonitorHandle};
+use crate::logging::Logging;
+use crate::screenshot::{self, AttachedScreenshot, FolderScanner, ThumbnailWorker};
+use crate::settings::SettingsStore;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Shared state accessible from Tauri commands
+pub struct AppState {
+    db: Mutex<db::Database>,
+    db_path: PathBuf,
+    monitor: MonitorHandle,
+    settings: Arc<SettingsStore>,
+    default_backup_dir: PathBuf, // Used when no backup folder is set
+    thumbnail_worker: ThumbnailWorker,
+    scanner: Mutex<FolderScanner>, // Shared by the scheduled and manual folder scans
+    background: BackgroundTasks,
+    logging: Logging,
+}
+
+impl AppState {
+    pub fn new(
+        database: db::Database,
+        db_path: PathBuf,
+        monitor: MonitorHandle,
+        settings: Arc<SettingsStore>,
+        default_backup_dir: PathBuf,
+        thumbnail_worker: ThumbnailWorker,
+        logging: Logging,
+    ) -> Self {
+        Self {
+            db: Mutex::new(database),
+            db_path,
+            monitor,
+            settings,
+            default_backup_dir,
+            thumbnail_worker,
+            scanner: Mutex::new(FolderScanner::new()),
+            background: BackgroundTasks::new(),
+            logging,
+        }
+    }
+
+    /// Lock the database connection used by commands
+    pub fn db(&self) -> Result<MutexGuard<'_, db::Database>, String> {
+        self.db
+            .lock()
+            .map_err(|_| "Database lock poisoned".to_string())
+    }
+
+    /// Open a separate connection for long reads that should not hold the command lock
+    pub fn open_db(&self) -> Result<db::Database, String> {
+        db::Database::open(self.db_path.clone())
+            .map_err(|e| format!("Failed to open database: {}", e))
+    }
+
+    /// Control channel of the log monitor thread
+    pub fn monitor(&self) -> &MonitorHandle {
+        &self.monitor
+    }
+
+    /// Gate used to pause the log monitor
+    pub fn monitor_gate(&self) -> &MonitorGate {
+        self.monitor.gate()
+    }
+
+    pub fn settings(&self) -> &SettingsStore {
+        &self.settings
+    }
+
+    /// Backup configuration from the current settings
+    pub fn backup_config(&self) -> db::BackupConfig {
+        self.settings.get().backup_config(&self.default_backup_dir)
+    }
+
+    /// Periodic background threads, stopped on exit
+    pub fn background(&self) -> &BackgroundTasks {
+        &self.background
+    }
+
+    pub fn thumbnail_worker(&self) -> &ThumbnailWorker {
+        &self.thumbnail_worker
+    }
+
+    /// Lock the picture folder scanner, which remembers files handled by earlier scans
+    pub fn scanner(&self) -> Result<MutexGuard<'_, FolderScanner>, String> {
+        self.scanner
+            .lock()
+            .map_err(|_| "Screenshot scanner lock poisoned".to_string())
+    }
+
+    /// Link new images in the watched picture folders. Files are walked, hashed and read
+    /// while only the scanner is locked; the database lock is held just for the lookups.
+    pub fn scan_screenshot_folders(&self) -> Result<Vec<AttachedScreenshot>, String> {
+        let mut scanner = self.scanner()?;
+
+        let folders = {
+            let database = self.db()?;
+            operations::get_screenshot_folders(database.connection())
+                .map_err(|e| format!("Failed to get screenshot folders: {}", e))?
+        };
+        let folders: Vec<PathBuf> = folders.into_iter().map(PathBuf::from).collect();
+        let files = scanner.collect_new_files(&folders);
+        if files.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let files = {
+            let database = self.db()?;
+            scanner
+                .skip_recorded(database.connection(), files)
+                .map_err(|e| format!("Failed to look up screenshots: {}", e))?
+        };
+        let files = screenshot::read_scanned_files(files, self.settings().get().local_timezone());
+
+        let database = self.db()?;
+        scanner
+            .link(database.connection(), files)
+            .map_err(|e| format!("Failed to link screenshots: {}", e))
+    }
+
+    pub fn logging(&self) -> &Logging {
+        &self.logging
+    }
+}
@@ -0,0 +1,41 @@
+use crate::app::AppState;
+use crate::db::{backup, BackupInfo};
+use crate::settings;
+use std::path::Path;
+use tauri::Emitter;
+
+/// Create a database backup now
+#[tauri::command]
+pub async fn create_backup(state: tauri::State<'_, AppState>) -> Result<BackupInfo, String> {
+    state.open_db()?.create_backup(&state.backup_config())
+}
+
+/// List backups in the backup folder, newest first
+#[tauri::command]
+pub async fn list_backups(state: tauri::State<'_, AppState>) -> Result<Vec<BackupInfo>, String> {
+    backup::list_backups(&state.backup_config().dir)
+}
+
+/// Replace the live database with a backup while the log monitor is paused
+#[tauri::command]
+pub async fn restore_backup(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    file_path: String,
+) -> Result<(), String> {
+    {
+        let _paused = state.monitor_gate().hold();
+        let mut database = state.db()?;
+
+        database.restore_backup(Path::new(&file_path), &state.backup_config())?;
+
+        // Settings describe this machine, not the journal, so they survive a restore
+        settings::save(database.connection(), &state.settings().get())?;
+
+        // Monitor state refers to the old database contents
+        state.monitor_gate().request_reload();
+    }
+
+    app.emit("database-restored", ())
+        .map_err(|e| format!("Failed to emit database-restored event: {}", e))
+}
@@ -0,0 +1,48 @@
+use crate::app::AppState;
+use crate::log_monitor::MonitorDiagnostics;
+use crate::logging::{self, LogExportSummary};
+use std::path::Path;
+
+/// Backend health report for troubleshooting
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    #[serde(flatten)]
+    pub monitor: MonitorDiagnostics,
+    pub paused: bool,
+    pub database_size: u64, // Bytes
+    pub schema_version: i32,
+}
+
+/// Report log monitor state, parse statistics and database health
+#[tauri::command]
+pub async fn get_diagnostics(state: tauri::State<'_, AppState>) -> Result<Diagnostics, String> {
+    let mut monitor = state.monitor().diagnostics();
+    for file in &mut monitor.files {
+        file.size = std::fs::metadata(&file.path).ok().map(|meta| meta.len());
+    }
+
+    let database = state.db()?;
+    let database_size = database
+        .size()
+        .map_err(|e| format!("Failed to get database size: {}", e))?;
+    let schema_version = database
+        .schema_version()
+        .map_err(|e| format!("Failed to get schema version: {}", e))?;
+
+    Ok(Diagnostics {
+        monitor,
+        paused: state.monitor().is_paused(),
+        database_size,
+        schema_version,
+    })
+}
+
+/// Write the recent app logs (kept for a week) to one file for bug reports
+#[tauri::command]
+pub async fn export_app_logs(
+    state: tauri::State<'_, AppState>,
+    path: String,
+) -> Result<LogExportSummary, String> {
+    logging::export_logs(state.logging().dir(), Path::new(&path))
+}
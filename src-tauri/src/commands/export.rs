@@ -0,0 +1,110 @@
+use crate::app::AppState;
+use crate::db::Database;
+use crate::export::{
+    self, AnonymizeOptions, CsvKind, DiaryOptions, DiarySummary, ExportOptions, ExportSummary,
+    IcalOptions,
+};
+use rusqlite::Transaction;
+use std::path::Path;
+use tauri::Manager;
+
+/// Export the journal as a JSON document
+#[tauri::command]
+pub async fn export_json(
+    state: tauri::State<'_, AppState>,
+    path: String,
+    options: Option<ExportOptions>,
+) -> Result<ExportSummary, String> {
+    let mut options = options.unwrap_or_default();
+    with_privacy_defaults(&state, &mut options.anonymize);
+    let mut database = state.open_db()?;
+    let snapshot = read_snapshot(&mut database)?;
+
+    export::export_json(&snapshot, Path::new(&path), &options)
+}
+
+/// Export instances, participants or screenshots as CSV for spreadsheets
+#[tauri::command]
+pub async fn export_csv(
+    state: tauri::State<'_, AppState>,
+    path: String,
+    kind: CsvKind,
+    options: Option<ExportOptions>,
+) -> Result<usize, String> {
+    let mut options = options.unwrap_or_default();
+    with_privacy_defaults(&state, &mut options.anonymize);
+    let mut database = state.open_db()?;
+    let snapshot = read_snapshot(&mut database)?;
+
+    export::export_csv(
+        &snapshot,
+        Path::new(&path),
+        kind,
+        &options,
+        state.settings().get().local_timezone(),
+    )
+}
+
+/// Export instances as iCalendar events
+#[tauri::command]
+pub async fn export_ical(
+    state: tauri::State<'_, AppState>,
+    path: String,
+    options: Option<IcalOptions>,
+) -> Result<usize, String> {
+    let mut options = options.unwrap_or_default();
+    with_privacy_defaults(&state, &mut options.anonymize);
+    let mut database = state.open_db()?;
+    let snapshot = read_snapshot(&mut database)?;
+
+    export::export_ical(
+        &snapshot,
+        Path::new(&path),
+        &options,
+        state.settings().get().local_timezone(),
+    )
+}
+
+/// Export a Markdown/HTML diary into a directory.
+/// Templates in `<app data>/templates/diary` override the built-in ones.
+#[tauri::command]
+pub async fn export_diary(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    out_dir: String,
+    options: Option<DiaryOptions>,
+) -> Result<DiarySummary, String> {
+    let template_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join("templates")
+        .join("diary");
+    let mut options = options.unwrap_or_default();
+    with_privacy_defaults(&state, &mut options.anonymize);
+    let mut database = state.open_db()?;
+    let snapshot = read_snapshot(&mut database)?;
+
+    export::export_diary(
+        &snapshot,
+        Path::new(&out_dir),
+        &options,
+        Some(&template_dir),
+        state.settings().get().local_timezone(),
+    )
+}
+
+/// Fill in the anonymization from the privacy settings when the export didn't choose one
+fn with_privacy_defaults(state: &AppState, anonymize: &mut Option<AnonymizeOptions>) {
+    if anonymize.is_none() {
+        *anonymize = state.settings().get().privacy.anonymize_options();
+    }
+}
+
+/// Begin a deferred read transaction so an export sees one consistent state.
+/// Exports use their own connection and leave the command lock free meanwhile.
+fn read_snapshot(database: &mut Database) -> Result<Transaction<'_>, String> {
+    database
+        .transaction()
+        .map_err(|e| format!("Failed to begin export: {}", e))
+}
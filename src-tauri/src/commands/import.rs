@@ -0,0 +1,47 @@
+use crate::app::AppState;
+use crate::import::{self, ImportSummary, LogImportSummary};
+use chrono_tz::Tz;
+use std::path::{Path, PathBuf};
+
+/// Merge a previously exported JSON journal into the database
+#[tauri::command]
+pub async fn import_json(
+    state: tauri::State<'_, AppState>,
+    path: String,
+) -> Result<ImportSummary, String> {
+    // Merging may end or change instances the monitor is tracking
+    let _paused = state.monitor_gate().hold();
+    let mut database = state.db()?;
+
+    let summary = import::import_json(&mut database, Path::new(&path))?;
+    state.monitor_gate().request_reload();
+    Ok(summary)
+}
+
+/// Import VRChat log files from another machine, read in the given time zone
+/// (None detects it from the logs or uses the settings' time zone)
+#[tauri::command]
+pub async fn import_logs(
+    state: tauri::State<'_, AppState>,
+    paths: Vec<String>,
+    timezone: Option<String>,
+) -> Result<LogImportSummary, String> {
+    let timezone = timezone
+        .map(|name| {
+            name.parse::<Tz>()
+                .map_err(|_| format!("Unknown time zone: {}", name))
+        })
+        .transpose()?;
+    let paths: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
+
+    // Keep the monitor from writing between the import's reads and commit
+    let _paused = state.monitor_gate().hold();
+    let mut database = state.db()?;
+
+    import::import_logs(
+        &mut database,
+        &paths,
+        timezone,
+        state.settings().get().timezone(),
+    )
+}
@@ -0,0 +1,60 @@
+use crate::app::AppState;
+use crate::db::operations::{self, CoverPhoto};
+use std::collections::HashMap;
+
+/// Set or clear the note for an instance (empty text clears it)
+#[tauri::command]
+pub async fn set_instance_note(
+    state: tauri::State<'_, AppState>,
+    instance_id: i64,
+    note: Option<String>,
+) -> Result<(), String> {
+    let note = note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    let database = state.db()?;
+
+    operations::set_instance_note(database.connection(), instance_id, note)
+        .map_err(|e| format!("Failed to save note: {}", e))
+}
+
+/// Choose the cover screenshot of an instance (None falls back to its first favorite)
+#[tauri::command]
+pub async fn set_instance_cover(
+    state: tauri::State<'_, AppState>,
+    instance_id: i64,
+    screenshot_id: Option<i64>,
+) -> Result<(), String> {
+    let database = state.db()?;
+    let conn = database.connection();
+
+    if let Some(screenshot_id) = screenshot_id {
+        let screenshot = operations::get_screenshot(conn, screenshot_id)
+            .map_err(|e| format!("Failed to get screenshot: {}", e))?;
+        if !matches!(screenshot, Some((id, _, _)) if id == instance_id) {
+            return Err(format!(
+                "Screenshot {} does not belong to instance {}",
+                screenshot_id, instance_id
+            ));
+        }
+    }
+
+    operations::set_instance_cover(conn, instance_id, screenshot_id)
+        .map_err(|e| format!("Failed to save cover: {}", e))
+}
+
+/// Get cover photos of instances for the timeline
+#[tauri::command]
+pub async fn get_instance_covers(
+    state: tauri::State<'_, AppState>,
+    instance_ids: Vec<i64>,
+) -> Result<HashMap<i64, Option<CoverPhoto>>, String> {
+    let database = state.db()?;
+
+    instance_ids
+        .into_iter()
+        .map(|instance_id| {
+            operations::get_instance_cover(database.connection(), instance_id)
+                .map(|cover| (instance_id, cover))
+                .map_err(|e| format!("Failed to get cover: {}", e))
+        })
+        .collect()
+}
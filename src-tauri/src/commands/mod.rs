@@ -1,5 +1,31 @@
+pub mod backup;
 pub mod browser;
+pub mod diagnostics;
+pub mod export;
 pub mod filesystem;
+pub mod import;
+pub mod instances;
+pub mod monitor;
+pub mod retention;
+pub mod screenshots;
+pub mod sessions;
+pub mod settings;
+pub mod stats;
+pub mod users;
+pub mod worlds;
 
+pub use backup::*;
 pub use browser::*;
+pub use diagnostics::*;
+pub use export::*;
 pub use filesystem::*;
+pub use import::*;
+pub use instances::*;
+pub use monitor::*;
+pub use retention::*;
+pub use screenshots::*;
+pub use sessions::*;
+pub use settings::*;
+pub use stats::*;
+pub use users::*;
+pub use worlds::*;
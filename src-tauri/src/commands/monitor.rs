@@ -0,0 +1,54 @@
+use crate::app::AppState;
+use crate::db::operations;
+use crate::log_monitor::MonitorCommand;
+use chrono_tz::Tz;
+
+/// Stop reading VRChat logs until `resume_monitor` (new lines are read on resume)
+#[tauri::command]
+pub async fn pause_monitor(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.monitor().pause();
+    Ok(())
+}
+
+/// Resume reading VRChat logs
+#[tauri::command]
+pub async fn resume_monitor(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.monitor().resume();
+    Ok(())
+}
+
+/// Look for log files again and read anything new right away
+#[tauri::command]
+pub async fn rescan_logs(state: tauri::State<'_, AppState>) -> Result<(), String> {
+    state.monitor().send(MonitorCommand::Rescan)
+}
+
+/// Whether the log monitor is paused
+#[tauri::command]
+pub async fn is_monitor_paused(state: tauri::State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.monitor().is_paused())
+}
+
+/// Set the time zone a log file was written in (None detects it again on the next read).
+/// Applies to lines read afterwards, so set it before copying in logs from another machine.
+#[tauri::command]
+pub async fn set_log_file_timezone(
+    state: tauri::State<'_, AppState>,
+    path: String,
+    timezone: Option<String>,
+) -> Result<(), String> {
+    if let Some(timezone) = &timezone {
+        timezone
+            .parse::<Tz>()
+            .map_err(|_| format!("Unknown time zone: {}", timezone))?;
+    }
+
+    {
+        let _paused = state.monitor_gate().hold();
+        let database = state.db()?;
+        operations::set_log_file_timezone(database.connection(), &path, timezone.as_deref())
+            .map_err(|e| format!("Failed to save log time zone: {}", e))?;
+    }
+
+    state.monitor().send(MonitorCommand::Rescan)
+}
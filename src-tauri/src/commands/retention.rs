@@ -0,0 +1,38 @@
+use crate::app::AppState;
+use crate::db::operations::{self, PruneSummary, RetentionPolicy};
+use chrono::Utc;
+
+/// Report what retention rules would delete without deleting anything.
+/// Without `policy`, the rules in the settings are previewed.
+#[tauri::command]
+pub async fn preview_retention(
+    state: tauri::State<'_, AppState>,
+    policy: Option<RetentionPolicy>,
+) -> Result<PruneSummary, String> {
+    let policy = policy.unwrap_or_else(|| state.settings().get().retention);
+    let database = state.db()?;
+
+    operations::preview_prune(
+        database.connection(),
+        &policy,
+        Utc::now().timestamp_millis(),
+    )
+    .map_err(|e| format!("Failed to preview retention rules: {}", e))
+}
+
+/// Apply the retention rules now instead of waiting for the daily run
+#[tauri::command]
+pub async fn apply_retention(state: tauri::State<'_, AppState>) -> Result<PruneSummary, String> {
+    let policy = state.settings().get().retention;
+
+    // VACUUM needs the database to itself
+    let _paused = state.monitor_gate().hold();
+    let mut database = state.db()?;
+
+    let summary = database
+        .apply_retention(&policy, Utc::now().timestamp_millis())
+        .map_err(|e| format!("Failed to apply retention rules: {}", e))?;
+
+    tracing::info!(?summary, "Applied retention rules");
+    Ok(summary)
+}
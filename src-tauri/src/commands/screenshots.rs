@@ -0,0 +1,362 @@
+use crate::app::AppState;
+use crate::db::operations::{self, BestScreenshot, InstanceFilter, ScreenshotFile};
+use crate::screenshot::{
+    self, AttachedScreenshot, DuplicateGroup, FileCheckSummary, LinkCheck, RelinkSummary,
+    ScreenshotMetadata,
+};
+use chrono::Utc;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Result of re-reading screenshot metadata
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataRefreshSummary {
+    pub read: usize,
+    pub relinked: usize,
+    pub mismatched: usize,
+}
+
+/// Get stored metadata of a screenshot (None if it has never been read)
+#[tauri::command]
+pub async fn get_screenshot_metadata(
+    state: tauri::State<'_, AppState>,
+    screenshot_id: i64,
+) -> Result<Option<ScreenshotMetadata>, String> {
+    let database = state.db()?;
+
+    let json = operations::get_screenshot_metadata(database.connection(), screenshot_id)
+        .map_err(|e| format!("Failed to get screenshot metadata: {}", e))?;
+
+    Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+}
+
+/// Re-read metadata from screenshot files.
+/// Without `screenshot_ids`, reads every screenshot whose metadata has never been read.
+#[tauri::command]
+pub async fn refresh_screenshot_metadata(
+    state: tauri::State<'_, AppState>,
+    screenshot_ids: Option<Vec<i64>>,
+) -> Result<MetadataRefreshSummary, String> {
+    let files = {
+        let database = state.db()?;
+        let conn = database.connection();
+
+        let screenshot_ids = match screenshot_ids {
+            Some(ids) => ids,
+            None => operations::get_screenshots_without_metadata(conn)
+                .map_err(|e| format!("Failed to list screenshots: {}", e))?,
+        };
+
+        let mut files = Vec::with_capacity(screenshot_ids.len());
+        for screenshot_id in screenshot_ids {
+            if let Some((_, file_path, _)) = operations::get_screenshot(conn, screenshot_id)
+                .map_err(|e| format!("Failed to get screenshot: {}", e))?
+            {
+                files.push((screenshot_id, file_path));
+            }
+        }
+        files
+    };
+
+    // Reading the files happens without holding the database lock
+    let timezone = state.settings().get().local_timezone();
+    let metadata: Vec<(i64, ScreenshotMetadata)> = files
+        .into_iter()
+        .map(|(screenshot_id, file_path)| {
+            (
+                screenshot_id,
+                screenshot::read_file_metadata(Path::new(&file_path), timezone),
+            )
+        })
+        .collect();
+
+    let mut database = state.db()?;
+    let tx = database
+        .transaction()
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    let mut summary = MetadataRefreshSummary::default();
+    for (screenshot_id, metadata) in metadata {
+        let check = screenshot::apply_metadata(&tx, screenshot_id, metadata)
+            .map_err(|e| format!("Failed to refresh screenshot {}: {}", screenshot_id, e))?;
+
+        match check {
+            Some(LinkCheck::Relinked { .. }) => summary.relinked += 1,
+            Some(LinkCheck::Mismatch) => summary.mismatched += 1,
+            _ => {}
+        }
+        if check.is_some() {
+            summary.read += 1;
+        }
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    Ok(summary)
+}
+
+/// Get thumbnail paths for screenshots.
+/// Missing thumbnails are queued for generation and announced by a `thumbnail-ready` event.
+#[tauri::command]
+pub async fn get_thumbnails(
+    state: tauri::State<'_, AppState>,
+    screenshot_ids: Vec<i64>,
+) -> Result<HashMap<i64, Option<String>>, String> {
+    let worker = state.thumbnail_worker();
+    let database = state.db()?;
+
+    let mut thumbnails = HashMap::with_capacity(screenshot_ids.len());
+    for screenshot_id in screenshot_ids {
+        let Some((_, file_path, _)) =
+            operations::get_screenshot(database.connection(), screenshot_id)
+                .map_err(|e| format!("Failed to get screenshot: {}", e))?
+        else {
+            continue;
+        };
+
+        let source = Path::new(&file_path);
+        let cached = screenshot::cached_thumbnail(worker.config(), screenshot_id, source);
+        if cached.is_none() && source.is_file() {
+            worker.enqueue(screenshot_id, source.to_path_buf());
+        }
+
+        thumbnails.insert(
+            screenshot_id,
+            cached.map(|path| path.to_string_lossy().to_string()),
+        );
+    }
+
+    Ok(thumbnails)
+}
+
+/// Attach a screenshot that the log did not mention (e.g. Steam or OBS captures).
+/// Without `instance_id`, the instance open at the capture time is used.
+#[tauri::command]
+pub async fn attach_screenshot(
+    state: tauri::State<'_, AppState>,
+    file_path: String,
+    instance_id: Option<i64>,
+) -> Result<AttachedScreenshot, String> {
+    let timezone = state.settings().get().local_timezone();
+    let attached = {
+        let database = state.db()?;
+        screenshot::attach_screenshot(
+            database.connection(),
+            Path::new(&file_path),
+            instance_id,
+            timezone,
+        )?
+    };
+
+    state
+        .thumbnail_worker()
+        .enqueue(attached.screenshot_id, file_path.into());
+    Ok(attached)
+}
+
+/// Get picture folders watched by the screenshot scanner
+#[tauri::command]
+pub async fn get_screenshot_folders(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, String> {
+    let database = state.db()?;
+    operations::get_screenshot_folders(database.connection())
+        .map_err(|e| format!("Failed to get screenshot folders: {}", e))
+}
+
+/// Watch a picture folder for screenshots
+#[tauri::command]
+pub async fn add_screenshot_folder(
+    state: tauri::State<'_, AppState>,
+    path: String,
+) -> Result<(), String> {
+    if !Path::new(&path).is_dir() {
+        return Err(format!("Folder not found: {}", path));
+    }
+
+    let database = state.db()?;
+    operations::add_screenshot_folder(database.connection(), &path, Utc::now().timestamp_millis())
+        .map_err(|e| format!("Failed to add screenshot folder: {}", e))
+}
+
+/// Stop watching a picture folder (screenshots already linked are kept)
+#[tauri::command]
+pub async fn remove_screenshot_folder(
+    state: tauri::State<'_, AppState>,
+    path: String,
+) -> Result<(), String> {
+    let database = state.db()?;
+    operations::remove_screenshot_folder(database.connection(), &path)
+        .map_err(|e| format!("Failed to remove screenshot folder: {}", e))
+}
+
+/// Scan all watched folders now and link new images by capture time
+#[tauri::command]
+pub async fn scan_screenshot_folders(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<AttachedScreenshot>, String> {
+    let attached = state.scan_screenshot_folders()?;
+
+    for screenshot in &attached {
+        state
+            .thumbnail_worker()
+            .enqueue(screenshot.screenshot_id, (&screenshot.file_path).into());
+    }
+    Ok(attached)
+}
+
+/// Check that screenshot files still exist, flagging missing ones and hashing new ones
+#[tauri::command]
+pub async fn check_screenshot_files(
+    state: tauri::State<'_, AppState>,
+) -> Result<FileCheckSummary, String> {
+    let files = {
+        let database = state.db()?;
+        operations::get_screenshot_files(database.connection(), false)
+            .map_err(|e| format!("Failed to list screenshots: {}", e))?
+    };
+
+    // Reading and hashing files happens without holding the database lock
+    let checks = screenshot::check_files(&files);
+
+    let database = state.db()?;
+    screenshot::apply_file_checks(database.connection(), &checks)
+        .map_err(|e| format!("Failed to store file checks: {}", e))
+}
+
+/// Get screenshots whose file was missing at the last check
+#[tauri::command]
+pub async fn get_missing_screenshots(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<ScreenshotFile>, String> {
+    let database = state.db()?;
+    operations::get_screenshot_files(database.connection(), true)
+        .map_err(|e| format!("Failed to get missing screenshots: {}", e))
+}
+
+/// Find screenshots whose file is gone under `new_root` (e.g. after moving the pictures
+/// folder to another drive) and update their paths
+#[tauri::command]
+pub async fn relink_screenshots(
+    state: tauri::State<'_, AppState>,
+    new_root: String,
+) -> Result<RelinkSummary, String> {
+    let new_root = Path::new(&new_root);
+    if !new_root.is_dir() {
+        return Err(format!("Folder not found: {:?}", new_root));
+    }
+
+    let files = {
+        let database = state.db()?;
+        operations::get_screenshot_files(database.connection(), false)
+            .map_err(|e| format!("Failed to list screenshots: {}", e))?
+    };
+
+    let plan = screenshot::plan_relink(&files, new_root);
+
+    let database = state.db()?;
+    screenshot::apply_relink(database.connection(), &plan)
+        .map_err(|e| format!("Failed to relink screenshots: {}", e))
+}
+
+/// List groups of screenshots that look alike, within and across instances.
+/// Screenshots whose perceptual hash has not been computed yet are not compared.
+#[tauri::command]
+pub async fn find_duplicate_screenshots(
+    state: tauri::State<'_, AppState>,
+    max_distance: Option<u32>,
+    include_hidden: bool,
+) -> Result<Vec<DuplicateGroup>, String> {
+    let screenshots = {
+        let database = state.db()?;
+        operations::get_hashed_screenshots(database.connection(), include_hidden)
+            .map_err(|e| format!("Failed to get screenshots: {}", e))?
+    };
+
+    Ok(screenshot::find_duplicate_groups(
+        screenshots,
+        max_distance.unwrap_or(screenshot::DEFAULT_MAX_DISTANCE),
+    ))
+}
+
+/// Hide screenshots as duplicates of `keep_id` (files are not deleted)
+#[tauri::command]
+pub async fn mark_duplicate_screenshots(
+    state: tauri::State<'_, AppState>,
+    keep_id: i64,
+    duplicate_ids: Vec<i64>,
+) -> Result<(), String> {
+    let mut database = state.db()?;
+    let tx = database
+        .transaction()
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    for screenshot_id in duplicate_ids.into_iter().filter(|&id| id != keep_id) {
+        operations::mark_screenshot_duplicate(&tx, screenshot_id, keep_id)
+            .map_err(|e| format!("Failed to mark duplicate: {}", e))?;
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+/// Hide or show screenshots (showing also clears duplicate marks)
+#[tauri::command]
+pub async fn set_screenshots_hidden(
+    state: tauri::State<'_, AppState>,
+    screenshot_ids: Vec<i64>,
+    hidden: bool,
+) -> Result<(), String> {
+    let mut database = state.db()?;
+    let tx = database
+        .transaction()
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    for screenshot_id in screenshot_ids {
+        operations::set_screenshot_hidden(&tx, screenshot_id, hidden)
+            .map_err(|e| format!("Failed to update screenshot: {}", e))?;
+    }
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+/// Mark or unmark a screenshot as favorite
+#[tauri::command]
+pub async fn set_screenshot_favorite(
+    state: tauri::State<'_, AppState>,
+    screenshot_id: i64,
+    favorite: bool,
+) -> Result<(), String> {
+    let database = state.db()?;
+    operations::set_screenshot_favorite(database.connection(), screenshot_id, favorite)
+        .map_err(|e| format!("Failed to update favorite: {}", e))
+}
+
+/// Set or clear the caption of a screenshot (empty text clears it)
+#[tauri::command]
+pub async fn set_screenshot_caption(
+    state: tauri::State<'_, AppState>,
+    screenshot_id: i64,
+    caption: Option<String>,
+) -> Result<(), String> {
+    let caption = caption.as_deref().map(str::trim).filter(|c| !c.is_empty());
+    let database = state.db()?;
+
+    operations::set_screenshot_caption(database.connection(), screenshot_id, caption)
+        .map_err(|e| format!("Failed to save caption: {}", e))
+}
+
+/// "Best of" selection: favorites and covers of instances matching the filter, oldest first
+#[tauri::command]
+pub async fn get_best_screenshots(
+    state: tauri::State<'_, AppState>,
+    filter: Option<InstanceFilter>,
+    limit: Option<usize>,
+) -> Result<Vec<BestScreenshot>, String> {
+    let database = state.db()?;
+
+    operations::get_best_screenshots(database.connection(), &filter.unwrap_or_default(), limit)
+        .map_err(|e| format!("Failed to get screenshots: {}", e))
+}
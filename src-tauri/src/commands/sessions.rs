@@ -0,0 +1,27 @@
+use crate::app::AppState;
+use crate::db::operations::{self, GameSession};
+
+/// Get game sessions (one per VRChat launch) started in a time range in Unix ms
+#[tauri::command]
+pub async fn get_game_sessions(
+    state: tauri::State<'_, AppState>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Vec<GameSession>, String> {
+    let database = state.db()?;
+
+    operations::get_game_sessions(database.connection(), from, to)
+        .map_err(|e| format!("Failed to get game sessions: {}", e))
+}
+
+/// Get the game session an instance was visited in
+#[tauri::command]
+pub async fn get_instance_game_session(
+    state: tauri::State<'_, AppState>,
+    instance_id: i64,
+) -> Result<Option<GameSession>, String> {
+    let database = state.db()?;
+
+    operations::get_instance_game_session(database.connection(), instance_id)
+        .map_err(|e| format!("Failed to get game session: {}", e))
+}
@@ -0,0 +1,37 @@
+use crate::app::AppState;
+use crate::settings::{self, Settings};
+use tauri::Emitter;
+
+/// Get the current settings
+#[tauri::command]
+pub async fn get_settings(state: tauri::State<'_, AppState>) -> Result<Settings, String> {
+    Ok(state.settings().get())
+}
+
+/// Validate and save settings. Background threads pick them up without a restart
+/// and the frontend is notified with a `settings-changed` event.
+#[tauri::command]
+pub async fn update_settings(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, AppState>,
+    settings: Settings,
+) -> Result<Settings, String> {
+    settings.validate()?;
+
+    {
+        let mut database = state.db()?;
+        let tx = database
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+        settings::save(&tx, &settings)?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+    }
+
+    state.settings().replace(settings.clone());
+    state.logging().apply(&settings.logging)?;
+
+    app.emit("settings-changed", &settings)
+        .map_err(|e| format!("Failed to emit settings-changed event: {}", e))?;
+    Ok(settings)
+}
@@ -0,0 +1,49 @@
+use crate::app::AppState;
+use crate::db::operations::{self, CalendarDay, HeatmapCell};
+use chrono_tz::Tz;
+
+/// Get per-day activity for the calendar view (range in Unix ms)
+#[tauri::command]
+pub async fn get_calendar_summary(
+    state: tauri::State<'_, AppState>,
+    my_account_id: Option<i64>,
+    from: i64,
+    to: i64,
+    timezone: Option<String>,
+) -> Result<Vec<CalendarDay>, String> {
+    let timezone = parse_timezone(timezone.as_deref())?
+        .unwrap_or_else(|| state.settings().get().local_timezone());
+    let database = state.db()?;
+    let conn = database.connection();
+
+    operations::get_calendar_days(conn, &timezone, my_account_id, from, to)
+        .map_err(|e| format!("Failed to aggregate calendar: {}", e))
+}
+
+/// Get hour-of-week activity for the heatmap view (range in Unix ms)
+#[tauri::command]
+pub async fn get_activity_heatmap(
+    state: tauri::State<'_, AppState>,
+    my_account_id: Option<i64>,
+    from: i64,
+    to: i64,
+    timezone: Option<String>,
+) -> Result<Vec<HeatmapCell>, String> {
+    let timezone = parse_timezone(timezone.as_deref())?
+        .unwrap_or_else(|| state.settings().get().local_timezone());
+    let database = state.db()?;
+    let conn = database.connection();
+
+    operations::get_activity_heatmap(conn, &timezone, my_account_id, from, to)
+        .map_err(|e| format!("Failed to aggregate heatmap: {}", e))
+}
+
+/// Parse IANA timezone name (None falls back to the configured or system local timezone)
+fn parse_timezone(timezone: Option<&str>) -> Result<Option<Tz>, String> {
+    timezone
+        .map(|name| {
+            name.parse::<Tz>()
+                .map_err(|_| format!("Unknown timezone: {}", name))
+        })
+        .transpose()
+}
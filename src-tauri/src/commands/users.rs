@@ -0,0 +1,121 @@
+use crate::app::AppState;
+use crate::db::operations::{self, BlockedUser, PurgeSummary};
+use crate::screenshot;
+use chrono::Utc;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+/// Erase everything recorded about a user (usr_xxx) while keeping participant counts.
+/// With `block`, the user is also never recorded again.
+#[tauri::command]
+pub async fn purge_user(
+    state: tauri::State<'_, AppState>,
+    user_id: String,
+    block: bool,
+) -> Result<PurgeSummary, String> {
+    let _paused = state.monitor_gate().hold();
+    let mut database = state.db()?;
+
+    let id = operations::find_user(database.connection(), &user_id)
+        .map_err(|e| format!("Failed to find user: {}", e))?;
+    if let Some(id) = id {
+        let is_local = operations::get_my_account_id(database.connection(), id)
+            .map_err(|e| format!("Failed to check account: {}", e))?
+            .is_some();
+        if is_local {
+            return Err(format!("Cannot purge local account {}", user_id));
+        }
+    }
+
+    let tx = database
+        .transaction()
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+    let summary =
+        purge(&tx, id, &user_id, block).map_err(|e| format!("Failed to purge user: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    // Monitor state may still refer to the deleted rows
+    state.monitor_gate().request_reload();
+
+    tracing::info!(user_id, ?summary, "Purged user");
+    Ok(summary)
+}
+
+/// Get users on the privacy blocklist
+#[tauri::command]
+pub async fn get_blocked_users(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<BlockedUser>, String> {
+    let database = state.db()?;
+    operations::get_blocked_users(database.connection())
+        .map_err(|e| format!("Failed to get blocked users: {}", e))
+}
+
+/// Allow a blocked user to be recorded again (erased data is not restored)
+#[tauri::command]
+pub async fn unblock_user(
+    state: tauri::State<'_, AppState>,
+    user_id: String,
+) -> Result<(), String> {
+    let database = state.db()?;
+    operations::unblock_user(database.connection(), &user_id)
+        .map_err(|e| format!("Failed to unblock user: {}", e))
+}
+
+/// Get the tags given to people, keyed by VRChat user ID
+#[tauri::command]
+pub async fn get_user_tags(
+    state: tauri::State<'_, AppState>,
+) -> Result<HashMap<String, Vec<String>>, String> {
+    let database = state.db()?;
+    operations::get_user_tags(database.connection())
+        .map_err(|e| format!("Failed to get user tags: {}", e))
+}
+
+/// Replace the tags of a person. Tagged people are kept by retention rules.
+#[tauri::command]
+pub async fn set_user_tags(
+    state: tauri::State<'_, AppState>,
+    user_id: String,
+    tags: Vec<String>,
+) -> Result<(), String> {
+    let tags: Vec<String> = tags
+        .iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+
+    let mut database = state.db()?;
+    let id = operations::find_user(database.connection(), &user_id)
+        .map_err(|e| format!("Failed to find user: {}", e))?
+        .ok_or_else(|| format!("Unknown user {}", user_id))?;
+
+    let tx = database
+        .transaction()
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+    operations::set_user_tags(&tx, id, &tags)
+        .map_err(|e| format!("Failed to set user tags: {}", e))?;
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))
+}
+
+fn purge(
+    conn: &Connection,
+    id: Option<i64>,
+    user_id: &str,
+    block: bool,
+) -> Result<PurgeSummary, rusqlite::Error> {
+    let mut summary = match id {
+        Some(id) => operations::purge_user(conn, id)?,
+        None => PurgeSummary::default(),
+    };
+    summary.screenshot_metadata = screenshot::remove_user_from_metadata(conn, user_id)?;
+    summary.instance_owners = operations::remove_instance_owner(conn, user_id)?;
+
+    if block {
+        operations::block_user(conn, user_id, Utc::now().timestamp_millis())?;
+    }
+
+    Ok(summary)
+}
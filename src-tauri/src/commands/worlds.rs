@@ -0,0 +1,53 @@
+use crate::app::AppState;
+use crate::db::operations::{self, CoverPhoto};
+use std::collections::HashMap;
+
+/// Choose the cover screenshot of a world (None falls back to its latest favorite)
+#[tauri::command]
+pub async fn set_world_cover(
+    state: tauri::State<'_, AppState>,
+    world_id: String,
+    screenshot_id: Option<i64>,
+) -> Result<(), String> {
+    let database = state.db()?;
+    let conn = database.connection();
+
+    if let Some(screenshot_id) = screenshot_id {
+        let (instance_id, _, _) = operations::get_screenshot(conn, screenshot_id)
+            .map_err(|e| format!("Failed to get screenshot: {}", e))?
+            .ok_or_else(|| format!("Screenshot not found: {}", screenshot_id))?;
+        let (_, screenshot_world_id, _) = operations::get_instance_location(conn, instance_id)
+            .map_err(|e| format!("Failed to get instance: {}", e))?;
+        if screenshot_world_id != world_id {
+            return Err(format!(
+                "Screenshot {} was not taken in {}",
+                screenshot_id, world_id
+            ));
+        }
+    }
+
+    let found = operations::set_world_cover(conn, &world_id, screenshot_id)
+        .map_err(|e| format!("Failed to save cover: {}", e))?;
+    if !found {
+        return Err(format!("World not found: {}", world_id));
+    }
+    Ok(())
+}
+
+/// Get cover photos of worlds (wrld_xxx)
+#[tauri::command]
+pub async fn get_world_covers(
+    state: tauri::State<'_, AppState>,
+    world_ids: Vec<String>,
+) -> Result<HashMap<String, Option<CoverPhoto>>, String> {
+    let database = state.db()?;
+
+    world_ids
+        .into_iter()
+        .map(|world_id| {
+            operations::get_world_cover(database.connection(), &world_id)
+                .map(|cover| (world_id, cover))
+                .map_err(|e| format!("Failed to get cover: {}", e))
+        })
+        .collect()
+}
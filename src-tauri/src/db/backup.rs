@@ -0,0 +1,339 @@
+use super::migrations;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const BACKUP_PREFIX: &str = "vrcjournal-";
+const BACKUP_EXTENSION: &str = "db";
+const RESTORE_POINT_FILE_NAME: &str = "pre-restore.db";
+
+/// Backup location and rotation
+#[derive(Debug, Clone)]
+pub struct BackupConfig {
+    pub dir: PathBuf,
+    pub keep: usize,        // Number of backups kept by rotation
+    pub interval: Duration, // Scheduled backup interval
+    pub timezone: Tz,       // Zone of the times in backup file names
+}
+
+/// Backup file information
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_path: String,
+    pub created_at: i64, // Unix timestamp in milliseconds
+    pub file_size: u64,
+}
+
+/// Create a consistent backup and rotate old ones
+pub fn create_backup(conn: &Connection, config: &BackupConfig) -> Result<BackupInfo, String> {
+    let path = new_backup_path(config);
+
+    write_snapshot(conn, &path)?;
+    rotate_backups(config)?;
+
+    read_backup_info(&path).ok_or_else(|| format!("Backup not found: {:?}", path))
+}
+
+/// Path of a new backup named after the current time, not used by an earlier one
+fn new_backup_path(config: &BackupConfig) -> PathBuf {
+    let stamp = Utc::now()
+        .with_timezone(&config.timezone)
+        .format("%Y%m%d-%H%M%S-%3f");
+
+    let mut path = config
+        .dir
+        .join(format!("{}{}.{}", BACKUP_PREFIX, stamp, BACKUP_EXTENSION));
+    let mut count = 1;
+    while path.exists() {
+        count += 1;
+        path = config.dir.join(format!(
+            "{}{}-{}.{}",
+            BACKUP_PREFIX, stamp, count, BACKUP_EXTENSION
+        ));
+    }
+    path
+}
+
+/// Snapshot the current database before a restore (outside rotation, overwritten each time)
+pub fn create_restore_point(conn: &Connection, config: &BackupConfig) -> Result<PathBuf, String> {
+    let path = config.dir.join(RESTORE_POINT_FILE_NAME);
+    write_snapshot(conn, &path)?;
+    Ok(path)
+}
+
+/// Time until the next scheduled backup is due
+pub fn time_until_next_backup(config: &BackupConfig) -> Duration {
+    let latest = list_backups(&config.dir)
+        .ok()
+        .and_then(|backups| backups.first().map(|b| b.created_at));
+
+    let Some(latest) = latest else {
+        return Duration::ZERO;
+    };
+
+    let elapsed = (Utc::now().timestamp_millis() - latest).max(0) as u64;
+    config
+        .interval
+        .saturating_sub(Duration::from_millis(elapsed))
+}
+
+/// Replace the database contents with a verified backup.
+/// A restore point of the current contents is written first so the restore can be undone.
+pub fn restore_backup(
+    conn: &mut Connection,
+    path: &Path,
+    config: &BackupConfig,
+) -> Result<(), String> {
+    verify_backup(path)?;
+    create_restore_point(conn, config)?;
+
+    conn.restore(DatabaseName::Main, path, None::<fn(Progress)>)
+        .map_err(|e| format!("Failed to restore backup: {}", e))?;
+
+    // Backups taken by older versions may need newer migrations
+    migrations::run_migrations(conn)
+        .map_err(|e| format!("Failed to migrate restored database: {}", e))
+}
+
+/// Write a verified snapshot to `path`, replacing it atomically
+fn write_snapshot(conn: &Connection, path: &Path) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    }
+
+    let tmp_path = path.with_extension("tmp");
+    let _ = fs::remove_file(&tmp_path);
+
+    // VACUUM INTO writes a consistent snapshot while other connections keep working
+    conn.execute("VACUUM INTO ?1", (tmp_path.to_string_lossy(),))
+        .map_err(|e| format!("Failed to write backup: {}", e))?;
+
+    if let Err(e) = verify_backup(&tmp_path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    fs::rename(&tmp_path, path).map_err(|e| format!("Failed to finalize backup: {}", e))
+}
+
+/// Check backup integrity and that this version can migrate it
+pub fn verify_backup(path: &Path) -> Result<(), String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open backup {:?}: {}", path, e))?;
+
+    let result: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to check backup integrity: {}", e))?;
+
+    if result != "ok" {
+        return Err(format!("Backup {:?} is corrupted: {}", path, result));
+    }
+
+    // Other SQLite files have no migrations table
+    let version = migrations::current_version(&conn).unwrap_or(0);
+    if version == 0 {
+        return Err(format!("{:?} is not a VRCJournal backup", path));
+    }
+    if version > migrations::LATEST_VERSION {
+        return Err(format!(
+            "Backup made by a newer version of VRCJournal (schema version {}, supported up to {})",
+            version,
+            migrations::LATEST_VERSION
+        ));
+    }
+
+    Ok(())
+}
+
+/// List backups, newest first
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupInfo>, String> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read backup directory: {}", e))?;
+
+    let mut backups: Vec<BackupInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| is_backup_file(path))
+        .filter_map(|path| read_backup_info(&path))
+        .collect();
+
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_at));
+    Ok(backups)
+}
+
+/// Delete the oldest backups beyond `keep`
+fn rotate_backups(config: &BackupConfig) -> Result<(), String> {
+    for backup in list_backups(&config.dir)?.iter().skip(config.keep.max(1)) {
+        if let Err(e) = fs::remove_file(&backup.file_path) {
+            tracing::warn!(path = %backup.file_path, error = %e, "Failed to delete old backup");
+        }
+    }
+    Ok(())
+}
+
+fn is_backup_file(path: &Path) -> bool {
+    path.is_file()
+        && path.extension().and_then(|e| e.to_str()) == Some(BACKUP_EXTENSION)
+        && path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| n.starts_with(BACKUP_PREFIX))
+            .unwrap_or(false)
+}
+
+fn read_backup_info(path: &Path) -> Option<BackupInfo> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = DateTime::<Utc>::from(metadata.modified().ok()?);
+
+    Some(BackupInfo {
+        file_path: path.to_string_lossy().to_string(),
+        created_at: modified.timestamp_millis(),
+        file_size: metadata.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+    use std::time::SystemTime;
+
+    fn config(dir: &Path, keep: usize) -> BackupConfig {
+        BackupConfig {
+            dir: dir.join("backups"),
+            keep,
+            interval: Duration::from_secs(60 * 60),
+            timezone: Tz::UTC,
+        }
+    }
+
+    fn world_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM worlds", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path(), 3);
+        let mut database = Database::open(dir.path().join("journal.db")).unwrap();
+        database.migrate().unwrap();
+        crate::db::operations::upsert_world(database.connection(), "wrld_a", 0).unwrap();
+
+        let info = database.create_backup(&config).unwrap();
+        assert!(Path::new(&info.file_path).starts_with(&config.dir));
+        verify_backup(Path::new(&info.file_path)).unwrap();
+        assert_eq!(list_backups(&config.dir).unwrap().len(), 1);
+        assert!(time_until_next_backup(&config) > Duration::ZERO);
+
+        database
+            .connection()
+            .execute("DELETE FROM worlds", [])
+            .unwrap();
+        database
+            .restore_backup(Path::new(&info.file_path), &config)
+            .unwrap();
+        assert_eq!(world_count(database.connection()), 1);
+
+        // The emptied database was kept as a restore point outside rotation
+        let restore_point = config.dir.join(RESTORE_POINT_FILE_NAME);
+        let conn = Connection::open(restore_point).unwrap();
+        assert_eq!(world_count(&conn), 0);
+        assert_eq!(list_backups(&config.dir).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_restore_rejects_corrupted_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path(), 3);
+        let mut database = Database::open(dir.path().join("journal.db")).unwrap();
+        database.migrate().unwrap();
+
+        let broken = dir.path().join("broken.db");
+        fs::write(&broken, b"not a database").unwrap();
+
+        assert!(database.restore_backup(&broken, &config).is_err());
+        // Nothing was touched, not even the restore point
+        assert!(!config.dir.join(RESTORE_POINT_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_restore_rejects_unknown_schemas() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path(), 3);
+        let mut database = Database::open(dir.path().join("journal.db")).unwrap();
+        database.migrate().unwrap();
+
+        let other = dir.path().join("other.db");
+        Connection::open(&other)
+            .unwrap()
+            .execute_batch("CREATE TABLE notes (text TEXT)")
+            .unwrap();
+        assert!(database.restore_backup(&other, &config).is_err());
+
+        let newer = PathBuf::from(database.create_backup(&config).unwrap().file_path);
+        Connection::open(&newer)
+            .unwrap()
+            .execute(
+                "INSERT INTO schema_migrations (version) VALUES (?1)",
+                (migrations::LATEST_VERSION + 1,),
+            )
+            .unwrap();
+        assert!(database.restore_backup(&newer, &config).is_err());
+        assert!(!config.dir.join(RESTORE_POINT_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_backups_in_the_same_second_are_kept_apart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path(), 3);
+        let database = Database::open(dir.path().join("journal.db")).unwrap();
+        database.migrate().unwrap();
+
+        let first = database.create_backup(&config).unwrap();
+        let second = database.create_backup(&config).unwrap();
+        assert_ne!(first.file_path, second.file_path);
+        assert_eq!(list_backups(&config.dir).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rotation_keeps_newest_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config(dir.path(), 2);
+        fs::create_dir_all(&config.dir).unwrap();
+
+        let now = SystemTime::now();
+        for (i, name) in ["a", "b", "c", "d"].iter().enumerate() {
+            let path = config
+                .dir
+                .join(format!("{}{}.{}", BACKUP_PREFIX, name, BACKUP_EXTENSION));
+            let file = fs::File::create(&path).unwrap();
+            file.set_modified(now - Duration::from_secs(60 * (4 - i as u64)))
+                .unwrap();
+        }
+        // Files of other apps are never rotated away
+        fs::write(config.dir.join("notes.txt"), b"keep").unwrap();
+
+        rotate_backups(&config).unwrap();
+
+        let kept: Vec<String> = list_backups(&config.dir)
+            .unwrap()
+            .into_iter()
+            .map(|backup| backup.file_path)
+            .collect();
+        assert_eq!(kept.len(), 2);
+        assert!(kept[0].ends_with("vrcjournal-d.db"));
+        assert!(kept[1].ends_with("vrcjournal-c.db"));
+        assert!(config.dir.join("notes.txt").exists());
+    }
+}
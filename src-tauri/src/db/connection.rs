@@ -1,5 +1,8 @@
+use super::backup::{self, BackupConfig, BackupInfo};
+use super::operations::{self, PruneSummary, RetentionPolicy};
 use rusqlite::{Connection, Result, Transaction};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 pub struct Database {
     conn: Connection,
@@ -12,9 +15,25 @@ impl Database {
 
         conn.execute_batch("PRAGMA foreign_keys = ON;")?;
 
+        // The monitor thread and commands hold separate connections;
+        // WAL lets long reads such as exports run while the monitor writes
+        conn.pragma_update_and_check(None, "journal_mode", "WAL", |_| Ok(()))?;
+        conn.busy_timeout(Duration::from_secs(5))?;
+
         Ok(Database { conn })
     }
 
+    /// Open a migrated in-memory database for tests
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+
+        let database = Database { conn };
+        database.migrate()?;
+        Ok(database)
+    }
+
     /// Get database connection reference
     pub fn connection(&self) -> &Connection {
         &self.conn
@@ -25,8 +44,84 @@ impl Database {
         self.conn.transaction()
     }
 
+    /// Close the connection, reporting errors that dropping it would hide
+    pub fn close(self) -> Result<()> {
+        self.conn.close().map_err(|(_, e)| e)
+    }
+
     /// Run database migrations
     pub fn migrate(&self) -> Result<()> {
         super::migrations::run_migrations(&self.conn)
     }
+
+    /// Latest applied schema version
+    pub fn schema_version(&self) -> Result<i32> {
+        super::migrations::current_version(&self.conn)
+    }
+
+    /// Size of the database file in bytes (excluding the WAL)
+    pub fn size(&self) -> Result<u64> {
+        self.conn.query_row(
+            "SELECT page_count * page_size FROM pragma_page_count(), pragma_page_size()",
+            (),
+            |row| row.get(0),
+        )
+    }
+
+    /// Create a verified backup in the configured folder
+    pub fn create_backup(&self, config: &BackupConfig) -> std::result::Result<BackupInfo, String> {
+        backup::create_backup(&self.conn, config)
+    }
+
+    /// Restore the live database from a backup file, keeping a restore point
+    pub fn restore_backup(
+        &mut self,
+        path: &Path,
+        config: &BackupConfig,
+    ) -> std::result::Result<(), String> {
+        backup::restore_backup(&mut self.conn, path, config)
+    }
+
+    /// Apply retention rules in one transaction, then reclaim the freed space
+    pub fn apply_retention(&mut self, policy: &RetentionPolicy, now: i64) -> Result<PruneSummary> {
+        let tx = self.conn.transaction()?;
+        let summary = operations::prune_remote_player_data(&tx, policy, now)?;
+        operations::set_last_pruned_at(&tx, now)?;
+        tx.commit()?;
+
+        // VACUUM cannot run inside a transaction
+        if !summary.is_empty() {
+            self.conn.execute_batch("VACUUM")?;
+        }
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_writes_proceed_during_read_transaction() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.db");
+        let mut reader = Database::open(path.clone()).unwrap();
+        reader.migrate().unwrap();
+        let writer = Database::open(path).unwrap();
+
+        let count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM worlds", (), |row| row.get(0))
+                .unwrap()
+        };
+
+        // An export holds its snapshot while the monitor keeps recording
+        let snapshot = reader.transaction().unwrap();
+        assert_eq!(count(&snapshot), 0);
+        operations::upsert_world(writer.connection(), "wrld_a", 0).unwrap();
+        assert_eq!(count(&snapshot), 0);
+        snapshot.commit().unwrap();
+
+        assert_eq!(count(reader.connection()), 1);
+    }
 }
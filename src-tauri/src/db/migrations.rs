@@ -1,6 +1,21 @@
 use rusqlite::{Connection, Result};
 
 const INITIAL_SCHEMA: &str = include_str!("../../migrations/001_initial_schema.sql");
+const INSTANCE_NOTES: &str = include_str!("../../migrations/002_instance_notes.sql");
+const SCREENSHOT_METADATA: &str = include_str!("../../migrations/003_screenshot_metadata.sql");
+const SCREENSHOT_SOURCES: &str = include_str!("../../migrations/004_screenshot_sources.sql");
+const SCREENSHOT_FILES: &str = include_str!("../../migrations/005_screenshot_files.sql");
+const SCREENSHOT_DUPLICATES: &str = include_str!("../../migrations/006_screenshot_duplicates.sql");
+const SCREENSHOT_ANNOTATIONS: &str =
+    include_str!("../../migrations/007_screenshot_annotations.sql");
+const PRIVACY_BLOCKLIST: &str = include_str!("../../migrations/008_privacy_blocklist.sql");
+const RETENTION: &str = include_str!("../../migrations/009_retention.sql");
+const SETTINGS: &str = include_str!("../../migrations/010_settings.sql");
+const GAME_SESSIONS: &str = include_str!("../../migrations/011_game_sessions.sql");
+const LOG_FILE_TIMEZONES: &str = include_str!("../../migrations/012_log_file_timezones.sql");
+
+/// Schema version after all migrations have run
+pub const LATEST_VERSION: i32 = 12;
 
 /// Run database migrations
 pub fn run_migrations(conn: &Connection) -> Result<()> {
@@ -20,14 +35,86 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
         .unwrap_or(0);
 
     if current_version < 1 {
-        println!("Running migration 001: Initial schema");
+        tracing::info!("Running migration 001: Initial schema");
         conn.execute_batch(INITIAL_SCHEMA)?;
         conn.execute("INSERT INTO schema_migrations (version) VALUES (1)", [])?;
     }
 
-    println!(
-        "Database migrations complete. Current version: {}",
-        current_version.max(1)
-    );
+    if current_version < 2 {
+        tracing::info!("Running migration 002: Instance notes");
+        conn.execute_batch(INSTANCE_NOTES)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (2)", [])?;
+    }
+
+    if current_version < 3 {
+        tracing::info!("Running migration 003: Screenshot metadata");
+        conn.execute_batch(SCREENSHOT_METADATA)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (3)", [])?;
+    }
+
+    if current_version < 4 {
+        tracing::info!("Running migration 004: Screenshot sources");
+        conn.execute_batch(SCREENSHOT_SOURCES)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (4)", [])?;
+    }
+
+    if current_version < 5 {
+        tracing::info!("Running migration 005: Screenshot files");
+        conn.execute_batch(SCREENSHOT_FILES)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (5)", [])?;
+    }
+
+    if current_version < 6 {
+        tracing::info!("Running migration 006: Screenshot duplicates");
+        conn.execute_batch(SCREENSHOT_DUPLICATES)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (6)", [])?;
+    }
+
+    if current_version < 7 {
+        tracing::info!("Running migration 007: Screenshot annotations");
+        conn.execute_batch(SCREENSHOT_ANNOTATIONS)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (7)", [])?;
+    }
+
+    if current_version < 8 {
+        tracing::info!("Running migration 008: Privacy blocklist");
+        conn.execute_batch(PRIVACY_BLOCKLIST)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (8)", [])?;
+    }
+
+    if current_version < 9 {
+        tracing::info!("Running migration 009: Retention rules and user tags");
+        conn.execute_batch(RETENTION)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (9)", [])?;
+    }
+
+    if current_version < 10 {
+        tracing::info!("Running migration 010: Settings");
+        conn.execute_batch(SETTINGS)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (10)", [])?;
+    }
+
+    if current_version < 11 {
+        tracing::info!("Running migration 011: Game sessions");
+        conn.execute_batch(GAME_SESSIONS)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (11)", [])?;
+    }
+
+    if current_version < 12 {
+        tracing::info!("Running migration 012: Log file time zones");
+        conn.execute_batch(LOG_FILE_TIMEZONES)?;
+        conn.execute("INSERT INTO schema_migrations (version) VALUES (12)", [])?;
+    }
+
+    tracing::info!(version = LATEST_VERSION, "Database migrations complete");
     Ok(())
 }
+
+/// Get the latest applied schema version
+pub fn current_version(conn: &Connection) -> Result<i32> {
+    conn.query_row(
+        "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+        [],
+        |row| row.get(0),
+    )
+}
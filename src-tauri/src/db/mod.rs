@@ -1,5 +1,7 @@
+pub mod backup;
 pub mod connection;
 pub mod migrations;
 pub mod operations;
 
+pub use backup::{BackupConfig, BackupInfo};
 pub use connection::Database;
@@ -41,6 +41,59 @@ pub fn upsert_avatar(
     }
 }
 
+/// Merge avatar from another database (widens the seen period, fills a missing avatar_id).
+/// Avatars are matched by avatar_id when known; a name only matches an avatar
+/// whose ID is unknown on one side, so different avatars sharing a name stay apart.
+pub fn merge_avatar(
+    conn: &Connection,
+    avatar_name: &str,
+    avatar_id: Option<&str>,
+    seen_at: i64,
+) -> Result<i64> {
+    let by_id: Option<i64> = match avatar_id {
+        Some(avatar_id) => conn
+            .query_row(
+                "SELECT id FROM avatars WHERE avatar_id = ?1",
+                (avatar_id,),
+                |row| row.get(0),
+            )
+            .optional()?,
+        None => None,
+    };
+    let existing = match by_id {
+        Some(id) => Some(id),
+        None => conn
+            .query_row(
+                "SELECT id FROM avatars
+                 WHERE avatar_name = ?1 AND (?2 IS NULL OR avatar_id IS NULL)
+                 ORDER BY avatar_id IS NOT NULL, last_seen_at DESC
+                 LIMIT 1",
+                (avatar_name, avatar_id),
+                |row| row.get(0),
+            )
+            .optional()?,
+    };
+
+    if let Some(id) = existing {
+        conn.execute(
+            "UPDATE avatars
+             SET first_seen_at = MIN(first_seen_at, ?1),
+                 last_seen_at = MAX(last_seen_at, ?1),
+                 avatar_id = COALESCE(avatar_id, ?2)
+             WHERE id = ?3",
+            (seen_at, avatar_id, id),
+        )?;
+        Ok(id)
+    } else {
+        conn.execute(
+            "INSERT INTO avatars (avatar_id, avatar_name, first_seen_at, last_seen_at)
+             VALUES (?1, ?2, ?3, ?3)",
+            (avatar_id, avatar_name, seen_at),
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+}
+
 /// Get avatar name by ID
 pub fn get_avatar_name(conn: &Connection, avatar_id: i64) -> Result<String> {
     conn.query_row(
@@ -65,3 +118,45 @@ pub fn record_avatar_history(
     )?;
     Ok(())
 }
+
+/// Check whether an avatar change is already recorded
+pub fn has_avatar_history(
+    conn: &Connection,
+    instance_id: i64,
+    user_id: i64,
+    avatar_id: i64,
+    changed_at: i64,
+) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(
+             SELECT 1 FROM avatar_history
+             WHERE instance_id = ?1 AND user_id = ?2 AND avatar_id = ?3 AND changed_at = ?4
+         )",
+        (instance_id, user_id, avatar_id, changed_at),
+        |row| row.get(0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::Database;
+
+    #[test]
+    fn test_merge_avatar_keeps_same_named_avatars_apart() {
+        let database = Database::open_in_memory().unwrap();
+        let conn = database.connection();
+
+        let from_log = upsert_avatar(conn, "Cool Avatar", None, 10).unwrap();
+        // A known ID claims the avatar seen only by name
+        let first = merge_avatar(conn, "Cool Avatar", Some("avtr_1"), 20).unwrap();
+        assert_eq!(first, from_log);
+        // Another ID with the same name is a different avatar
+        let second = merge_avatar(conn, "Cool Avatar", Some("avtr_2"), 30).unwrap();
+        assert_ne!(second, first);
+        assert_eq!(
+            merge_avatar(conn, "Cool Avatar", Some("avtr_2"), 40).unwrap(),
+            second
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use crate::types::InstanceStatus;
+use rusqlite::ToSql;
+
+/// WHERE clause matching `InstanceFilter` (expects the instances table aliased as `i`)
+pub const INSTANCE_FILTER_SQL: &str = "(:account IS NULL OR i.my_account_id = :account)
+    AND (:from IS NULL OR i.started_at >= :from)
+    AND (:to IS NULL OR i.started_at < :to)
+    AND (:world_id IS NULL OR i.world_id IN (
+        SELECT id FROM worlds WHERE world_id = :world_id))
+    AND (:world_name IS NULL OR i.world_id IN (
+        SELECT world_id FROM world_name_history WHERE world_name LIKE '%' || :world_name || '%'))
+    AND (:user_id IS NULL OR i.id IN (
+        SELECT iu.instance_id FROM instance_users iu
+        JOIN users u ON iu.user_id = u.id
+        WHERE u.user_id = :user_id))
+    AND (:status IS NULL OR i.status = :status)";
+
+/// Instance filter used by the exporters and the best screenshot view
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InstanceFilter {
+    pub my_account_id: Option<i64>,
+    pub from: Option<i64>,          // Unix timestamp in milliseconds (inclusive)
+    pub to: Option<i64>,            // Unix timestamp in milliseconds (exclusive)
+    pub world_id: Option<String>,   // wrld_xxx
+    pub world_name: Option<String>, // Partial match against any known name of the world
+    pub user_id: Option<String>,    // usr_xxx of a participant
+    pub status: Option<InstanceStatus>,
+}
+
+impl InstanceFilter {
+    /// Named parameters for `INSTANCE_FILTER_SQL`
+    pub fn params(&self) -> [(&'static str, &dyn ToSql); 7] {
+        [
+            (":account", &self.my_account_id as &dyn ToSql),
+            (":from", &self.from as &dyn ToSql),
+            (":to", &self.to as &dyn ToSql),
+            (":world_id", &self.world_id as &dyn ToSql),
+            (":world_name", &self.world_name as &dyn ToSql),
+            (":user_id", &self.user_id as &dyn ToSql),
+            (":status", &self.status as &dyn ToSql),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{sample_database, FRIEND_USER_ID};
+    use rusqlite::Connection;
+
+    fn count(conn: &Connection, filter: &InstanceFilter) -> i64 {
+        let sql = format!(
+            "SELECT COUNT(*) FROM instances i WHERE {}",
+            INSTANCE_FILTER_SQL
+        );
+        conn.query_row(&sql, &filter.params()[..], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_range_includes_from_and_excludes_to() {
+        let database = sample_database();
+        let conn = database.connection();
+        let started_at: i64 = conn
+            .query_row("SELECT started_at FROM instances", (), |row| row.get(0))
+            .unwrap();
+
+        let from = InstanceFilter {
+            from: Some(started_at),
+            ..Default::default()
+        };
+        assert_eq!(count(conn, &from), 1);
+
+        let to = InstanceFilter {
+            to: Some(started_at),
+            ..Default::default()
+        };
+        assert_eq!(count(conn, &to), 0);
+    }
+
+    #[test]
+    fn test_filters_without_matches() {
+        let database = sample_database();
+        let conn = database.connection();
+        assert_eq!(count(conn, &InstanceFilter::default()), 1);
+
+        let cases = [
+            InstanceFilter {
+                my_account_id: Some(99),
+                ..Default::default()
+            },
+            InstanceFilter {
+                world_id: Some("wrld_unknown".to_string()),
+                ..Default::default()
+            },
+            InstanceFilter {
+                world_name: Some("Other".to_string()),
+                ..Default::default()
+            },
+            InstanceFilter {
+                user_id: Some("usr_nobody".to_string()),
+                ..Default::default()
+            },
+            InstanceFilter {
+                user_id: Some(FRIEND_USER_ID.to_string()),
+                status: Some(InstanceStatus::Crashed),
+                ..Default::default()
+            },
+        ];
+        for filter in &cases {
+            assert_eq!(count(conn, filter), 0, "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn test_world_name_matches_part_of_any_name() {
+        let database = sample_database();
+        let filter = InstanceFilter {
+            world_name: Some("st Wor".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(count(database.connection(), &filter), 1);
+    }
+}
@@ -0,0 +1,169 @@
+use rusqlite::{named_params, Connection, OptionalExtension, Result, Row};
+
+/// One VRChat launch
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameSession {
+    pub id: i64,
+    pub log_file: String,
+    pub version: Option<String>,
+    pub vr_mode: Option<bool>, // None until the log says which mode VRChat started in
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub quit_cleanly: bool,
+    pub instance_count: i64,
+}
+
+const GAME_SESSION_COLUMNS: &str = "
+    s.id, s.log_file, s.version, s.vr_mode, s.started_at, s.ended_at, s.quit_cleanly,
+    (SELECT COUNT(*) FROM instances WHERE game_session_id = s.id)";
+
+fn game_session_from_row(row: &Row) -> Result<GameSession> {
+    Ok(GameSession {
+        id: row.get(0)?,
+        log_file: row.get(1)?,
+        version: row.get(2)?,
+        vr_mode: row.get(3)?,
+        started_at: row.get(4)?,
+        ended_at: row.get(5)?,
+        quit_cleanly: row.get(6)?,
+        instance_count: row.get(7)?,
+    })
+}
+
+/// Start the session of a log file (returns the existing one when the file was read before)
+pub fn start_game_session(conn: &Connection, log_file: &str, started_at: i64) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO game_sessions (log_file, started_at) VALUES (?1, ?2)
+         ON CONFLICT(log_file) DO NOTHING",
+        (log_file, started_at),
+    )?;
+
+    conn.query_row(
+        "SELECT id FROM game_sessions WHERE log_file = ?1",
+        (log_file,),
+        |row| row.get(0),
+    )
+}
+
+pub fn set_game_session_version(conn: &Connection, session_id: i64, version: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE game_sessions SET version = ?1 WHERE id = ?2",
+        (version, session_id),
+    )?;
+    Ok(())
+}
+
+pub fn set_game_session_vr_mode(conn: &Connection, session_id: i64, vr_mode: bool) -> Result<()> {
+    conn.execute(
+        "UPDATE game_sessions SET vr_mode = ?1 WHERE id = ?2",
+        (vr_mode, session_id),
+    )?;
+    Ok(())
+}
+
+/// End a session (keeps an earlier end time)
+pub fn end_game_session(
+    conn: &Connection,
+    session_id: i64,
+    ended_at: i64,
+    quit_cleanly: bool,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE game_sessions
+         SET ended_at = COALESCE(ended_at, ?1),
+             quit_cleanly = quit_cleanly OR ?2
+         WHERE id = ?3",
+        (ended_at, quit_cleanly, session_id),
+    )?;
+    Ok(())
+}
+
+/// Undo the end of a log file's session recorded at `ended_at` without a clean quit.
+/// Returns the session when it was reopened.
+pub fn reopen_game_session(
+    conn: &Connection,
+    log_file: &str,
+    ended_at: i64,
+) -> Result<Option<i64>> {
+    let session_id = conn
+        .query_row(
+            "SELECT id FROM game_sessions
+             WHERE log_file = ?1 AND ended_at = ?2 AND NOT quit_cleanly",
+            (log_file, ended_at),
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if let Some(session_id) = session_id {
+        conn.execute(
+            "UPDATE game_sessions SET ended_at = NULL WHERE id = ?1",
+            (session_id,),
+        )?;
+    }
+    Ok(session_id)
+}
+
+/// Latest session that has not ended
+pub fn get_latest_running_game_session(conn: &Connection) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM game_sessions
+         WHERE ended_at IS NULL
+         ORDER BY started_at DESC
+         LIMIT 1",
+        (),
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Link an instance to the session it was played in
+pub fn set_instance_game_session(
+    conn: &Connection,
+    instance_id: i64,
+    session_id: i64,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE instances SET game_session_id = ?1 WHERE id = ?2",
+        (session_id, instance_id),
+    )?;
+    Ok(())
+}
+
+/// Sessions started in a period, newest first (`from` inclusive, `to` exclusive)
+pub fn get_game_sessions(
+    conn: &Connection,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Vec<GameSession>> {
+    let sql = format!(
+        "SELECT {GAME_SESSION_COLUMNS} FROM game_sessions s
+         WHERE (:from IS NULL OR s.started_at >= :from)
+           AND (:to IS NULL OR s.started_at < :to)
+         ORDER BY s.started_at DESC"
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let sessions = stmt
+        .query_map(
+            named_params! { ":from": from, ":to": to },
+            game_session_from_row,
+        )?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(sessions)
+}
+
+/// Session an instance was played in (None for instances recorded before sessions existed)
+pub fn get_instance_game_session(
+    conn: &Connection,
+    instance_id: i64,
+) -> Result<Option<GameSession>> {
+    let sql = format!(
+        "SELECT {GAME_SESSION_COLUMNS} FROM game_sessions s
+         JOIN instances i ON i.game_session_id = s.id
+         WHERE i.id = ?1"
+    );
+
+    conn.query_row(&sql, (instance_id,), game_session_from_row)
+        .optional()
+}
@@ -1,5 +1,7 @@
+use super::EFFECTIVE_ENDED_AT_SQL;
 use crate::types::InstanceStatus;
-use rusqlite::{Connection, OptionalExtension, Result};
+use chrono::Utc;
+use rusqlite::{named_params, Connection, OptionalExtension, Result};
 
 /// Create new instance (world_name_at_join_id will be set later via update_instance_world_name_history)
 pub fn create_instance(
@@ -42,6 +44,47 @@ pub fn update_instance_status(
     Ok(())
 }
 
+/// Get the user's note for an instance
+pub fn get_instance_note(conn: &Connection, instance_id: i64) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT note FROM instances WHERE id = ?1",
+        (instance_id,),
+        |row| row.get(0),
+    )
+}
+
+/// Set or clear the user's note for an instance
+pub fn set_instance_note(conn: &Connection, instance_id: i64, note: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE instances SET note = ?1 WHERE id = ?2",
+        (note, instance_id),
+    )?;
+    Ok(())
+}
+
+/// Raise the number of participants erased by a privacy purge (returns whether it changed)
+pub fn merge_purged_participants(conn: &Connection, instance_id: i64, count: i64) -> Result<bool> {
+    let updated = conn.execute(
+        "UPDATE instances SET purged_participants = ?1
+         WHERE id = ?2 AND purged_participants < ?1",
+        (count, instance_id),
+    )?;
+    Ok(updated > 0)
+}
+
+/// Choose the cover screenshot of an instance (None falls back to its first favorite)
+pub fn set_instance_cover(
+    conn: &Connection,
+    instance_id: i64,
+    screenshot_id: Option<i64>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE instances SET cover_screenshot_id = ?1 WHERE id = ?2",
+        (screenshot_id, instance_id),
+    )?;
+    Ok(())
+}
+
 /// Get latest active instance
 pub fn get_latest_active_instance(conn: &Connection, my_account_id: i64) -> Result<Option<i64>> {
     conn.query_row(
@@ -55,6 +98,111 @@ pub fn get_latest_active_instance(conn: &Connection, my_account_id: i64) -> Resu
     .optional()
 }
 
+/// Find the instance of an account that was open at `timestamp` in the given world.
+/// `instance_id` narrows the match when known.
+pub fn find_instance_at(
+    conn: &Connection,
+    my_account_id: i64,
+    world_id: &str,
+    instance_id: Option<&str>,
+    timestamp: i64,
+) -> Result<Option<i64>> {
+    let sql = format!(
+        "SELECT i.id FROM instances i
+         JOIN worlds w ON i.world_id = w.id
+         WHERE i.my_account_id = :account
+           AND w.world_id = :world_id
+           AND (:instance_id IS NULL OR i.instance_id = :instance_id)
+           AND i.started_at <= :timestamp
+           AND {} >= :timestamp
+         ORDER BY i.started_at DESC
+         LIMIT 1",
+        EFFECTIVE_ENDED_AT_SQL
+    );
+
+    conn.query_row(
+        &sql,
+        named_params! {
+            ":account": my_account_id,
+            ":world_id": world_id,
+            ":instance_id": instance_id,
+            ":timestamp": timestamp,
+            ":now": Utc::now().timestamp_millis(),
+        },
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Instances without an end time only cover capture times this long after their start,
+/// so an instance left open by a crash does not claim every later screenshot
+const MAX_OPEN_INSTANCE_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Find the instance of an account (None: of any account) that was open at `timestamp`.
+/// The most recently started one wins when instances overlap.
+pub fn find_instance_at_time(
+    conn: &Connection,
+    my_account_id: Option<i64>,
+    timestamp: i64,
+) -> Result<Option<i64>> {
+    let sql = format!(
+        "SELECT i.id FROM instances i
+         WHERE (:account IS NULL OR i.my_account_id = :account)
+           AND i.started_at <= :timestamp
+           AND (i.ended_at >= :timestamp
+                OR (i.ended_at IS NULL
+                    AND i.started_at >= :timestamp - :max_open
+                    AND {} >= :timestamp))
+         ORDER BY i.started_at DESC
+         LIMIT 1",
+        EFFECTIVE_ENDED_AT_SQL
+    );
+
+    conn.query_row(
+        &sql,
+        named_params! {
+            ":account": my_account_id,
+            ":timestamp": timestamp,
+            ":max_open": MAX_OPEN_INSTANCE_MS,
+            ":now": Utc::now().timestamp_millis(),
+        },
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Get the account, VRChat world ID and VRChat instance ID of an instance
+pub fn get_instance_location(
+    conn: &Connection,
+    instance_id: i64,
+) -> Result<(i64, String, String)> {
+    conn.query_row(
+        "SELECT i.my_account_id, w.world_id, i.instance_id
+         FROM instances i
+         JOIN worlds w ON i.world_id = w.id
+         WHERE i.id = ?1",
+        (instance_id,),
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+}
+
+/// Find instance by its natural key (returns id, ended_at, status)
+pub fn find_instance(
+    conn: &Connection,
+    my_account_id: i64,
+    world_id: i64,
+    instance_id: &str,
+    started_at: i64,
+) -> Result<Option<(i64, Option<i64>, String)>> {
+    conn.query_row(
+        "SELECT id, ended_at, status FROM instances
+         WHERE my_account_id = ?1 AND world_id = ?2 AND instance_id = ?3 AND started_at = ?4",
+        (my_account_id, world_id, instance_id, started_at),
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+    .optional()
+}
+
 /// Add user to instance
 pub fn add_user_to_instance(
     conn: &Connection,
@@ -71,6 +219,22 @@ pub fn add_user_to_instance(
     Ok(conn.last_insert_rowid())
 }
 
+/// Find instance participation by user and join time (returns id, left_at)
+pub fn find_instance_user(
+    conn: &Connection,
+    instance_id: i64,
+    user_id: i64,
+    joined_at: i64,
+) -> Result<Option<(i64, Option<i64>)>> {
+    conn.query_row(
+        "SELECT id, left_at FROM instance_users
+         WHERE instance_id = ?1 AND user_id = ?2 AND joined_at = ?3",
+        (instance_id, user_id, joined_at),
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .optional()
+}
+
 /// Mark user as left
 pub fn set_user_left_instance(
     conn: &Connection,
@@ -99,6 +263,38 @@ pub fn set_all_users_left_instance(
     Ok(())
 }
 
+/// Reopen the instance of a session that was closed as crashed at `ended_at`,
+/// along with the players marked as left then. Returns the instance when one was reopened.
+pub fn reopen_crashed_instance(
+    conn: &Connection,
+    game_session_id: i64,
+    ended_at: i64,
+) -> Result<Option<i64>> {
+    let instance_id = conn
+        .query_row(
+            "SELECT id FROM instances
+             WHERE game_session_id = ?1 AND ended_at = ?2 AND status = ?3
+             ORDER BY started_at DESC
+             LIMIT 1",
+            (game_session_id, ended_at, InstanceStatus::Crashed.as_str()),
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if let Some(instance_id) = instance_id {
+        conn.execute(
+            "UPDATE instances SET ended_at = NULL, status = ?1 WHERE id = ?2",
+            (InstanceStatus::Active.as_str(), instance_id),
+        )?;
+        // Players who really left in the last logged second can't be told apart and return too
+        conn.execute(
+            "UPDATE instance_users SET left_at = NULL WHERE instance_id = ?1 AND left_at = ?2",
+            (instance_id, ended_at),
+        )?;
+    }
+    Ok(instance_id)
+}
+
 /// Get instance world ID
 pub fn get_instance_world_id(conn: &Connection, instance_id: i64) -> Result<i64> {
     conn.query_row(
@@ -148,3 +344,64 @@ pub fn get_instance_active_users(
 
     Ok(users)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{database_from_log, sample_database, SAMPLE_LOG};
+    use chrono::TimeZone;
+
+    fn utc_ms(d: u32, h: u32, mi: u32) -> i64 {
+        Utc.with_ymd_and_hms(2025, 10, d, h, mi, 0)
+            .unwrap()
+            .timestamp_millis()
+    }
+
+    fn my_account_id(conn: &Connection) -> i64 {
+        conn.query_row("SELECT id FROM my_accounts", (), |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_instance_at_time_matches_only_the_account() {
+        let database = sample_database();
+        let conn = database.connection();
+        let account = my_account_id(conn);
+
+        assert!(
+            find_instance_at_time(conn, Some(account), utc_ms(13, 10, 0))
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            find_instance_at_time(conn, Some(account + 1), utc_ms(13, 10, 0)).unwrap(),
+            None
+        );
+        assert!(find_instance_at_time(conn, None, utc_ms(13, 10, 0))
+            .unwrap()
+            .is_some());
+        // After the instance was left
+        assert_eq!(
+            find_instance_at_time(conn, Some(account), utc_ms(14, 1, 0)).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_instance_at_time_bounds_open_instances() {
+        // The log stops while the instance is still active
+        let database = database_from_log(&SAMPLE_LOG[..6]);
+        let conn = database.connection();
+        let account = my_account_id(conn);
+
+        assert!(
+            find_instance_at_time(conn, Some(account), utc_ms(13, 20, 0))
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(
+            find_instance_at_time(conn, Some(account), utc_ms(15, 10, 0)).unwrap(),
+            None
+        );
+    }
+}
@@ -60,3 +60,35 @@ pub fn get_log_file_position(conn: &Connection, file_path: &str) -> Result<Optio
         Err(e) => Err(e),
     }
 }
+
+/// Set or clear the time zone of a log file (the file does not need to exist yet)
+pub fn set_log_file_timezone(
+    conn: &Connection,
+    file_path: &str,
+    timezone: Option<&str>,
+) -> Result<()> {
+    let now = Utc::now().timestamp_millis();
+
+    conn.execute(
+        "INSERT INTO log_files (file_path, file_size, last_read_position, last_processed_at, timezone)
+         VALUES (?1, 0, 0, ?2, ?3)
+         ON CONFLICT(file_path) DO UPDATE SET timezone = ?3",
+        rusqlite::params![file_path, now, timezone],
+    )?;
+    Ok(())
+}
+
+/// Get the time zone set for a log file (None uses the settings' time zone)
+pub fn get_log_file_timezone(conn: &Connection, file_path: &str) -> Result<Option<String>> {
+    let result = conn.query_row(
+        "SELECT timezone FROM log_files WHERE file_path = ?1",
+        (file_path,),
+        |row| row.get(0),
+    );
+
+    match result {
+        Ok(timezone) => Ok(timezone),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -1,15 +1,25 @@
 pub mod avatars;
+pub mod filter;
+pub mod game_sessions;
 pub mod instances;
 pub mod log_files;
 pub mod my_accounts;
+pub mod retention;
 pub mod screenshots;
+pub mod settings;
+pub mod stats;
 pub mod users;
 pub mod worlds;
 
 pub use avatars::*;
+pub use filter::*;
+pub use game_sessions::*;
 pub use instances::*;
 pub use log_files::*;
 pub use my_accounts::*;
+pub use retention::*;
 pub use screenshots::*;
+pub use settings::*;
+pub use stats::*;
 pub use users::*;
 pub use worlds::*;
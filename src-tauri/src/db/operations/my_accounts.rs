@@ -1,4 +1,4 @@
-use rusqlite::{Connection, Result};
+use rusqlite::{Connection, OptionalExtension, Result};
 
 /// Upsert local account
 pub fn upsert_my_account(conn: &Connection, user_id: i64, timestamp: i64) -> Result<i64> {
@@ -19,6 +19,16 @@ pub fn upsert_my_account(conn: &Connection, user_id: i64, timestamp: i64) -> Res
     Ok(id)
 }
 
+/// Get local account ID for a user (returns None if the user is not a local account)
+pub fn get_my_account_id(conn: &Connection, user_id: i64) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM my_accounts WHERE user_id = ?1",
+        (user_id,),
+        |row| row.get(0),
+    )
+    .optional()
+}
+
 /// Get latest authenticated account
 pub fn get_latest_authenticated_account(conn: &Connection) -> Result<Option<(i64, i64)>> {
     let result = conn.query_row(
@@ -36,3 +46,28 @@ pub fn get_latest_authenticated_account(conn: &Connection) -> Result<Option<(i64
         Err(e) => Err(e),
     }
 }
+
+/// Merge local account from another database (widens the authentication period)
+pub fn merge_my_account(
+    conn: &Connection,
+    user_id: i64,
+    first_authenticated_at: i64,
+    last_authenticated_at: i64,
+) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO my_accounts (user_id, first_authenticated_at, last_authenticated_at)
+         VALUES (?1, ?2, ?3)
+         ON CONFLICT(user_id) DO UPDATE SET
+           first_authenticated_at = MIN(first_authenticated_at, excluded.first_authenticated_at),
+           last_authenticated_at = MAX(last_authenticated_at, excluded.last_authenticated_at)",
+        (user_id, first_authenticated_at, last_authenticated_at),
+    )?;
+
+    let id = conn.query_row(
+        "SELECT id FROM my_accounts WHERE user_id = ?1",
+        (user_id,),
+        |row| row.get(0),
+    )?;
+
+    Ok(id)
+}
@@ -0,0 +1,230 @@
+use super::EFFECTIVE_ENDED_AT_SQL;
+use rusqlite::{named_params, Connection, Result};
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Rules for dropping remote player data from old instances (instances themselves are kept)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RetentionPolicy {
+    pub participant_days: Option<u32>, // Who was in instances older than this (None keeps forever)
+    pub avatar_history_days: Option<u32>, // Avatar changes in instances older than this
+}
+
+impl RetentionPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.participant_days.is_some() || self.avatar_history_days.is_some()
+    }
+}
+
+/// Rows removed (or that would be removed) by retention rules
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneSummary {
+    pub instances: usize, // Instances that lost participants (their participant count is kept)
+    pub participations: usize,
+    pub avatar_changes: usize,
+    pub users: usize, // People with nothing left in the journal
+}
+
+impl PruneSummary {
+    pub fn is_empty(&self) -> bool {
+        self.participations == 0 && self.avatar_changes == 0 && self.users == 0
+    }
+}
+
+/// Get when retention rules were last applied
+pub fn get_last_pruned_at(conn: &Connection) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT last_pruned_at FROM retention_policy WHERE id = 1",
+        (),
+        |row| row.get(0),
+    )
+}
+
+/// Record when retention rules were last applied
+pub fn set_last_pruned_at(conn: &Connection, timestamp: i64) -> Result<()> {
+    conn.execute(
+        "UPDATE retention_policy SET last_pruned_at = ?1 WHERE id = 1",
+        (timestamp,),
+    )?;
+    Ok(())
+}
+
+/// Drop remote player data from instances that ended before the policy's cutoffs.
+/// Open instances count as ended at their last recorded event; the active one is kept.
+/// Instances remember the participants they lose so counts stay the same.
+pub fn prune_remote_player_data(
+    conn: &Connection,
+    policy: &RetentionPolicy,
+    now: i64,
+) -> Result<PruneSummary> {
+    let mut summary = PruneSummary::default();
+
+    if let Some(days) = policy.avatar_history_days {
+        let sql = format!(
+            "DELETE FROM avatar_history
+             WHERE instance_id IN ({})
+               AND {}",
+            expired_instances_sql(),
+            exempt_users_sql("user_id")
+        );
+        summary.avatar_changes = conn.execute(
+            &sql,
+            named_params! { ":cutoff": cutoff(now, days), ":now": now },
+        )?;
+    }
+
+    if let Some(days) = policy.participant_days {
+        let params = named_params! { ":cutoff": cutoff(now, days), ":now": now };
+
+        let sql = format!(
+            "UPDATE instances SET purged_participants = purged_participants + (
+                 SELECT COUNT(*) FROM instance_users
+                 WHERE instance_id = instances.id AND {exempt}
+             )
+             WHERE id IN ({expired})
+               AND EXISTS (SELECT 1 FROM instance_users
+                           WHERE instance_id = instances.id AND {exempt})",
+            expired = expired_instances_sql(),
+            exempt = exempt_users_sql("user_id")
+        );
+        summary.instances = conn.execute(&sql, params)?;
+
+        let sql = format!(
+            "DELETE FROM instance_users
+             WHERE instance_id IN ({})
+               AND {}",
+            expired_instances_sql(),
+            exempt_users_sql("user_id")
+        );
+        summary.participations = conn.execute(&sql, params)?;
+    }
+
+    if !summary.is_empty() {
+        // Name history goes with the user (ON DELETE CASCADE)
+        let sql = format!(
+            "DELETE FROM users
+             WHERE id NOT IN (SELECT user_id FROM instance_users)
+               AND id NOT IN (SELECT user_id FROM avatar_history)
+               AND {}",
+            exempt_users_sql("id")
+        );
+        summary.users = conn.execute(&sql, ())?;
+    }
+
+    Ok(summary)
+}
+
+/// Report what `prune_remote_player_data` would remove without changing anything
+pub fn preview_prune(
+    conn: &Connection,
+    policy: &RetentionPolicy,
+    now: i64,
+) -> Result<PruneSummary> {
+    conn.execute_batch("SAVEPOINT retention_preview")?;
+    let summary = prune_remote_player_data(conn, policy, now);
+    conn.execute_batch("ROLLBACK TO retention_preview; RELEASE retention_preview")?;
+    summary
+}
+
+/// Instances whose effective end is before `:cutoff` (the active instance ends `:now`)
+fn expired_instances_sql() -> String {
+    format!(
+        "SELECT i.id FROM instances i WHERE {} < :cutoff",
+        EFFECTIVE_ENDED_AT_SQL
+    )
+}
+
+/// People retention rules never touch: local accounts and anyone the user tagged
+fn exempt_users_sql(column: &str) -> String {
+    format!(
+        "{column} NOT IN (SELECT user_id FROM my_accounts)
+           AND {column} NOT IN (SELECT user_id FROM user_tags)"
+    )
+}
+
+fn cutoff(now: i64, days: u32) -> i64 {
+    now - i64::from(days) * DAY_MS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::operations::find_user;
+    use crate::test_support::{database_from_log, sample_database, FRIEND_USER_ID, SAMPLE_LOG};
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, (), |row| row.get(0)).unwrap()
+    }
+
+    fn ended_at(conn: &Connection) -> i64 {
+        count(conn, "SELECT ended_at FROM instances")
+    }
+
+    const PARTICIPANTS: RetentionPolicy = RetentionPolicy {
+        participant_days: Some(30),
+        avatar_history_days: None,
+    };
+
+    #[test]
+    fn test_cutoff_keeps_instances_ending_on_it() {
+        let database = sample_database();
+        let conn = database.connection();
+        let on_cutoff = ended_at(conn) + 30 * DAY_MS;
+
+        let summary = prune_remote_player_data(conn, &PARTICIPANTS, on_cutoff).unwrap();
+        assert!(summary.is_empty());
+
+        let summary = prune_remote_player_data(conn, &PARTICIPANTS, on_cutoff + 1).unwrap();
+        assert_eq!((summary.instances, summary.participations), (1, 1));
+    }
+
+    #[test]
+    fn test_prune_removes_remote_rows_and_keeps_the_rest() {
+        let database = sample_database();
+        let conn = database.connection();
+        let policy = RetentionPolicy {
+            participant_days: Some(30),
+            avatar_history_days: Some(7),
+        };
+        let now = ended_at(conn) + 60 * DAY_MS;
+
+        let preview = preview_prune(conn, &policy, now).unwrap();
+        assert_eq!(count(conn, "SELECT COUNT(*) FROM avatar_history"), 1);
+
+        let summary = prune_remote_player_data(conn, &policy, now).unwrap();
+        assert_eq!(
+            (
+                summary.avatar_changes,
+                summary.participations,
+                summary.users
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(preview.participations, summary.participations);
+
+        // The friend and their name history are gone, the local player stays
+        assert_eq!(find_user(conn, FRIEND_USER_ID).unwrap(), None);
+        assert_eq!(count(conn, "SELECT COUNT(*) FROM user_name_history"), 1);
+        assert_eq!(count(conn, "SELECT COUNT(*) FROM instance_users"), 1);
+        assert_eq!(count(conn, "SELECT purged_participants FROM instances"), 1);
+        assert_eq!(count(conn, "SELECT COUNT(*) FROM screenshots"), 1);
+    }
+
+    #[test]
+    fn test_open_instances_expire_unless_active() {
+        // The log stops while still in the instance
+        let database = database_from_log(&SAMPLE_LOG[..7]);
+        let conn = database.connection();
+        let later = count(conn, "SELECT started_at FROM instances") + 365 * DAY_MS;
+
+        let summary = prune_remote_player_data(conn, &PARTICIPANTS, later).unwrap();
+        assert!(summary.is_empty());
+
+        conn.execute("UPDATE instances SET status = 'interrupted'", ())
+            .unwrap();
+        let summary = prune_remote_player_data(conn, &PARTICIPANTS, later).unwrap();
+        assert_eq!(summary.participations, 1);
+    }
+}
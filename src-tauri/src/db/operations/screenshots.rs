@@ -1,4 +1,6 @@
-use rusqlite::{Connection, Result};
+use super::{InstanceFilter, INSTANCE_FILTER_SQL};
+use crate::types::ScreenshotSource;
+use rusqlite::{Connection, OptionalExtension, Result, ToSql};
 
 /// Record a screenshot
 pub fn record_screenshot(
@@ -6,11 +8,591 @@ pub fn record_screenshot(
     instance_id: i64,
     file_path: &str,
     taken_at: i64,
+    source: ScreenshotSource,
 ) -> Result<i64> {
     conn.execute(
-        "INSERT INTO screenshots (instance_id, file_path, taken_at)
-         VALUES (?1, ?2, ?3)",
-        (instance_id, file_path, taken_at),
+        "INSERT INTO screenshots (instance_id, file_path, taken_at, source)
+         VALUES (?1, ?2, ?3, ?4)",
+        (instance_id, file_path, taken_at, source),
     )?;
     Ok(conn.last_insert_rowid())
 }
+
+/// Find screenshot in an instance by file path
+pub fn find_screenshot(
+    conn: &Connection,
+    instance_id: i64,
+    file_path: &str,
+) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM screenshots WHERE instance_id = ?1 AND file_path = ?2",
+        (instance_id, file_path),
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Find screenshot in any instance by file path
+pub fn find_screenshot_by_path(conn: &Connection, file_path: &str) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM screenshots WHERE file_path = ?1 ORDER BY id LIMIT 1",
+        (file_path,),
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Get how a screenshot was linked to its instance
+pub fn get_screenshot_source(conn: &Connection, screenshot_id: i64) -> Result<ScreenshotSource> {
+    conn.query_row(
+        "SELECT source FROM screenshots WHERE id = ?1",
+        (screenshot_id,),
+        |row| row.get(0),
+    )
+}
+
+/// Change how a screenshot was linked to its instance
+pub fn set_screenshot_source(
+    conn: &Connection,
+    screenshot_id: i64,
+    source: ScreenshotSource,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET source = ?1 WHERE id = ?2",
+        (source, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Get screenshot by id (returns instance_id, file_path, taken_at)
+pub fn get_screenshot(conn: &Connection, screenshot_id: i64) -> Result<Option<(i64, String, i64)>> {
+    conn.query_row(
+        "SELECT instance_id, file_path, taken_at FROM screenshots WHERE id = ?1",
+        (screenshot_id,),
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )
+    .optional()
+}
+
+/// Get stored metadata JSON of a screenshot
+pub fn get_screenshot_metadata(conn: &Connection, screenshot_id: i64) -> Result<Option<String>> {
+    conn.query_row(
+        "SELECT metadata FROM screenshots WHERE id = ?1",
+        (screenshot_id,),
+        |row| row.get(0),
+    )
+    .optional()
+    .map(Option::flatten)
+}
+
+/// Store metadata read from the screenshot file
+pub fn update_screenshot_metadata(
+    conn: &Connection,
+    screenshot_id: i64,
+    width: Option<u32>,
+    height: Option<u32>,
+    metadata: &str,
+    read_at: i64,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots
+         SET width = ?1, height = ?2, metadata = ?3, metadata_read_at = ?4
+         WHERE id = ?5",
+        (width, height, metadata, read_at, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Get screenshots whose stored metadata JSON contains `text` (returns id, metadata)
+pub fn find_screenshot_metadata_containing(
+    conn: &Connection,
+    text: &str,
+) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, metadata FROM screenshots
+         WHERE instr(metadata, ?1) > 0
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map((text,), |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Replace stored metadata JSON without changing when it was read
+pub fn set_screenshot_metadata(
+    conn: &Connection,
+    screenshot_id: i64,
+    metadata: &str,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET metadata = ?1 WHERE id = ?2",
+        (metadata, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Move screenshot to another instance
+pub fn set_screenshot_instance(
+    conn: &Connection,
+    screenshot_id: i64,
+    instance_id: i64,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET instance_id = ?1 WHERE id = ?2",
+        (instance_id, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Get screenshots whose metadata has never been read
+pub fn get_screenshots_without_metadata(conn: &Connection) -> Result<Vec<i64>> {
+    let mut stmt =
+        conn.prepare("SELECT id FROM screenshots WHERE metadata_read_at IS NULL ORDER BY id")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Stored location and fingerprint of a screenshot file
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotFile {
+    pub screenshot_id: i64,
+    pub instance_id: i64,
+    pub file_path: String,
+    pub file_size: Option<i64>,
+    pub content_hash: Option<String>, // SHA-256 (hex)
+    pub missing_since: Option<i64>,
+    pub perceptual_hash: Option<i64>, // dHash bits
+}
+
+/// Get stored file information of all screenshots, or only of those flagged as missing
+pub fn get_screenshot_files(conn: &Connection, only_missing: bool) -> Result<Vec<ScreenshotFile>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, instance_id, file_path, file_size, content_hash, missing_since,
+                perceptual_hash
+         FROM screenshots
+         WHERE ?1 = 0 OR missing_since IS NOT NULL
+         ORDER BY id",
+    )?;
+    let rows = stmt.query_map((only_missing,), |row| {
+        Ok(ScreenshotFile {
+            screenshot_id: row.get(0)?,
+            instance_id: row.get(1)?,
+            file_path: row.get(2)?,
+            file_size: row.get(3)?,
+            content_hash: row.get(4)?,
+            missing_since: row.get(5)?,
+            perceptual_hash: row.get(6)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Store size and content hash of a screenshot file
+pub fn update_screenshot_file(
+    conn: &Connection,
+    screenshot_id: i64,
+    file_size: i64,
+    content_hash: &str,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET file_size = ?1, content_hash = ?2 WHERE id = ?3",
+        (file_size, content_hash, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Store the perceptual hash of a screenshot
+pub fn update_screenshot_perceptual_hash(
+    conn: &Connection,
+    screenshot_id: i64,
+    perceptual_hash: i64,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET perceptual_hash = ?1 WHERE id = ?2",
+        (perceptual_hash, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Flag a screenshot file as missing since `missing_since`, or clear the flag with None
+pub fn set_screenshot_missing(
+    conn: &Connection,
+    screenshot_id: i64,
+    missing_since: Option<i64>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET missing_since = ?1 WHERE id = ?2",
+        (missing_since, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Point a screenshot to its new file location
+pub fn set_screenshot_path(conn: &Connection, screenshot_id: i64, file_path: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET file_path = ?1, missing_since = NULL WHERE id = ?2",
+        (file_path, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Find a missing screenshot with the given content hash
+pub fn find_missing_screenshot_by_hash(
+    conn: &Connection,
+    content_hash: &str,
+) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM screenshots
+         WHERE content_hash = ?1 AND missing_since IS NOT NULL
+         ORDER BY id
+         LIMIT 1",
+        (content_hash,),
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Screenshot with a perceptual hash, as compared for near-duplicates
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HashedScreenshot {
+    pub screenshot_id: i64,
+    pub instance_id: i64,
+    pub file_path: String,
+    pub taken_at: i64,
+    pub hidden: bool,
+    pub duplicate_of: Option<i64>,
+    #[serde(skip)]
+    pub perceptual_hash: u64,
+}
+
+/// Get screenshots whose perceptual hash has been computed
+pub fn get_hashed_screenshots(
+    conn: &Connection,
+    include_hidden: bool,
+) -> Result<Vec<HashedScreenshot>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, instance_id, file_path, taken_at, hidden, duplicate_of, perceptual_hash
+         FROM screenshots
+         WHERE perceptual_hash IS NOT NULL AND (?1 OR hidden = 0)
+         ORDER BY taken_at, id",
+    )?;
+    let rows = stmt.query_map((include_hidden,), |row| {
+        Ok(HashedScreenshot {
+            screenshot_id: row.get(0)?,
+            instance_id: row.get(1)?,
+            file_path: row.get(2)?,
+            taken_at: row.get(3)?,
+            hidden: row.get(4)?,
+            duplicate_of: row.get(5)?,
+            perceptual_hash: row.get::<_, i64>(6)? as u64,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Hide a screenshot as a duplicate of another one (the file is kept)
+pub fn mark_screenshot_duplicate(
+    conn: &Connection,
+    screenshot_id: i64,
+    duplicate_of: i64,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET hidden = 1, duplicate_of = ?1 WHERE id = ?2",
+        (duplicate_of, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Hide or show a screenshot. Showing it also clears its duplicate mark.
+pub fn set_screenshot_hidden(conn: &Connection, screenshot_id: i64, hidden: bool) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots
+         SET hidden = ?1,
+             duplicate_of = CASE WHEN ?1 THEN duplicate_of END
+         WHERE id = ?2",
+        (hidden, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Mark or unmark a screenshot as favorite
+pub fn set_screenshot_favorite(
+    conn: &Connection,
+    screenshot_id: i64,
+    favorite: bool,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET favorite = ?1 WHERE id = ?2",
+        (favorite, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Set or clear the caption of a screenshot
+pub fn set_screenshot_caption(
+    conn: &Connection,
+    screenshot_id: i64,
+    caption: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE screenshots SET caption = ?1 WHERE id = ?2",
+        (caption, screenshot_id),
+    )?;
+    Ok(())
+}
+
+/// Screenshot shown as the cover of an instance or world
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoverPhoto {
+    pub screenshot_id: i64,
+    pub file_path: String,
+    pub caption: Option<String>,
+    pub chosen: bool, // false when falling back to a favorite
+}
+
+/// Cover of an instance: the chosen one, otherwise its first favorite
+pub fn get_instance_cover(conn: &Connection, instance_id: i64) -> Result<Option<CoverPhoto>> {
+    conn.query_row(
+        "SELECT s.id, s.file_path, s.caption, s.id IS i.cover_screenshot_id
+         FROM instances i
+         JOIN screenshots s ON s.id = COALESCE(
+             i.cover_screenshot_id,
+             (SELECT f.id FROM screenshots f
+              WHERE f.instance_id = i.id AND f.favorite = 1 AND f.hidden = 0
+              ORDER BY f.taken_at, f.id
+              LIMIT 1))
+         WHERE i.id = ?1",
+        (instance_id,),
+        read_cover_photo,
+    )
+    .optional()
+}
+
+/// Cover of a world (wrld_xxx): the chosen one, otherwise its latest favorite
+pub fn get_world_cover(conn: &Connection, world_id: &str) -> Result<Option<CoverPhoto>> {
+    conn.query_row(
+        "SELECT s.id, s.file_path, s.caption, s.id IS w.cover_screenshot_id
+         FROM worlds w
+         JOIN screenshots s ON s.id = COALESCE(
+             w.cover_screenshot_id,
+             (SELECT f.id FROM screenshots f
+              JOIN instances fi ON f.instance_id = fi.id
+              WHERE fi.world_id = w.id AND f.favorite = 1 AND f.hidden = 0
+              ORDER BY f.taken_at DESC, f.id DESC
+              LIMIT 1))
+         WHERE w.world_id = ?1",
+        (world_id,),
+        read_cover_photo,
+    )
+    .optional()
+}
+
+fn read_cover_photo(row: &rusqlite::Row) -> Result<CoverPhoto> {
+    Ok(CoverPhoto {
+        screenshot_id: row.get(0)?,
+        file_path: row.get(1)?,
+        caption: row.get(2)?,
+        chosen: row.get(3)?,
+    })
+}
+
+/// Favorite or cover screenshot in a "best of" selection
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BestScreenshot {
+    pub screenshot_id: i64,
+    pub instance_id: i64,
+    pub world_name: String,
+    pub file_path: String,
+    pub taken_at: i64,
+    pub caption: Option<String>,
+    pub favorite: bool,
+    pub cover: bool, // Chosen as cover of its instance or world
+}
+
+/// Favorites and covers of instances matching the filter, oldest first
+pub fn get_best_screenshots(
+    conn: &Connection,
+    filter: &InstanceFilter,
+    limit: Option<usize>,
+) -> Result<Vec<BestScreenshot>> {
+    let sql = format!(
+        "SELECT s.id, s.instance_id, COALESCE(wnh.world_name, w.world_name), s.file_path,
+                s.taken_at, s.caption, s.favorite,
+                s.id IS i.cover_screenshot_id OR s.id IS w.cover_screenshot_id
+         FROM screenshots s
+         JOIN instances i ON s.instance_id = i.id
+         JOIN worlds w ON i.world_id = w.id
+         LEFT JOIN world_name_history wnh ON i.world_name_at_join_id = wnh.id
+         WHERE {}
+           AND s.hidden = 0
+           AND (s.favorite = 1 OR s.id IS i.cover_screenshot_id OR s.id IS w.cover_screenshot_id)
+         ORDER BY s.taken_at, s.id
+         LIMIT :limit",
+        INSTANCE_FILTER_SQL
+    );
+
+    // SQLite treats a negative limit as no limit
+    let limit = limit.map_or(-1, |limit| limit as i64);
+    let mut params: Vec<(&str, &dyn ToSql)> = filter.params().to_vec();
+    params.push((":limit", &limit));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params.as_slice(), |row| {
+        Ok(BestScreenshot {
+            screenshot_id: row.get(0)?,
+            instance_id: row.get(1)?,
+            world_name: row.get(2)?,
+            file_path: row.get(3)?,
+            taken_at: row.get(4)?,
+            caption: row.get(5)?,
+            favorite: row.get(6)?,
+            cover: row.get(7)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Get picture folders watched by the screenshot scanner
+pub fn get_screenshot_folders(conn: &Connection) -> Result<Vec<String>> {
+    let mut stmt = conn.prepare("SELECT path FROM screenshot_folders ORDER BY added_at, id")?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Add a picture folder to the screenshot scanner (no-op if already added)
+pub fn add_screenshot_folder(conn: &Connection, path: &str, added_at: i64) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO screenshot_folders (path, added_at) VALUES (?1, ?2)",
+        (path, added_at),
+    )?;
+    Ok(())
+}
+
+/// Remove a picture folder from the screenshot scanner
+pub fn remove_screenshot_folder(conn: &Connection, path: &str) -> Result<()> {
+    conn.execute("DELETE FROM screenshot_folders WHERE path = ?1", (path,))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::operations::{set_instance_cover, set_world_cover};
+    use crate::test_support::sample_database;
+
+    const WORLD_ID: &str = "wrld_00000000-0000-0000-0000-00000000000a";
+
+    /// Instance and screenshot IDs of the sample, with a second, later screenshot added
+    fn two_screenshots(conn: &Connection) -> (i64, i64, i64) {
+        let (instance_id, first, taken_at): (i64, i64, i64) = conn
+            .query_row(
+                "SELECT instance_id, id, taken_at FROM screenshots",
+                (),
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        let second = record_screenshot(
+            conn,
+            instance_id,
+            "C:\\pics\\later.png",
+            taken_at + 60_000,
+            ScreenshotSource::Log,
+        )
+        .unwrap();
+        (instance_id, first, second)
+    }
+
+    fn cover_of_instance(conn: &Connection, instance_id: i64) -> Option<(i64, bool)> {
+        get_instance_cover(conn, instance_id)
+            .unwrap()
+            .map(|cover| (cover.screenshot_id, cover.chosen))
+    }
+
+    #[test]
+    fn test_instance_cover_falls_back_to_first_visible_favorite() {
+        let database = sample_database();
+        let conn = database.connection();
+        let (instance_id, first, second) = two_screenshots(conn);
+
+        assert_eq!(cover_of_instance(conn, instance_id), None);
+
+        set_screenshot_favorite(conn, second, true).unwrap();
+        set_screenshot_favorite(conn, first, true).unwrap();
+        assert_eq!(cover_of_instance(conn, instance_id), Some((first, false)));
+
+        set_screenshot_hidden(conn, first, true).unwrap();
+        assert_eq!(cover_of_instance(conn, instance_id), Some((second, false)));
+
+        set_instance_cover(conn, instance_id, Some(first)).unwrap();
+        assert_eq!(cover_of_instance(conn, instance_id), Some((first, true)));
+
+        // Deleting the chosen screenshot clears the cover
+        conn.execute("DELETE FROM screenshots WHERE id = ?1", (first,))
+            .unwrap();
+        assert_eq!(cover_of_instance(conn, instance_id), Some((second, false)));
+    }
+
+    #[test]
+    fn test_world_cover_falls_back_to_latest_favorite() {
+        let database = sample_database();
+        let conn = database.connection();
+        let (_, first, second) = two_screenshots(conn);
+        set_screenshot_favorite(conn, first, true).unwrap();
+        set_screenshot_favorite(conn, second, true).unwrap();
+
+        let cover = get_world_cover(conn, WORLD_ID).unwrap().unwrap();
+        assert_eq!((cover.screenshot_id, cover.chosen), (second, false));
+
+        assert!(set_world_cover(conn, WORLD_ID, Some(first)).unwrap());
+        assert!(!set_world_cover(conn, "wrld_unknown", Some(first)).unwrap());
+        let cover = get_world_cover(conn, WORLD_ID).unwrap().unwrap();
+        assert_eq!((cover.screenshot_id, cover.chosen), (first, true));
+
+        set_world_cover(conn, WORLD_ID, None).unwrap();
+        let cover = get_world_cover(conn, WORLD_ID).unwrap().unwrap();
+        assert_eq!(cover.screenshot_id, second);
+    }
+
+    #[test]
+    fn test_best_screenshots_list_favorites_and_covers_with_captions() {
+        let database = sample_database();
+        let conn = database.connection();
+        let (instance_id, first, second) = two_screenshots(conn);
+        let filter = InstanceFilter::default();
+
+        assert!(get_best_screenshots(conn, &filter, None)
+            .unwrap()
+            .is_empty());
+
+        set_screenshot_favorite(conn, second, true).unwrap();
+        set_screenshot_caption(conn, second, Some("Sunset")).unwrap();
+        set_instance_cover(conn, instance_id, Some(first)).unwrap();
+
+        let best = get_best_screenshots(conn, &filter, None).unwrap();
+        let summary: Vec<_> = best
+            .iter()
+            .map(|s| (s.screenshot_id, s.favorite, s.cover, s.caption.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (first, false, true, None),
+                (second, true, false, Some("Sunset"))
+            ]
+        );
+        assert_eq!(best[0].world_name, "Test World");
+        assert_eq!(
+            get_best_screenshots(conn, &filter, Some(1)).unwrap().len(),
+            1
+        );
+
+        set_screenshot_caption(conn, second, None).unwrap();
+        set_screenshot_favorite(conn, second, false).unwrap();
+        let best = get_best_screenshots(conn, &filter, None).unwrap();
+        assert_eq!(best.len(), 1);
+        assert_eq!(best[0].screenshot_id, first);
+    }
+}
@@ -0,0 +1,409 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc};
+use rusqlite::{named_params, Connection, Result};
+use std::collections::{BTreeMap, HashSet};
+
+/// Effective end of an instance in milliseconds.
+/// Open instances end "now" while active, otherwise at their last recorded participant,
+/// avatar or screenshot time, never past the next instance of the same account.
+pub const EFFECTIVE_ENDED_AT_SQL: &str = "COALESCE(
+    i.ended_at,
+    CASE WHEN i.status = 'active' THEN :now END,
+    MIN(
+        MAX(
+            i.started_at,
+            COALESCE((SELECT MAX(COALESCE(u.left_at, u.joined_at)) FROM instance_users u
+                      WHERE u.instance_id = i.id), i.started_at),
+            COALESCE((SELECT MAX(a.changed_at) FROM avatar_history a
+                      WHERE a.instance_id = i.id), i.started_at),
+            COALESCE((SELECT MAX(s.taken_at) FROM screenshots s
+                      WHERE s.instance_id = i.id), i.started_at)
+        ),
+        COALESCE((SELECT MIN(n.started_at) FROM instances n
+                  WHERE n.my_account_id = i.my_account_id AND n.started_at > i.started_at),
+                 9223372036854775807)
+    )
+)";
+
+/// Time spent in VRChat on a single local date
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub duration_ms: i64,
+    pub instance_count: usize,
+    pub world_count: usize,
+    pub screenshot_count: usize,
+}
+
+/// Time spent in VRChat during one hour of the week (weekday 0 = Monday)
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapCell {
+    pub weekday: u32,
+    pub hour: u32,
+    pub duration_ms: i64,
+    pub instance_count: usize,
+    pub world_count: usize,
+    pub screenshot_count: usize,
+}
+
+/// Instance time range used for aggregation
+#[derive(Debug, Clone, Copy)]
+struct ActivityInterval {
+    instance_id: i64,
+    world_id: i64,
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default)]
+struct Bucket {
+    duration_ms: i64,
+    instances: HashSet<i64>,
+    worlds: HashSet<i64>,
+    screenshots: usize,
+}
+
+/// Aggregate activity per local date within [from, to) (Unix ms)
+pub fn get_calendar_days<Tz: TimeZone>(
+    conn: &Connection,
+    tz: &Tz,
+    my_account_id: Option<i64>,
+    from: i64,
+    to: i64,
+) -> Result<Vec<CalendarDay>> {
+    let intervals = get_activity_intervals(conn, my_account_id, from, to)?;
+    let screenshots = get_screenshot_times(conn, my_account_id, from, to)?;
+
+    let buckets = aggregate(
+        &intervals,
+        &screenshots,
+        |t| next_local_midnight(tz, t),
+        |t| t.with_timezone(tz).date_naive(),
+    );
+
+    Ok(buckets
+        .into_iter()
+        .map(|(date, bucket)| CalendarDay {
+            date,
+            duration_ms: bucket.duration_ms,
+            instance_count: bucket.instances.len(),
+            world_count: bucket.worlds.len(),
+            screenshot_count: bucket.screenshots,
+        })
+        .collect())
+}
+
+/// Aggregate activity per local hour of week within [from, to) (Unix ms).
+/// Always returns all 168 cells ordered by weekday then hour.
+pub fn get_activity_heatmap<Tz: TimeZone>(
+    conn: &Connection,
+    tz: &Tz,
+    my_account_id: Option<i64>,
+    from: i64,
+    to: i64,
+) -> Result<Vec<HeatmapCell>> {
+    let intervals = get_activity_intervals(conn, my_account_id, from, to)?;
+    let screenshots = get_screenshot_times(conn, my_account_id, from, to)?;
+
+    let mut buckets = aggregate(
+        &intervals,
+        &screenshots,
+        |t| next_local_hour(tz, t),
+        |t| {
+            let local = t.with_timezone(tz);
+            (local.weekday().num_days_from_monday(), local.hour())
+        },
+    );
+
+    let mut cells = Vec::with_capacity(7 * 24);
+    for weekday in 0..7 {
+        for hour in 0..24 {
+            let bucket = buckets.remove(&(weekday, hour)).unwrap_or_default();
+            cells.push(HeatmapCell {
+                weekday,
+                hour,
+                duration_ms: bucket.duration_ms,
+                instance_count: bucket.instances.len(),
+                world_count: bucket.worlds.len(),
+                screenshot_count: bucket.screenshots,
+            });
+        }
+    }
+
+    Ok(cells)
+}
+
+/// Get instance time ranges overlapping [from, to), clipped to that range
+fn get_activity_intervals(
+    conn: &Connection,
+    my_account_id: Option<i64>,
+    from: i64,
+    to: i64,
+) -> Result<Vec<ActivityInterval>> {
+    let sql = format!(
+        "SELECT id, world_id, started_at, ended_at FROM (
+             SELECT i.id, i.world_id, i.started_at, {} AS ended_at
+             FROM instances i
+             WHERE (:account IS NULL OR i.my_account_id = :account)
+         )
+         WHERE started_at < :to AND ended_at > :from
+         ORDER BY started_at",
+        EFFECTIVE_ENDED_AT_SQL
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(
+        named_params! {
+            ":account": my_account_id,
+            ":from": from,
+            ":to": to,
+            ":now": Utc::now().timestamp_millis(),
+        },
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        },
+    )?;
+
+    let mut intervals = Vec::new();
+    for row in rows {
+        let (instance_id, world_id, started_at, ended_at) = row?;
+        let (Some(started_at), Some(ended_at)) = (
+            DateTime::from_timestamp_millis(started_at.max(from)),
+            DateTime::from_timestamp_millis(ended_at.min(to)),
+        ) else {
+            continue;
+        };
+
+        intervals.push(ActivityInterval {
+            instance_id,
+            world_id,
+            started_at,
+            ended_at,
+        });
+    }
+
+    Ok(intervals)
+}
+
+/// Get screenshot timestamps within [from, to)
+fn get_screenshot_times(
+    conn: &Connection,
+    my_account_id: Option<i64>,
+    from: i64,
+    to: i64,
+) -> Result<Vec<DateTime<Utc>>> {
+    let mut stmt = conn.prepare(
+        "SELECT s.taken_at
+         FROM screenshots s
+         JOIN instances i ON s.instance_id = i.id
+         WHERE (:account IS NULL OR i.my_account_id = :account)
+           AND s.taken_at >= :from AND s.taken_at < :to",
+    )?;
+
+    let rows = stmt.query_map(
+        named_params! {
+            ":account": my_account_id,
+            ":from": from,
+            ":to": to,
+        },
+        |row| row.get::<_, i64>(0),
+    )?;
+
+    let mut times = Vec::new();
+    for row in rows {
+        if let Some(taken_at) = DateTime::from_timestamp_millis(row?) {
+            times.push(taken_at);
+        }
+    }
+
+    Ok(times)
+}
+
+/// Distribute intervals and screenshots into buckets.
+/// Intervals are split at every boundary returned by `next_boundary` so no segment spans two buckets.
+fn aggregate<K: Ord>(
+    intervals: &[ActivityInterval],
+    screenshots: &[DateTime<Utc>],
+    next_boundary: impl Fn(DateTime<Utc>) -> DateTime<Utc>,
+    key: impl Fn(DateTime<Utc>) -> K,
+) -> BTreeMap<K, Bucket> {
+    let mut buckets: BTreeMap<K, Bucket> = BTreeMap::new();
+
+    for interval in intervals {
+        for (start, end) in split_interval(interval.started_at, interval.ended_at, &next_boundary) {
+            let bucket = buckets.entry(key(start)).or_default();
+            bucket.duration_ms += (end - start).num_milliseconds();
+            bucket.instances.insert(interval.instance_id);
+            bucket.worlds.insert(interval.world_id);
+        }
+    }
+
+    for &taken_at in screenshots {
+        buckets.entry(key(taken_at)).or_default().screenshots += 1;
+    }
+
+    buckets
+}
+
+/// Split [start, end) into consecutive segments at each boundary
+fn split_interval(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    next_boundary: impl Fn(DateTime<Utc>) -> DateTime<Utc>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let mut segments = Vec::new();
+    let mut cursor = start;
+
+    while cursor < end {
+        // Guard against a boundary function that fails to advance
+        let boundary = next_boundary(cursor).max(cursor + Duration::milliseconds(1));
+        let segment_end = boundary.min(end);
+        segments.push((cursor, segment_end));
+        cursor = segment_end;
+    }
+
+    segments
+}
+
+/// Next local midnight after `t` (skips forward if midnight does not exist due to DST)
+fn next_local_midnight<Tz: TimeZone>(tz: &Tz, t: DateTime<Utc>) -> DateTime<Utc> {
+    let date = t.with_timezone(tz).date_naive();
+    let mut candidate = date
+        .succ_opt()
+        .unwrap_or(NaiveDate::MAX)
+        .and_time(NaiveTime::MIN);
+
+    for _ in 0..24 {
+        if let Some(dt) = tz.from_local_datetime(&candidate).earliest() {
+            return dt.with_timezone(&Utc);
+        }
+        candidate += Duration::hours(1);
+    }
+
+    t + Duration::days(1)
+}
+
+/// Next instant where the local wall clock shows a full hour
+fn next_local_hour<Tz: TimeZone>(tz: &Tz, t: DateTime<Utc>) -> DateTime<Utc> {
+    let local = t.with_timezone(tz);
+    let into_hour = Duration::seconds(i64::from(local.minute() * 60 + local.second()))
+        + Duration::nanoseconds(i64::from(local.nanosecond()));
+
+    t + Duration::hours(1) - into_hour
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{operations, Database};
+    use crate::types::InstanceStatus;
+    use chrono_tz::{America::New_York, Asia::Tokyo};
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    fn interval(started_at: &str, ended_at: &str) -> ActivityInterval {
+        ActivityInterval {
+            instance_id: 1,
+            world_id: 1,
+            started_at: utc(started_at),
+            ended_at: utc(ended_at),
+        }
+    }
+
+    #[test]
+    fn test_calendar_splits_session_at_local_midnight() {
+        // 23:00 to 01:30 JST
+        let intervals = [interval("2025-10-13T14:00:00Z", "2025-10-13T16:30:00Z")];
+
+        let buckets = aggregate(
+            &intervals,
+            &[],
+            |t| next_local_midnight(&Tokyo, t),
+            |t| t.with_timezone(&Tokyo).date_naive(),
+        );
+
+        let day1 = &buckets[&NaiveDate::from_ymd_opt(2025, 10, 13).unwrap()];
+        let day2 = &buckets[&NaiveDate::from_ymd_opt(2025, 10, 14).unwrap()];
+        assert_eq!(day1.duration_ms, Duration::hours(1).num_milliseconds());
+        assert_eq!(day2.duration_ms, Duration::minutes(90).num_milliseconds());
+        assert_eq!(day1.instances.len(), 1);
+        assert_eq!(day2.instances.len(), 1);
+    }
+
+    #[test]
+    fn test_calendar_day_length_on_dst_fall_back() {
+        // 2025-11-02 is 25 hours long in New York
+        let intervals = [interval("2025-11-02T04:00:00Z", "2025-11-03T05:00:00Z")];
+
+        let buckets = aggregate(
+            &intervals,
+            &[],
+            |t| next_local_midnight(&New_York, t),
+            |t| t.with_timezone(&New_York).date_naive(),
+        );
+
+        assert_eq!(buckets.len(), 1);
+        let day = &buckets[&NaiveDate::from_ymd_opt(2025, 11, 2).unwrap()];
+        assert_eq!(day.duration_ms, Duration::hours(25).num_milliseconds());
+    }
+
+    #[test]
+    fn test_heatmap_buckets_by_local_hour() {
+        // Monday 09:30 to 11:15 JST
+        let intervals = [interval("2025-10-13T00:30:00Z", "2025-10-13T02:15:00Z")];
+        let screenshots = [utc("2025-10-13T01:10:00Z")];
+
+        let buckets = aggregate(
+            &intervals,
+            &screenshots,
+            |t| next_local_hour(&Tokyo, t),
+            |t| {
+                let local = t.with_timezone(&Tokyo);
+                (local.weekday().num_days_from_monday(), local.hour())
+            },
+        );
+
+        assert_eq!(buckets[&(0, 9)].duration_ms, Duration::minutes(30).num_milliseconds());
+        assert_eq!(buckets[&(0, 10)].duration_ms, Duration::hours(1).num_milliseconds());
+        assert_eq!(buckets[&(0, 11)].duration_ms, Duration::minutes(15).num_milliseconds());
+        assert_eq!(buckets[&(0, 10)].screenshots, 1);
+    }
+
+    #[test]
+    fn test_open_instance_ends_at_last_recorded_event() {
+        let database = Database::open_in_memory().unwrap();
+        let conn = database.connection();
+        let hour = Duration::hours(1).num_milliseconds();
+        let start = utc("2025-10-13T00:00:00Z").timestamp_millis();
+
+        let me = operations::upsert_user(conn, "usr_me", "Me", start).unwrap();
+        let account = operations::upsert_my_account(conn, me, start).unwrap();
+        let world = operations::upsert_world(conn, "wrld_a", start).unwrap();
+
+        // Left open by a crash; the last participant left after 30 minutes
+        let crashed = operations::create_instance(conn, account, world, "1", start).unwrap();
+        operations::update_instance_status(conn, crashed, InstanceStatus::Crashed).unwrap();
+        let friend = operations::upsert_user(conn, "usr_f", "Friend", start).unwrap();
+        let name = operations::upsert_user_name_history(conn, friend, "Friend", start).unwrap();
+        let joined = operations::add_user_to_instance(conn, crashed, friend, name, start).unwrap();
+        operations::set_user_left_instance(conn, joined, start + hour / 2).unwrap();
+
+        // VRChat was launched again two days later
+        operations::create_instance(conn, account, world, "2", start + 48 * hour).unwrap();
+
+        let intervals = get_activity_intervals(conn, Some(account), start, start + hour).unwrap();
+        assert_eq!(intervals.len(), 1);
+        assert_eq!(
+            intervals[0].ended_at.timestamp_millis() - intervals[0].started_at.timestamp_millis(),
+            hour / 2
+        );
+    }
+}
@@ -1,4 +1,6 @@
+use crate::types::MergeOutcome;
 use rusqlite::{Connection, OptionalExtension, Result};
+use std::collections::HashMap;
 
 /// Upsert user and return user ID
 pub fn upsert_user(
@@ -70,3 +72,275 @@ pub fn upsert_user_name_history(
         Ok(conn.last_insert_rowid())
     }
 }
+
+/// Merge user from another database (keeps the earliest first_seen_at and the most recent name)
+pub fn merge_user(
+    conn: &Connection,
+    user_id: &str,
+    display_name: &str,
+    first_seen_at: i64,
+    last_seen_at: i64,
+) -> Result<(i64, MergeOutcome)> {
+    let existing: Option<(i64, String, i64, i64)> = conn
+        .query_row(
+            "SELECT id, display_name, first_seen_at, last_seen_at FROM users WHERE user_id = ?1",
+            (user_id,),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?;
+
+    let Some((id, current_name, current_first, current_last)) = existing else {
+        conn.execute(
+            "INSERT INTO users (user_id, display_name, first_seen_at, last_seen_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            (user_id, display_name, first_seen_at, last_seen_at),
+        )?;
+        return Ok((conn.last_insert_rowid(), MergeOutcome::Inserted));
+    };
+
+    let first = current_first.min(first_seen_at);
+    let (name, last) = if last_seen_at > current_last {
+        (display_name, last_seen_at)
+    } else {
+        (current_name.as_str(), current_last)
+    };
+
+    if first == current_first && last == current_last && name == current_name {
+        return Ok((id, MergeOutcome::Unchanged));
+    }
+
+    conn.execute(
+        "UPDATE users SET display_name = ?1, first_seen_at = ?2, last_seen_at = ?3 WHERE id = ?4",
+        (name, first, last, id),
+    )?;
+    Ok((id, MergeOutcome::Merged))
+}
+
+/// Merge user name history entry (widens the period of an existing entry with the same name)
+pub fn merge_user_name_history(
+    conn: &Connection,
+    user_id: i64,
+    display_name: &str,
+    first_seen_at: i64,
+    last_seen_at: i64,
+) -> Result<i64> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM user_name_history
+             WHERE user_id = ?1 AND display_name = ?2
+             ORDER BY first_seen_at DESC
+             LIMIT 1",
+            (user_id, display_name),
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if let Some(id) = existing {
+        conn.execute(
+            "UPDATE user_name_history
+             SET first_seen_at = MIN(first_seen_at, ?1), last_seen_at = MAX(last_seen_at, ?2)
+             WHERE id = ?3",
+            (first_seen_at, last_seen_at, id),
+        )?;
+        Ok(id)
+    } else {
+        conn.execute(
+            "INSERT INTO user_name_history (user_id, display_name, first_seen_at, last_seen_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            (user_id, display_name, first_seen_at, last_seen_at),
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+}
+
+/// Find a user by VRChat user ID and return its row ID
+pub fn find_user(conn: &Connection, user_id: &str) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM users WHERE user_id = ?1",
+        (user_id,),
+        |row| row.get(0),
+    )
+    .optional()
+}
+
+/// Rows removed by a privacy purge
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PurgeSummary {
+    pub instances: usize, // Instances the user took part in (their participant count is kept)
+    pub participations: usize,
+    pub avatar_changes: usize,
+    pub name_history: usize,
+    pub screenshot_metadata: usize, // Screenshots whose stored metadata listed the user
+    pub instance_owners: usize,     // Instance IDs that named the user as owner
+}
+
+/// Erase a user with their name history, instance participation and avatar history.
+/// Instances they took part in remember one purged participant so counts stay the same.
+pub fn purge_user(conn: &Connection, id: i64) -> Result<PurgeSummary> {
+    let instances = conn.execute(
+        "UPDATE instances SET purged_participants = purged_participants + (
+             SELECT COUNT(*) FROM instance_users
+             WHERE instance_id = instances.id AND user_id = ?1
+         )
+         WHERE id IN (SELECT instance_id FROM instance_users WHERE user_id = ?1)",
+        (id,),
+    )?;
+    let avatar_changes = conn.execute("DELETE FROM avatar_history WHERE user_id = ?1", (id,))?;
+    let participations = conn.execute("DELETE FROM instance_users WHERE user_id = ?1", (id,))?;
+    let name_history = conn.execute("DELETE FROM user_name_history WHERE user_id = ?1", (id,))?;
+    conn.execute("DELETE FROM users WHERE id = ?1", (id,))?;
+
+    Ok(PurgeSummary {
+        instances,
+        participations,
+        avatar_changes,
+        name_history,
+        screenshot_metadata: 0,
+        instance_owners: 0,
+    })
+}
+
+/// Drop a user (usr_xxx) as owner from instance IDs: `12345~private(usr_xxx)~region(jp)`
+/// becomes `12345~private~region(jp)`. Returns the number of instances changed.
+pub fn remove_instance_owner(conn: &Connection, user_id: &str) -> Result<usize> {
+    conn.execute(
+        "UPDATE instances SET instance_id = REPLACE(instance_id, '(' || ?1 || ')', '')
+         WHERE instr(instance_id, '(' || ?1 || ')') > 0",
+        (user_id,),
+    )
+}
+
+/// User on the privacy blocklist
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedUser {
+    pub user_id: String, // usr_xxx
+    pub blocked_at: i64,
+}
+
+/// Add a user to the blocklist (keeps the original time if already blocked)
+pub fn block_user(conn: &Connection, user_id: &str, blocked_at: i64) -> Result<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO blocked_users (user_id, blocked_at) VALUES (?1, ?2)",
+        (user_id, blocked_at),
+    )?;
+    Ok(())
+}
+
+/// Remove a user from the blocklist
+pub fn unblock_user(conn: &Connection, user_id: &str) -> Result<()> {
+    conn.execute("DELETE FROM blocked_users WHERE user_id = ?1", (user_id,))?;
+    Ok(())
+}
+
+/// Check whether a user is on the blocklist
+pub fn is_user_blocked(conn: &Connection, user_id: &str) -> Result<bool> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM blocked_users WHERE user_id = ?1)",
+        (user_id,),
+        |row| row.get(0),
+    )
+}
+
+/// Get the blocklist, most recently blocked first
+pub fn get_blocked_users(conn: &Connection) -> Result<Vec<BlockedUser>> {
+    let mut stmt = conn.prepare(
+        "SELECT user_id, blocked_at FROM blocked_users ORDER BY blocked_at DESC, id DESC",
+    )?;
+    let rows = stmt.query_map((), |row| {
+        Ok(BlockedUser {
+            user_id: row.get(0)?,
+            blocked_at: row.get(1)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Get the tags the user gave to people, keyed by VRChat user ID
+pub fn get_user_tags(conn: &Connection) -> Result<HashMap<String, Vec<String>>> {
+    let mut stmt = conn.prepare(
+        "SELECT u.user_id, t.tag FROM user_tags t
+         JOIN users u ON t.user_id = u.id
+         ORDER BY t.tag",
+    )?;
+    let rows = stmt.query_map((), |row| {
+        Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+    })?;
+
+    let mut tags: HashMap<String, Vec<String>> = HashMap::new();
+    for row in rows {
+        let (user_id, tag) = row?;
+        tags.entry(user_id).or_default().push(tag);
+    }
+    Ok(tags)
+}
+
+/// Replace the tags of a user (an empty list removes them all)
+pub fn set_user_tags(conn: &Connection, id: i64, tags: &[String]) -> Result<()> {
+    conn.execute("DELETE FROM user_tags WHERE user_id = ?1", (id,))?;
+    for tag in tags {
+        conn.execute(
+            "INSERT OR IGNORE INTO user_tags (user_id, tag) VALUES (?1, ?2)",
+            (id, tag),
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::operations::add_user_to_instance;
+    use crate::test_support::{database_from_log, FRIEND_USER_ID, MY_USER_ID, SAMPLE_LOG};
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, (), |row| row.get(0)).unwrap()
+    }
+
+    #[test]
+    fn test_purge_user_keeps_counts_and_drops_owner_ids() {
+        // The friend owns the instance
+        let joining = SAMPLE_LOG[1].replace(MY_USER_ID, FRIEND_USER_ID);
+        let mut lines = SAMPLE_LOG.to_vec();
+        lines[1] = &joining;
+        let database = database_from_log(&lines);
+        let conn = database.connection();
+
+        let friend = find_user(conn, FRIEND_USER_ID).unwrap().unwrap();
+        // The friend comes back later, so two participations are erased
+        let (instance, name): (i64, i64) = conn
+            .query_row(
+                "SELECT instance_id, display_name_at_join_id FROM instance_users WHERE user_id = ?1",
+                (friend,),
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        add_user_to_instance(conn, instance, friend, name, 1_760_398_800_000).unwrap();
+        set_user_tags(conn, friend, &["school".to_string()]).unwrap();
+
+        let summary = purge_user(conn, friend).unwrap();
+        assert_eq!(
+            (
+                summary.instances,
+                summary.participations,
+                summary.avatar_changes,
+                summary.name_history
+            ),
+            (1, 2, 1, 1)
+        );
+        assert_eq!(find_user(conn, FRIEND_USER_ID).unwrap(), None);
+        assert_eq!(count(conn, "SELECT COUNT(*) FROM user_tags"), 0);
+        assert_eq!(count(conn, "SELECT purged_participants FROM instances"), 2);
+        // The local player's own rows stay
+        assert!(find_user(conn, MY_USER_ID).unwrap().is_some());
+        assert_eq!(count(conn, "SELECT COUNT(*) FROM instance_users"), 1);
+
+        assert_eq!(remove_instance_owner(conn, FRIEND_USER_ID).unwrap(), 1);
+        let instance_id: String = conn
+            .query_row("SELECT instance_id FROM instances", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(instance_id, "1234~friends~region(jp)");
+        assert_eq!(remove_instance_owner(conn, FRIEND_USER_ID).unwrap(), 0);
+    }
+}
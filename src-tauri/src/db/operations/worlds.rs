@@ -1,3 +1,4 @@
+use crate::types::MergeOutcome;
 use rusqlite::{Connection, OptionalExtension, Result};
 
 /// Upsert world and return world ID
@@ -28,6 +29,20 @@ pub fn update_world_name(conn: &Connection, world_id: i64, world_name: &str) ->
     Ok(())
 }
 
+/// Choose the cover screenshot of a world (None falls back to its latest favorite).
+/// Returns whether the world exists.
+pub fn set_world_cover(
+    conn: &Connection,
+    world_id: &str,
+    screenshot_id: Option<i64>,
+) -> Result<bool> {
+    let updated = conn.execute(
+        "UPDATE worlds SET cover_screenshot_id = ?1 WHERE world_id = ?2",
+        (screenshot_id, world_id),
+    )?;
+    Ok(updated > 0)
+}
+
 /// Upsert world name history
 pub fn upsert_world_name_history(
     conn: &Connection,
@@ -64,3 +79,103 @@ pub fn upsert_world_name_history(
         Ok(conn.last_insert_rowid())
     }
 }
+
+/// Merge world from another database (keeps the earliest first_seen_at and the most recent name)
+pub fn merge_world(
+    conn: &Connection,
+    world_id: &str,
+    world_name: &str,
+    first_seen_at: i64,
+    last_seen_at: i64,
+) -> Result<(i64, MergeOutcome)> {
+    let existing: Option<(i64, String, i64, i64)> = conn
+        .query_row(
+            "SELECT id, world_name, first_seen_at, last_seen_at FROM worlds WHERE world_id = ?1",
+            (world_id,),
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?;
+
+    let Some((id, current_name, current_first, current_last)) = existing else {
+        conn.execute(
+            "INSERT INTO worlds (world_id, world_name, first_seen_at, last_seen_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            (world_id, world_name, first_seen_at, last_seen_at),
+        )?;
+        return Ok((conn.last_insert_rowid(), MergeOutcome::Inserted));
+    };
+
+    let first = current_first.min(first_seen_at);
+    // An empty name means EnteringRoom was never seen for that world
+    let (name, last) = if last_seen_at > current_last && !world_name.is_empty() {
+        (world_name, last_seen_at)
+    } else if current_name.is_empty() {
+        (world_name, current_last.max(last_seen_at))
+    } else {
+        (current_name.as_str(), current_last.max(last_seen_at))
+    };
+
+    if first == current_first && last == current_last && name == current_name {
+        return Ok((id, MergeOutcome::Unchanged));
+    }
+
+    conn.execute(
+        "UPDATE worlds SET world_name = ?1, first_seen_at = ?2, last_seen_at = ?3 WHERE id = ?4",
+        (name, first, last, id),
+    )?;
+    Ok((id, MergeOutcome::Merged))
+}
+
+/// Merge world name history entry (widens the period of an existing entry with the same name)
+pub fn merge_world_name_history(
+    conn: &Connection,
+    world_id: i64,
+    world_name: &str,
+    first_seen_at: i64,
+    last_seen_at: i64,
+) -> Result<i64> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM world_name_history
+             WHERE world_id = ?1 AND world_name = ?2
+             ORDER BY first_seen_at DESC
+             LIMIT 1",
+            (world_id, world_name),
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    if let Some(id) = existing {
+        conn.execute(
+            "UPDATE world_name_history
+             SET first_seen_at = MIN(first_seen_at, ?1), last_seen_at = MAX(last_seen_at, ?2)
+             WHERE id = ?3",
+            (first_seen_at, last_seen_at, id),
+        )?;
+        Ok(id)
+    } else {
+        conn.execute(
+            "INSERT INTO world_name_history (world_id, world_name, first_seen_at, last_seen_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            (world_id, world_name, first_seen_at, last_seen_at),
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+}
+
+/// Find the latest name history entry of a world with the given name
+pub fn find_world_name_history(
+    conn: &Connection,
+    world_id: i64,
+    world_name: &str,
+) -> Result<Option<i64>> {
+    conn.query_row(
+        "SELECT id FROM world_name_history
+         WHERE world_id = ?1 AND world_name = ?2
+         ORDER BY first_seen_at DESC
+         LIMIT 1",
+        (world_id, world_name),
+        |row| row.get(0),
+    )
+    .optional()
+}
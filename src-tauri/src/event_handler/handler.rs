@@ -1,8 +1,11 @@
 use crate::db::operations;
+use crate::screenshot::{self, ScreenshotMetadata};
 use crate::types::{LogEvent, VRChatEvent};
 use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use rusqlite::Connection;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 use super::handlers;
 
@@ -11,10 +14,65 @@ pub struct HandlerContext<'a> {
     pub current_my_account_id: &'a mut Option<i64>,
     pub current_user_id: &'a mut Option<i64>,
     pub current_instance_id: &'a mut Option<i64>,
+    pub current_game_session_id: &'a mut Option<i64>,
     pub user_ids: &'a mut HashMap<String, i64>,
     pub instance_user_ids: &'a mut HashMap<i64, i64>,
     pub display_name_to_user_id: &'a mut HashMap<String, i64>,
     pub pending_avatars: &'a mut HashMap<String, (i64, DateTime<Utc>)>,
+    pub blocked_display_names: &'a mut HashSet<String>,
+    pub screenshot_metadata: &'a mut HashMap<String, ScreenshotMetadata>,
+    pub timezone: Tz, // Zone of the times in screenshot file names
+}
+
+impl HandlerContext<'_> {
+    /// Make `instance_id` the current instance, with the users still in it
+    pub fn restore_instance(
+        &mut self,
+        conn: &Connection,
+        instance_id: i64,
+    ) -> Result<(), rusqlite::Error> {
+        self.clear_instance();
+        *self.current_instance_id = Some(instance_id);
+
+        let users = operations::get_instance_active_users(conn, instance_id)?;
+        for (vrchat_user_id, user_id, instance_user_id) in users {
+            self.user_ids.insert(vrchat_user_id, user_id);
+            self.instance_user_ids.insert(user_id, instance_user_id);
+
+            // Restore display_name mapping
+            let display_name = operations::get_user_display_name(conn, user_id)?;
+            self.display_name_to_user_id.insert(display_name, user_id);
+        }
+
+        tracing::info!(
+            instance_id,
+            users = self.user_ids.len(),
+            "Restored users in current instance"
+        );
+        Ok(())
+    }
+
+    /// Forget the current instance and everyone in it
+    pub fn clear_instance(&mut self) {
+        *self.current_instance_id = None;
+        self.user_ids.clear();
+        self.instance_user_ids.clear();
+        self.display_name_to_user_id.clear();
+        self.pending_avatars.clear();
+        self.blocked_display_names.clear();
+    }
+}
+
+/// What the event handler currently tracks (for diagnostics)
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HandlerState {
+    pub my_account_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub instance_id: Option<i64>,
+    pub game_session_id: Option<i64>,
+    pub users_present: usize,   // Players in the current instance
+    pub pending_avatars: usize, // Avatars seen before their PlayerJoined line
 }
 
 /// Event handler for processing log events
@@ -22,10 +80,14 @@ pub struct EventHandler {
     current_my_account_id: Option<i64>,   // Current local account
     current_user_id: Option<i64>,         // Current user (corresponds to my_account)
     current_instance_id: Option<i64>,     // Current active instance
+    current_game_session_id: Option<i64>, // Current VRChat launch
     user_ids: HashMap<String, i64>,       // vrchat_user_id -> users.id mapping
     instance_user_ids: HashMap<i64, i64>, // user_id -> instance_users.id mapping
     display_name_to_user_id: HashMap<String, i64>, // display_name -> users.id mapping
     pending_avatars: HashMap<String, (i64, DateTime<Utc>)>, // display_name -> (avatar_id, timestamp) for avatars seen before PlayerJoined
+    blocked_display_names: HashSet<String>, // Players on the blocklist in the current instance
+    screenshot_metadata: HashMap<String, ScreenshotMetadata>, // file_path -> metadata read before the transaction
+    timezone: Tz, // Zone of the times in screenshot file names
 }
 
 impl EventHandler {
@@ -34,46 +96,73 @@ impl EventHandler {
             current_my_account_id: None,
             current_user_id: None,
             current_instance_id: None,
+            current_game_session_id: None,
             user_ids: HashMap::new(),
             instance_user_ids: HashMap::new(),
             display_name_to_user_id: HashMap::new(),
             pending_avatars: HashMap::new(),
+            blocked_display_names: HashSet::new(),
+            screenshot_metadata: HashMap::new(),
+            timezone: Tz::UTC,
+        }
+    }
+
+    /// Set the zone screenshot file names are read in (the PC's, as for log files)
+    pub fn set_timezone(&mut self, timezone: Tz) {
+        self.timezone = timezone;
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    /// Read the files of screenshot events up front, so no file I/O happens
+    /// while the event transaction is open
+    pub fn prefetch(&mut self, events: &[LogEvent]) {
+        self.screenshot_metadata.clear();
+        for event in events {
+            if let LogEvent::ScreenshotTaken { file_path, .. } = event {
+                let metadata = screenshot::read_file_metadata(Path::new(file_path), self.timezone);
+                self.screenshot_metadata.insert(file_path.clone(), metadata);
+            }
+        }
+    }
+
+    /// Current active instance, if any
+    pub fn current_instance_id(&self) -> Option<i64> {
+        self.current_instance_id
+    }
+
+    pub fn state(&self) -> HandlerState {
+        HandlerState {
+            my_account_id: self.current_my_account_id,
+            user_id: self.current_user_id,
+            instance_id: self.current_instance_id,
+            game_session_id: self.current_game_session_id,
+            users_present: self.instance_user_ids.len(),
+            pending_avatars: self.pending_avatars.len(),
         }
     }
 
     /// Restore state from database
     pub fn restore_previous_state(&mut self, conn: &Connection) -> Result<(), rusqlite::Error> {
+        self.current_game_session_id = operations::get_latest_running_game_session(conn)?;
+
         // Restore the most recently authenticated local account
         if let Some((my_account_id, user_id)) = operations::get_latest_authenticated_account(conn)?
         {
             self.current_my_account_id = Some(my_account_id);
             self.current_user_id = Some(user_id);
-            println!(
-                "EventHandler initialized with my_account_id: {}, user_id: {}",
-                my_account_id, user_id
-            );
+            tracing::info!(my_account_id, user_id, "EventHandler initialized");
 
             // Find active instance for this account
             if let Some(instance_id) = operations::get_latest_active_instance(conn, my_account_id)?
             {
-                self.current_instance_id = Some(instance_id);
-                println!("Found active instance: {}", instance_id);
-
-                // Restore users currently in the instance
-                let users = operations::get_instance_active_users(conn, instance_id)?;
-                for (vrchat_user_id, user_id, instance_user_id) in users {
-                    self.user_ids.insert(vrchat_user_id, user_id);
-                    self.instance_user_ids.insert(user_id, instance_user_id);
-
-                    // Restore display_name mapping
-                    let display_name = operations::get_user_display_name(conn, user_id)?;
-                    self.display_name_to_user_id.insert(display_name, user_id);
-                }
-
-                println!("Restored {} users in current instance", self.user_ids.len());
+                tracing::info!(instance_id, "Found active instance");
+                self.context().restore_instance(conn, instance_id)?;
             }
         } else {
-            println!("No local account found. Waiting for authentication event.");
+            tracing::info!("No local account found. Waiting for authentication event.");
         }
 
         Ok(())
@@ -85,15 +174,7 @@ impl EventHandler {
         conn: &Connection,
         event: LogEvent,
     ) -> Result<Option<VRChatEvent>, rusqlite::Error> {
-        let mut ctx = HandlerContext {
-            current_my_account_id: &mut self.current_my_account_id,
-            current_user_id: &mut self.current_user_id,
-            current_instance_id: &mut self.current_instance_id,
-            user_ids: &mut self.user_ids,
-            instance_user_ids: &mut self.instance_user_ids,
-            display_name_to_user_id: &mut self.display_name_to_user_id,
-            pending_avatars: &mut self.pending_avatars,
-        };
+        let mut ctx = self.context();
 
         match event {
             LogEvent::UserAuthenticated {
@@ -156,12 +237,46 @@ impl EventHandler {
             LogEvent::ScreenshotTaken {
                 timestamp,
                 file_path,
-            } => {
-                handlers::screenshot_taken::handle(conn, &ctx, timestamp, &file_path)
-            }
+            } => handlers::screenshot_taken::handle(conn, &mut ctx, timestamp, &file_path),
             LogEvent::EventSyncFailed { timestamp } => {
                 handlers::event_sync_failed::handle(conn, &ctx, timestamp)
             }
+            LogEvent::ClientVersion { timestamp, version } => {
+                handlers::client_version::handle(conn, &ctx, timestamp, &version)
+            }
+            LogEvent::VrModeDetected { timestamp, vr } => {
+                handlers::vr_mode_detected::handle(conn, &ctx, timestamp, vr)
+            }
+            LogEvent::ApplicationQuit { timestamp } => {
+                handlers::application_quit::handle(conn, &mut ctx, timestamp)
+            }
+            LogEvent::LogStarted {
+                timestamp,
+                file_path,
+            } => handlers::log_started::handle(conn, &mut ctx, timestamp, &file_path),
+            LogEvent::LogEnded { timestamp } => {
+                handlers::log_ended::handle(conn, &mut ctx, timestamp)
+            }
+            LogEvent::LogResumed {
+                ended_at,
+                file_path,
+            } => handlers::log_resumed::handle(conn, &mut ctx, ended_at, &file_path),
+        }
+    }
+
+    fn context(&mut self) -> HandlerContext<'_> {
+        HandlerContext {
+            current_my_account_id: &mut self.current_my_account_id,
+            current_user_id: &mut self.current_user_id,
+            current_instance_id: &mut self.current_instance_id,
+            current_game_session_id: &mut self.current_game_session_id,
+            user_ids: &mut self.user_ids,
+            instance_user_ids: &mut self.instance_user_ids,
+            display_name_to_user_id: &mut self.display_name_to_user_id,
+            pending_avatars: &mut self.pending_avatars,
+            blocked_display_names: &mut self.blocked_display_names,
+            screenshot_metadata: &mut self.screenshot_metadata,
+            timezone: self.timezone,
         }
     }
 }
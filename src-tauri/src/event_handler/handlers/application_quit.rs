@@ -0,0 +1,83 @@
+use crate::db::operations;
+use crate::event_handler::HandlerContext;
+use crate::types::{InstanceStatus, VRChatEvent};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+/// VRChat quit normally. An instance still open ends here as completed.
+pub fn handle(
+    conn: &Connection,
+    ctx: &mut HandlerContext,
+    timestamp: DateTime<Utc>,
+) -> Result<Option<VRChatEvent>, rusqlite::Error> {
+    let timestamp_ms = timestamp.timestamp_millis();
+
+    if let Some(session_id) = ctx.current_game_session_id.take() {
+        operations::end_game_session(conn, session_id, timestamp_ms, true)?;
+        tracing::info!(session_id, "VRChat quit, game session ended");
+    }
+
+    let instance_id = match *ctx.current_instance_id {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    operations::set_all_users_left_instance(conn, instance_id, timestamp_ms)?;
+    operations::end_instance(conn, instance_id, timestamp_ms)?;
+    ctx.clear_instance();
+
+    Ok(Some(VRChatEvent::InstanceEnded {
+        instance_id,
+        ended_at: timestamp_ms,
+        status: InstanceStatus::Completed,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::operations;
+    use crate::test_support::{
+        database_in_instance, instance_end, process_events, users_present, utc,
+    };
+    use crate::types::{InstanceStatus, LogEvent, VRChatEvent};
+
+    #[test]
+    fn test_quit_completes_the_instance_and_ends_the_session_cleanly() {
+        let (mut database, mut handler) = database_in_instance();
+        let instance_id = handler.current_instance_id().unwrap();
+        let quit_at = utc("2025-10-13T23:59:59Z");
+
+        let events = vec![
+            LogEvent::ApplicationQuit { timestamp: quit_at },
+            // The log ends right after the quit line
+            LogEvent::LogEnded {
+                timestamp: utc("2025-10-14T00:00:01Z"),
+            },
+        ];
+        let emitted = process_events(&mut database, &mut handler, events);
+        assert!(matches!(
+            emitted[..],
+            [VRChatEvent::InstanceEnded {
+                status: InstanceStatus::Completed,
+                ..
+            }]
+        ));
+
+        let conn = database.connection();
+        let quit_at = quit_at.timestamp_millis();
+        assert_eq!(
+            instance_end(conn, instance_id),
+            ("completed".to_string(), Some(quit_at))
+        );
+        assert_eq!(users_present(conn, instance_id), 0);
+
+        let session = operations::get_game_sessions(conn, None, None)
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            (session.ended_at, session.quit_cleanly),
+            (Some(quit_at), true)
+        );
+        assert_eq!(handler.state().game_session_id, None);
+    }
+}
@@ -16,11 +16,16 @@ pub fn handle(
     let instance_id = match *ctx.current_instance_id {
         Some(id) => id,
         None => {
-            eprintln!("AvatarChanged but no active instance");
+            tracing::warn!("AvatarChanged but no active instance");
             return Ok(None);
         }
     };
 
+    // Avatar lines only name the player, so blocked users are known from their join
+    if ctx.blocked_display_names.contains(display_name) {
+        return Ok(None);
+    }
+
     // Upsert avatar (avatar_id is currently unavailable from logs)
     let avatar_id = operations::upsert_avatar(conn, avatar_name, None, timestamp_ms)?;
 
@@ -31,9 +36,10 @@ pub fn handle(
             // Player not yet joined - store as pending
             ctx.pending_avatars
                 .insert(display_name.to_string(), (avatar_id, timestamp));
-            println!(
-                "Avatar changed before join, storing as pending: {} -> {}",
-                display_name, avatar_name
+            tracing::debug!(
+                display_name,
+                avatar_name,
+                "Avatar changed before join, storing as pending"
             );
             return Ok(None);
         }
@@ -42,7 +48,7 @@ pub fn handle(
     // Record avatar history
     operations::record_avatar_history(conn, instance_id, user_id, avatar_id, timestamp_ms)?;
 
-    println!("Avatar changed: {} -> {}", display_name, avatar_name);
+    tracing::debug!(display_name, avatar_name, "Avatar changed");
 
     Ok(Some(VRChatEvent::AvatarChanged {
         instance_id,
@@ -53,3 +59,35 @@ pub fn handle(
         changed_at: timestamp_ms,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{operations, Database};
+    use crate::event_handler::EventHandler;
+    use crate::test_support::{replay_log, FRIEND_USER_ID, SAMPLE_LOG};
+
+    #[test]
+    fn test_avatars_of_blocked_players_are_not_recorded() {
+        let mut database = Database::open_in_memory().unwrap();
+        operations::block_user(database.connection(), FRIEND_USER_ID, 0).unwrap();
+        // The friend switches avatars before and after joining
+        let mut lines = SAMPLE_LOG[..7].to_vec();
+        lines.push(
+            "2025.10.13 10:05:00 Debug      -  [Behaviour] Switching Friend to avatar Other Avatar",
+        );
+        let mut handler = EventHandler::new();
+        replay_log(&mut database, &mut handler, &lines);
+
+        let count = |sql: &str| -> i64 {
+            database
+                .connection()
+                .query_row(sql, (), |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count("SELECT COUNT(*) FROM avatar_history"), 0);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM avatars WHERE avatar_name = 'Other Avatar'"),
+            0
+        );
+    }
+}
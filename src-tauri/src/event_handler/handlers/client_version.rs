@@ -0,0 +1,66 @@
+use crate::db::operations;
+use crate::event_handler::HandlerContext;
+use crate::types::VRChatEvent;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+pub fn handle(
+    conn: &Connection,
+    ctx: &HandlerContext,
+    _timestamp: DateTime<Utc>,
+    version: &str,
+) -> Result<Option<VRChatEvent>, rusqlite::Error> {
+    let session_id = match *ctx.current_game_session_id {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    operations::set_game_session_version(conn, session_id, version)?;
+    tracing::info!(session_id, version, "VRChat version");
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{operations, Database};
+    use crate::event_handler::EventHandler;
+    use crate::test_support::{database_in_instance, process_events, utc};
+    use crate::types::LogEvent;
+
+    fn client_version(version: &str) -> LogEvent {
+        LogEvent::ClientVersion {
+            timestamp: utc("2025-10-13T09:53:05Z"),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_version_is_stored_on_the_session() {
+        let (mut database, mut handler) = database_in_instance();
+        process_events(
+            &mut database,
+            &mut handler,
+            vec![client_version("2025.3.4p2-1677--Release")],
+        );
+
+        let session = operations::get_game_sessions(database.connection(), None, None)
+            .unwrap()
+            .remove(0);
+        assert_eq!(session.version.as_deref(), Some("2025.3.4p2-1677--Release"));
+    }
+
+    #[test]
+    fn test_version_without_session_is_ignored() {
+        let mut database = Database::open_in_memory().unwrap();
+        let mut handler = EventHandler::new();
+
+        let emitted = process_events(&mut database, &mut handler, vec![client_version("1")]);
+        assert!(emitted.is_empty());
+        assert!(
+            operations::get_game_sessions(database.connection(), None, None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -31,9 +31,10 @@ pub fn handle(
     let instance_user_id = match ctx.instance_user_ids.remove(&user_id) {
         Some(iuid) => iuid,
         None => {
-            eprintln!(
-                "Warning: Player {} (user_id {}) not found in instance_user_ids",
-                display_name, user_id
+            tracing::warn!(
+                display_name,
+                user_id,
+                "Player not found in instance_user_ids"
             );
             return Ok(None);
         }
@@ -46,7 +47,7 @@ pub fn handle(
         operations::set_all_users_left_instance(conn, instance_id, timestamp_ms)?;
         operations::end_instance(conn, instance_id, timestamp_ms)?;
 
-        println!("Local player left, instance {} ended", instance_id);
+        tracing::info!(instance_id, "Local player left, instance ended");
 
         // Clear all state
         *ctx.current_instance_id = None;
@@ -54,6 +55,7 @@ pub fn handle(
         ctx.instance_user_ids.clear();
         ctx.display_name_to_user_id.clear();
         ctx.pending_avatars.clear();
+        ctx.blocked_display_names.clear();
 
         Ok(Some(VRChatEvent::InstanceEnded {
             instance_id,
@@ -63,7 +65,7 @@ pub fn handle(
     } else {
         // Remote player is leaving
         operations::set_user_left_instance(conn, instance_user_id, timestamp_ms)?;
-        println!("Player {} left (destroying)", display_name);
+        tracing::debug!(display_name, "Player left (destroying)");
 
         Ok(Some(VRChatEvent::UserLeft {
             instance_id,
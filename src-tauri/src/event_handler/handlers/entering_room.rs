@@ -16,7 +16,7 @@ pub fn handle(
     let instance_id = match *ctx.current_instance_id {
         Some(id) => id,
         None => {
-            eprintln!("EnteringRoom but no active instance");
+            tracing::warn!("EnteringRoom but no active instance");
             return Ok(None);
         }
     };
@@ -34,10 +34,7 @@ pub fn handle(
     // Link world_name_history to the instance
     operations::update_instance_world_name_history(conn, instance_id, world_name_history_id)?;
 
-    println!(
-        "Updated world name for instance {}: {}",
-        instance_id, world_name
-    );
+    tracing::debug!(instance_id, world_name, "Updated world name");
 
     Ok(Some(VRChatEvent::WorldNameUpdated {
         instance_id,
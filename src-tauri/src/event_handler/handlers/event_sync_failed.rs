@@ -14,13 +14,13 @@ pub fn handle(
     let instance_id = match *ctx.current_instance_id {
         Some(id) => id,
         None => {
-            eprintln!("EventSyncFailed without active instance");
+            tracing::warn!("EventSyncFailed without active instance");
             return Ok(None);
         }
     };
 
     operations::update_instance_status(conn, instance_id, InstanceStatus::SyncFailed)?;
-    println!("Instance {} marked as sync_failed", instance_id);
+    tracing::info!(instance_id, "Instance marked as sync_failed");
 
     Ok(Some(VRChatEvent::InstanceSyncFailed {
         instance_id,
@@ -16,7 +16,7 @@ pub fn handle(
     let my_account_id = match *ctx.current_my_account_id {
         Some(id) => id,
         None => {
-            eprintln!("Cannot join world: no local account authenticated");
+            tracing::warn!("Cannot join world: no local account authenticated");
             return Ok(None);
         }
     };
@@ -24,9 +24,9 @@ pub fn handle(
     // End previous instance if exists (mark as interrupted)
     if let Some(prev_instance_id) = *ctx.current_instance_id {
         operations::update_instance_status(conn, prev_instance_id, InstanceStatus::Interrupted)?;
-        println!(
-            "Previous instance {} marked as interrupted",
-            prev_instance_id
+        tracing::info!(
+            instance_id = prev_instance_id,
+            "Previous instance marked as interrupted"
         );
     }
 
@@ -35,19 +35,39 @@ pub fn handle(
     ctx.instance_user_ids.clear();
     ctx.display_name_to_user_id.clear();
     ctx.pending_avatars.clear();
+    ctx.blocked_display_names.clear();
 
     // Upsert world (without world name yet)
     let world_db_id = operations::upsert_world(conn, world_id, timestamp_ms)?;
 
+    // The same join read from another copy of the log (e.g. an imported one) is already
+    // recorded, so the events until the next join are ignored
+    if let Some((existing_id, _, _)) =
+        operations::find_instance(conn, my_account_id, world_db_id, instance_id, timestamp_ms)?
+    {
+        *ctx.current_instance_id = None;
+        tracing::info!(
+            instance_id = existing_id,
+            world_id,
+            "Instance already recorded, skipping"
+        );
+        return Ok(None);
+    }
+
     // Create new instance (world_name_at_join_id will be set later in entering_room)
     let new_instance_id =
         operations::create_instance(conn, my_account_id, world_db_id, instance_id, timestamp_ms)?;
 
+    if let Some(session_id) = *ctx.current_game_session_id {
+        operations::set_instance_game_session(conn, new_instance_id, session_id)?;
+    }
+
     *ctx.current_instance_id = Some(new_instance_id);
 
-    println!(
-        "Created new instance: {} in world: {}",
-        new_instance_id, world_id
+    tracing::info!(
+        instance_id = new_instance_id,
+        world_id,
+        "Created new instance"
     );
 
     Ok(Some(VRChatEvent::InstanceStarted {
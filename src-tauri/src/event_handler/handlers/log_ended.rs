@@ -0,0 +1,114 @@
+use crate::db::operations;
+use crate::event_handler::HandlerContext;
+use crate::types::{InstanceStatus, VRChatEvent};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+/// The log ended without OnApplicationQuit (VRChat crashed or was killed).
+/// An instance the local player was still in is closed at the last logged time,
+/// with everyone still present marked as left.
+pub fn handle(
+    conn: &Connection,
+    ctx: &mut HandlerContext,
+    timestamp: DateTime<Utc>,
+) -> Result<Option<VRChatEvent>, rusqlite::Error> {
+    let timestamp_ms = timestamp.timestamp_millis();
+
+    if let Some(session_id) = ctx.current_game_session_id.take() {
+        operations::end_game_session(conn, session_id, timestamp_ms, false)?;
+    }
+
+    let instance_id = match *ctx.current_instance_id {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    operations::set_all_users_left_instance(conn, instance_id, timestamp_ms)?;
+    operations::update_instance_status(conn, instance_id, InstanceStatus::Crashed)?;
+    operations::end_instance(conn, instance_id, timestamp_ms)?;
+
+    tracing::warn!(
+        instance_id,
+        "Log ended while in an instance, marked as crashed"
+    );
+    ctx.clear_instance();
+
+    Ok(Some(VRChatEvent::InstanceEnded {
+        instance_id,
+        ended_at: timestamp_ms,
+        status: InstanceStatus::Crashed,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::operations;
+    use crate::event_handler::EventHandler;
+    use crate::test_support::{
+        database_in_instance, instance_end, process_events, users_present, utc,
+    };
+    use crate::types::{InstanceStatus, LogEvent, VRChatEvent};
+
+    #[test]
+    fn test_log_end_closes_instance_as_crashed() {
+        let (mut database, mut handler) = database_in_instance();
+        let instance_id = handler.current_instance_id().unwrap();
+        let ended_at = utc("2025-10-13T10:00:00Z");
+
+        let emitted = process_events(
+            &mut database,
+            &mut handler,
+            vec![LogEvent::LogEnded {
+                timestamp: ended_at,
+            }],
+        );
+        assert!(matches!(
+            emitted[..],
+            [VRChatEvent::InstanceEnded {
+                status: InstanceStatus::Crashed,
+                ..
+            }]
+        ));
+
+        let conn = database.connection();
+        let ended_at = ended_at.timestamp_millis();
+        assert_eq!(
+            instance_end(conn, instance_id),
+            ("crashed".to_string(), Some(ended_at))
+        );
+        assert_eq!(users_present(conn, instance_id), 0);
+
+        let session = operations::get_game_sessions(conn, None, None)
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            (session.ended_at, session.quit_cleanly),
+            (Some(ended_at), false)
+        );
+
+        let state = handler.state();
+        assert_eq!((state.instance_id, state.game_session_id), (None, None));
+        assert_eq!(state.users_present, 0);
+    }
+
+    #[test]
+    fn test_log_end_outside_an_instance_only_ends_the_session() {
+        let mut database = crate::db::Database::open_in_memory().unwrap();
+        let mut handler = EventHandler::new();
+        let events = vec![
+            LogEvent::LogStarted {
+                timestamp: utc("2025-10-13T09:53:00Z"),
+                file_path: "output_log.txt".to_string(),
+            },
+            LogEvent::LogEnded {
+                timestamp: utc("2025-10-13T09:54:00Z"),
+            },
+        ];
+
+        assert!(process_events(&mut database, &mut handler, events).is_empty());
+        let session = operations::get_game_sessions(database.connection(), None, None)
+            .unwrap()
+            .remove(0);
+        assert!(session.ended_at.is_some());
+    }
+}
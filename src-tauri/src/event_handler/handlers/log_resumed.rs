@@ -0,0 +1,126 @@
+use crate::db::operations;
+use crate::event_handler::HandlerContext;
+use crate::types::{InstanceStatus, VRChatEvent};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+/// A log reported as ended because it stopped growing was written to again, so VRChat
+/// had only been idle. Undoes what `LogEnded` closed at `ended_at`: the game session and
+/// the instance marked as crashed, with the players still in it.
+pub fn handle(
+    conn: &Connection,
+    ctx: &mut HandlerContext,
+    ended_at: DateTime<Utc>,
+    file_path: &str,
+) -> Result<Option<VRChatEvent>, rusqlite::Error> {
+    let ended_at_ms = ended_at.timestamp_millis();
+
+    let session_id = match operations::reopen_game_session(conn, file_path, ended_at_ms)? {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+    *ctx.current_game_session_id = operations::get_latest_running_game_session(conn)?;
+    tracing::info!(session_id, file_path, "Log resumed, game session reopened");
+
+    // An instance joined since then is kept
+    if ctx.current_instance_id.is_some() {
+        return Ok(None);
+    }
+
+    let instance_id = match operations::reopen_crashed_instance(conn, session_id, ended_at_ms)? {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+    ctx.restore_instance(conn, instance_id)?;
+    tracing::info!(instance_id, "Log resumed, instance reopened");
+
+    Ok(Some(VRChatEvent::InstanceReopened {
+        instance_id,
+        status: InstanceStatus::Active,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::operations;
+    use crate::test_support::{
+        database_in_instance, instance_end, process_events, replay_log, users_present, utc,
+        SAMPLE_LOG, SAMPLE_LOG_FILE,
+    };
+    use crate::types::{LogEvent, VRChatEvent};
+
+    #[test]
+    fn test_resumed_log_reopens_session_and_instance() {
+        let (mut database, mut handler) = database_in_instance();
+        let instance_id = handler.current_instance_id().unwrap();
+        let session_id = handler.state().game_session_id;
+        let ended_at = utc("2025-10-13T09:53:26Z");
+
+        let events = vec![
+            LogEvent::LogEnded {
+                timestamp: ended_at,
+            },
+            LogEvent::LogResumed {
+                ended_at,
+                file_path: SAMPLE_LOG_FILE.to_string(),
+            },
+        ];
+        let emitted = process_events(&mut database, &mut handler, events);
+        assert!(matches!(
+            emitted.last(),
+            Some(VRChatEvent::InstanceReopened { instance_id: id, .. }) if *id == instance_id
+        ));
+
+        let conn = database.connection();
+        assert_eq!(
+            instance_end(conn, instance_id),
+            ("active".to_string(), None)
+        );
+        assert_eq!(users_present(conn, instance_id), 2);
+        let session = operations::get_game_sessions(conn, None, None)
+            .unwrap()
+            .remove(0);
+        assert_eq!(session.ended_at, None);
+
+        let state = handler.state();
+        assert_eq!(state.instance_id, Some(instance_id));
+        assert_eq!(state.game_session_id, session_id);
+        assert_eq!(state.users_present, 2);
+
+        // Later lines are recorded in the reopened instance
+        replay_log(&mut database, &mut handler, &SAMPLE_LOG[6..]);
+        let conn = database.connection();
+        assert_eq!(
+            instance_end(conn, instance_id),
+            (
+                "completed".to_string(),
+                Some(utc("2025-10-14T00:30:00Z").timestamp_millis())
+            )
+        );
+        assert_eq!(
+            operations::get_screenshot_files(conn, false).unwrap().len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_resume_keeps_a_clean_quit() {
+        let (mut database, mut handler) = database_in_instance();
+        let instance_id = handler.current_instance_id().unwrap();
+        let quit_at = utc("2025-10-13T10:00:00Z");
+
+        let events = vec![
+            LogEvent::ApplicationQuit { timestamp: quit_at },
+            LogEvent::LogResumed {
+                ended_at: quit_at,
+                file_path: SAMPLE_LOG_FILE.to_string(),
+            },
+        ];
+        assert_eq!(process_events(&mut database, &mut handler, events).len(), 1);
+        assert_eq!(
+            instance_end(database.connection(), instance_id).0,
+            "completed"
+        );
+        assert_eq!(handler.state().game_session_id, None);
+    }
+}
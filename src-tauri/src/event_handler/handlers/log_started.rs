@@ -0,0 +1,79 @@
+use crate::db::operations;
+use crate::event_handler::HandlerContext;
+use crate::types::VRChatEvent;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+/// A new log file means a new VRChat launch
+pub fn handle(
+    conn: &Connection,
+    ctx: &mut HandlerContext,
+    timestamp: DateTime<Utc>,
+    file_path: &str,
+) -> Result<Option<VRChatEvent>, rusqlite::Error> {
+    let session_id = operations::start_game_session(conn, file_path, timestamp.timestamp_millis())?;
+    *ctx.current_game_session_id = Some(session_id);
+
+    tracing::info!(session_id, file_path, "Game session started");
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::{operations, Database};
+    use crate::event_handler::EventHandler;
+    use crate::test_support::{database_in_instance, process_events, utc, SAMPLE_LOG_FILE};
+    use crate::types::LogEvent;
+
+    fn log_started(file_path: &str, timestamp: &str) -> LogEvent {
+        LogEvent::LogStarted {
+            timestamp: utc(timestamp),
+            file_path: file_path.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_one_session_per_log_file() {
+        let mut database = Database::open_in_memory().unwrap();
+        let mut handler = EventHandler::new();
+
+        process_events(
+            &mut database,
+            &mut handler,
+            vec![log_started("output_log_a.txt", "2025-10-13T09:00:00Z")],
+        );
+        let first = handler.state().game_session_id;
+
+        // Reading the same file again (e.g. after a rescan) keeps its session
+        process_events(
+            &mut database,
+            &mut handler,
+            vec![
+                log_started("output_log_b.txt", "2025-10-14T09:00:00Z"),
+                log_started("output_log_a.txt", "2025-10-13T09:00:00Z"),
+            ],
+        );
+        assert_eq!(handler.state().game_session_id, first);
+
+        let sessions = operations::get_game_sessions(database.connection(), None, None).unwrap();
+        let files: Vec<&str> = sessions.iter().map(|s| s.log_file.as_str()).collect();
+        assert_eq!(files, ["output_log_b.txt", "output_log_a.txt"]);
+        assert_eq!(
+            sessions[1].started_at,
+            utc("2025-10-13T09:00:00Z").timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn test_instances_are_linked_to_the_session() {
+        let (database, handler) = database_in_instance();
+        let instance_id = handler.current_instance_id().unwrap();
+
+        let session = operations::get_instance_game_session(database.connection(), instance_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(session.log_file, SAMPLE_LOG_FILE);
+        assert_eq!(session.instance_count, 1);
+    }
+}
@@ -1,8 +1,14 @@
+pub mod application_quit;
 pub mod avatar_changed;
+pub mod client_version;
 pub mod destroying_player;
 pub mod entering_room;
 pub mod event_sync_failed;
 pub mod joining_world;
+pub mod log_ended;
+pub mod log_resumed;
+pub mod log_started;
 pub mod player_joined;
 pub mod screenshot_taken;
 pub mod user_authenticated;
+pub mod vr_mode_detected;
@@ -16,20 +16,28 @@ pub fn handle(
     let instance_id = match *ctx.current_instance_id {
         Some(id) => id,
         None => {
-            eprintln!("PlayerJoined but no active instance");
+            tracing::warn!("PlayerJoined but no active instance");
             return Ok(None);
         }
     };
 
     // Check for duplicate join
     if ctx.user_ids.contains_key(vrchat_user_id) {
-        eprintln!(
-            "Warning: User {} ({}) already joined this instance, ignoring duplicate join event",
-            display_name, vrchat_user_id
+        tracing::warn!(
+            display_name,
+            vrchat_user_id,
+            "User already joined this instance, ignoring duplicate join event"
         );
         return Ok(None);
     }
 
+    // Users on the privacy blocklist are never recorded, nor are their avatars
+    if operations::is_user_blocked(conn, vrchat_user_id)? {
+        ctx.pending_avatars.remove(display_name);
+        ctx.blocked_display_names.insert(display_name.to_string());
+        return Ok(None);
+    }
+
     // Upsert user in database
     let user_id = operations::upsert_user(conn, vrchat_user_id, display_name, timestamp_ms)?;
 
@@ -64,13 +72,14 @@ pub fn handle(
             )?;
 
             let avatar_name = operations::get_avatar_name(conn, avatar_id)?;
-            println!(
-                "Player joined: {} with pending avatar: {}",
-                display_name, avatar_name
+            tracing::debug!(
+                display_name,
+                avatar_name,
+                "Player joined with pending avatar"
             );
             (Some(avatar_id), Some(avatar_name))
         } else {
-            println!("Player joined: {}", display_name);
+            tracing::debug!(display_name, "Player joined");
             (None, None)
         };
 
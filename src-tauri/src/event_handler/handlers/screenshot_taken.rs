@@ -1,28 +1,56 @@
 use crate::db::operations;
 use crate::event_handler::HandlerContext;
-use crate::types::VRChatEvent;
+use crate::screenshot::{self, LinkCheck};
+use crate::types::{ScreenshotSource, VRChatEvent};
 use chrono::{DateTime, Utc};
 use rusqlite::Connection;
+use std::path::Path;
 
 pub fn handle(
     conn: &Connection,
-    ctx: &HandlerContext,
+    ctx: &mut HandlerContext,
     timestamp: DateTime<Utc>,
     file_path: &str,
 ) -> Result<Option<VRChatEvent>, rusqlite::Error> {
     let timestamp_ms = timestamp.timestamp_millis();
 
+    // Normally read before the transaction; events not prefetched read the file here
+    let metadata = ctx.screenshot_metadata.remove(file_path);
+
     let instance_id = match *ctx.current_instance_id {
         Some(id) => id,
         None => {
-            eprintln!("Screenshot taken but no active instance");
+            tracing::warn!("Screenshot taken but no active instance");
             return Ok(None);
         }
     };
 
-    let screenshot_id = operations::record_screenshot(conn, instance_id, file_path, timestamp_ms)?;
+    // The folder scanner may have picked up the file before the log line arrived
+    let screenshot_id = match operations::find_screenshot_by_path(conn, file_path)? {
+        Some(id) => {
+            operations::set_screenshot_instance(conn, id, instance_id)?;
+            operations::set_screenshot_source(conn, id, ScreenshotSource::Log)?;
+            id
+        }
+        None => operations::record_screenshot(
+            conn,
+            instance_id,
+            file_path,
+            timestamp_ms,
+            ScreenshotSource::Log,
+        )?,
+    };
+
+    let metadata = metadata
+        .unwrap_or_else(|| screenshot::read_file_metadata(Path::new(file_path), ctx.timezone));
+
+    // Embedded world info may point to another instance (e.g. log lines out of order)
+    let instance_id = match screenshot::apply_metadata(conn, screenshot_id, metadata)? {
+        Some(LinkCheck::Relinked { to, .. }) => to,
+        _ => instance_id,
+    };
 
-    println!("Screenshot recorded: {}", file_path);
+    tracing::debug!(file_path, "Screenshot recorded");
 
     Ok(Some(VRChatEvent::ScreenshotTaken {
         instance_id,
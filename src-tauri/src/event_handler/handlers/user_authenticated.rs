@@ -27,9 +27,11 @@ pub fn handle(
     *ctx.current_user_id = Some(user_id);
     ctx.user_ids.insert(vrchat_user_id.to_string(), user_id);
 
-    println!(
-        "Local player authenticated: {} (my_account_id: {}, user_id: {})",
-        display_name, my_account_id, user_id
+    tracing::info!(
+        display_name,
+        my_account_id,
+        user_id,
+        "Local player authenticated"
     );
 
     Ok(Some(VRChatEvent::UserAuthenticated {
@@ -0,0 +1,50 @@
+use crate::db::operations;
+use crate::event_handler::HandlerContext;
+use crate::types::VRChatEvent;
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+pub fn handle(
+    conn: &Connection,
+    ctx: &HandlerContext,
+    _timestamp: DateTime<Utc>,
+    vr: bool,
+) -> Result<Option<VRChatEvent>, rusqlite::Error> {
+    let session_id = match *ctx.current_game_session_id {
+        Some(id) => id,
+        None => return Ok(None),
+    };
+
+    operations::set_game_session_vr_mode(conn, session_id, vr)?;
+    tracing::info!(session_id, vr, "VRChat mode detected");
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::db::operations;
+    use crate::test_support::{database_in_instance, process_events, utc};
+    use crate::types::LogEvent;
+
+    #[test]
+    fn test_vr_mode_is_stored_on_the_session() {
+        let (mut database, mut handler) = database_in_instance();
+        let vr_mode = |database: &crate::db::Database| {
+            operations::get_game_sessions(database.connection(), None, None)
+                .unwrap()
+                .remove(0)
+                .vr_mode
+        };
+        assert_eq!(vr_mode(&database), None);
+
+        for vr in [false, true] {
+            let event = LogEvent::VrModeDetected {
+                timestamp: utc("2025-10-13T09:53:06Z"),
+                vr,
+            };
+            process_events(&mut database, &mut handler, vec![event]);
+            assert_eq!(vr_mode(&database), Some(vr));
+        }
+    }
+}
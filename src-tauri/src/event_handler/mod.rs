@@ -1,4 +1,4 @@
 mod handler;
 mod handlers;
 
-pub use handler::{EventHandler, HandlerContext};
+pub use handler::{EventHandler, HandlerContext, HandlerState};
@@ -0,0 +1,179 @@
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+/// Instance ID tags whose value is the owner (usr_xxx or grp_xxx)
+const OWNER_TAGS: &[&str] = &["hidden", "friends", "private", "group"];
+
+/// Replace identities with pseudonyms so exports can be shared
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AnonymizeOptions {
+    #[serde(skip_serializing)]
+    pub salt: Option<String>, // Same salt, same pseudonyms across exports (random if omitted)
+    pub strip_instance_owners: bool, // Drop owner IDs from instance IDs instead of replacing them
+    pub strip_screenshot_paths: bool, // Keep only the file name of screenshot paths
+}
+
+/// Applies `AnonymizeOptions` to exported values (passes them through when not anonymizing)
+#[derive(Debug, Clone)]
+pub struct Anonymizer {
+    salt: Option<String>,
+    strip_instance_owners: bool,
+    strip_screenshot_paths: bool,
+}
+
+impl Anonymizer {
+    pub fn new(options: Option<&AnonymizeOptions>) -> Self {
+        Self {
+            salt: options.map(|o| o.salt.clone().unwrap_or_else(random_salt)),
+            strip_instance_owners: options.is_some_and(|o| o.strip_instance_owners),
+            strip_screenshot_paths: options.is_some_and(|o| o.strip_screenshot_paths),
+        }
+    }
+
+    /// Stable pseudonym in the same format (`usr_` followed by a UUID)
+    pub fn user_id(&self, user_id: String) -> String {
+        let Some(digest) = self.digest("user", &user_id) else {
+            return user_id;
+        };
+        let hex = to_hex(&digest[..16]);
+        format!(
+            "usr_{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+
+    /// Stable pseudonym per name, so name histories keep their shape
+    pub fn display_name(&self, display_name: String) -> String {
+        match self.digest("name", &display_name) {
+            Some(digest) => format!("Player {}", to_hex(&digest[..4])),
+            None => display_name,
+        }
+    }
+
+    /// Replace or drop the owner of an instance ID (`12345~friends(usr_xxx)~region(jp)`)
+    pub fn instance_id(&self, instance_id: String) -> String {
+        if self.salt.is_none() {
+            return instance_id;
+        }
+
+        instance_id
+            .split('~')
+            .map(|part| match part.split_once('(') {
+                Some((tag, value)) if OWNER_TAGS.contains(&tag) => {
+                    let owner = value.trim_end_matches(')');
+                    if self.strip_instance_owners {
+                        tag.to_string()
+                    } else if owner.starts_with("usr_") {
+                        format!("{}({})", tag, self.user_id(owner.to_string()))
+                    } else {
+                        part.to_string()
+                    }
+                }
+                _ => part.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("~")
+    }
+
+    /// Keep only the file name when screenshot paths are stripped
+    pub fn file_path(&self, file_path: String) -> String {
+        if self.salt.is_none() || !self.strip_screenshot_paths {
+            return file_path;
+        }
+        file_path
+            .rsplit(['\\', '/'])
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Notes and captions are free text that may name people, so they are left out
+    pub fn free_text(&self, text: Option<String>) -> Option<String> {
+        text.filter(|_| self.salt.is_none())
+    }
+
+    fn digest(&self, kind: &str, value: &str) -> Option<[u8; 32]> {
+        let salt = self.salt.as_ref()?;
+        let digest = Sha256::new()
+            .chain_update(salt)
+            .chain_update([0])
+            .chain_update(kind)
+            .chain_update([0])
+            .chain_update(value)
+            .finalize();
+        Some(digest.into())
+    }
+}
+
+/// RandomState is seeded from the OS, which is enough for a one-off salt
+fn random_salt() -> String {
+    let state = RandomState::new();
+    format!("{:016x}{:016x}", state.hash_one(1u8), state.hash_one(2u8))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn anonymizer(strip: bool) -> Anonymizer {
+        Anonymizer::new(Some(&AnonymizeOptions {
+            salt: Some("salt".to_string()),
+            strip_instance_owners: strip,
+            strip_screenshot_paths: strip,
+        }))
+    }
+
+    #[test]
+    fn test_pseudonyms_are_stable() {
+        let a = anonymizer(false);
+        let user_id = "usr_00000000-0000-0000-0000-000000000001".to_string();
+
+        let pseudonym = a.user_id(user_id.clone());
+        assert_ne!(pseudonym, user_id);
+        assert_eq!(pseudonym.len(), user_id.len());
+        assert_eq!(pseudonym, anonymizer(true).user_id(user_id.clone()));
+        assert_ne!(a.display_name("Alice".into()), a.display_name("Bob".into()));
+
+        let other_salt = Anonymizer::new(Some(&AnonymizeOptions::default()));
+        assert_ne!(other_salt.user_id(user_id), pseudonym);
+    }
+
+    #[test]
+    fn test_instance_owners() {
+        let instance_id = "12345~friends(usr_a)~region(jp)".to_string();
+        let replaced = anonymizer(false).instance_id(instance_id.clone());
+        assert!(replaced.starts_with("12345~friends(usr_"));
+        assert!(!replaced.contains("usr_a)"));
+        assert!(replaced.ends_with("~region(jp)"));
+
+        assert_eq!(
+            anonymizer(true).instance_id(instance_id.clone()),
+            "12345~friends~region(jp)"
+        );
+        assert_eq!(
+            anonymizer(false).instance_id("1~group(grp_x)~groupAccessType(public)".into()),
+            "1~group(grp_x)~groupAccessType(public)"
+        );
+        assert_eq!(
+            Anonymizer::new(None).instance_id(instance_id.clone()),
+            instance_id
+        );
+    }
+
+    #[test]
+    fn test_file_path() {
+        let path = r"C:\Users\me\Pictures\VRChat\2025-10\VRChat_1.png".to_string();
+        assert_eq!(anonymizer(true).file_path(path.clone()), "VRChat_1.png");
+        assert_eq!(anonymizer(false).file_path(path.clone()), path);
+    }
+}
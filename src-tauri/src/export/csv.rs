@@ -0,0 +1,352 @@
+use super::anonymize::Anonymizer;
+use super::{write_atomically, ExportOptions};
+use crate::db::operations::INSTANCE_FILTER_SQL;
+use crate::types::InstanceIdInfo;
+use chrono::DateTime;
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use std::borrow::Cow;
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+/// Lets Excel detect UTF-8 (world and display names are often Japanese)
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Which table to export as CSV
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CsvKind {
+    Instances,
+    Participants,
+    Screenshots,
+}
+
+/// Export instances, participants or screenshots as CSV, with times in `timezone`.
+/// Returns the number of data rows written.
+pub fn export_csv(
+    conn: &Connection,
+    path: &Path,
+    kind: CsvKind,
+    options: &ExportOptions,
+    timezone: Tz,
+) -> Result<usize, String> {
+    let anonymizer = Anonymizer::new(options.anonymize.as_ref());
+
+    write_atomically(path, |out| {
+        out.write_all(UTF8_BOM)?;
+
+        match kind {
+            CsvKind::Instances => write_instances(conn, out, options, &anonymizer, timezone),
+            CsvKind::Participants => write_participants(conn, out, options, &anonymizer, timezone),
+            CsvKind::Screenshots => write_screenshots(conn, out, options, &anonymizer, timezone),
+        }
+    })
+}
+
+fn write_instances(
+    conn: &Connection,
+    out: &mut impl Write,
+    options: &ExportOptions,
+    anonymizer: &Anonymizer,
+    timezone: Tz,
+) -> Result<usize, Box<dyn Error>> {
+    let sql = format!(
+        "SELECT w.world_id, COALESCE(wnh.world_name, w.world_name), i.instance_id,
+                i.started_at, i.ended_at, i.status,
+                (SELECT COUNT(*) FROM instance_users iu WHERE iu.instance_id = i.id)
+                    + i.purged_participants
+         FROM instances i
+         JOIN worlds w ON i.world_id = w.id
+         LEFT JOIN world_name_history wnh ON i.world_name_at_join_id = wnh.id
+         WHERE {}
+         ORDER BY i.started_at, i.id",
+        INSTANCE_FILTER_SQL
+    );
+
+    write_row(
+        out,
+        &[
+            "world_id",
+            "world_name",
+            "instance_id",
+            "access_type",
+            "region",
+            "started_at",
+            "ended_at",
+            "duration_minutes",
+            "status",
+            "player_count",
+        ],
+    )?;
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(&options.filter.params())?;
+    let mut count = 0;
+
+    while let Some(row) = rows.next()? {
+        let instance_id = anonymizer.instance_id(row.get(2)?);
+        let started_at: i64 = row.get(3)?;
+        let ended_at: Option<i64> = row.get(4)?;
+        let info = InstanceIdInfo::parse(&instance_id);
+
+        write_row(
+            out,
+            &[
+                &row.get::<_, String>(0)?,
+                &row.get::<_, String>(1)?,
+                &instance_id,
+                info.access_type.as_str(),
+                info.region.as_deref().unwrap_or_default(),
+                &format_time(Some(started_at), timezone),
+                &format_time(ended_at, timezone),
+                &format_minutes(started_at, ended_at),
+                &row.get::<_, String>(5)?,
+                &row.get::<_, i64>(6)?.to_string(),
+            ],
+        )?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn write_participants(
+    conn: &Connection,
+    out: &mut impl Write,
+    options: &ExportOptions,
+    anonymizer: &Anonymizer,
+    timezone: Tz,
+) -> Result<usize, Box<dyn Error>> {
+    let sql = format!(
+        "SELECT COALESCE(wnh.world_name, w.world_name), i.instance_id, i.started_at,
+                u.user_id, unh.display_name, iu.joined_at, iu.left_at
+         FROM instance_users iu
+         JOIN instances i ON iu.instance_id = i.id
+         JOIN worlds w ON i.world_id = w.id
+         LEFT JOIN world_name_history wnh ON i.world_name_at_join_id = wnh.id
+         JOIN users u ON iu.user_id = u.id
+         JOIN user_name_history unh ON iu.display_name_at_join_id = unh.id
+         WHERE {}
+         ORDER BY i.started_at, i.id, iu.joined_at, iu.id",
+        INSTANCE_FILTER_SQL
+    );
+
+    write_row(
+        out,
+        &[
+            "world_name",
+            "instance_id",
+            "instance_started_at",
+            "user_id",
+            "display_name_at_join",
+            "joined_at",
+            "left_at",
+            "dwell_minutes",
+        ],
+    )?;
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(&options.filter.params())?;
+    let mut count = 0;
+
+    while let Some(row) = rows.next()? {
+        let joined_at: i64 = row.get(5)?;
+        let left_at: Option<i64> = row.get(6)?;
+
+        write_row(
+            out,
+            &[
+                &row.get::<_, String>(0)?,
+                &anonymizer.instance_id(row.get(1)?),
+                &format_time(Some(row.get(2)?), timezone),
+                &anonymizer.user_id(row.get(3)?),
+                &anonymizer.display_name(row.get(4)?),
+                &format_time(Some(joined_at), timezone),
+                &format_time(left_at, timezone),
+                &format_minutes(joined_at, left_at),
+            ],
+        )?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+fn write_screenshots(
+    conn: &Connection,
+    out: &mut impl Write,
+    options: &ExportOptions,
+    anonymizer: &Anonymizer,
+    timezone: Tz,
+) -> Result<usize, Box<dyn Error>> {
+    let sql = format!(
+        "SELECT COALESCE(wnh.world_name, w.world_name), i.instance_id, s.file_path, s.taken_at,
+                s.source
+         FROM screenshots s
+         JOIN instances i ON s.instance_id = i.id
+         JOIN worlds w ON i.world_id = w.id
+         LEFT JOIN world_name_history wnh ON i.world_name_at_join_id = wnh.id
+         WHERE {}
+         ORDER BY s.taken_at, s.id",
+        INSTANCE_FILTER_SQL
+    );
+
+    write_row(
+        out,
+        &[
+            "world_name",
+            "instance_id",
+            "file_path",
+            "taken_at",
+            "source",
+        ],
+    )?;
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(&options.filter.params())?;
+    let mut count = 0;
+
+    while let Some(row) = rows.next()? {
+        write_row(
+            out,
+            &[
+                &row.get::<_, String>(0)?,
+                &anonymizer.instance_id(row.get(1)?),
+                &anonymizer.file_path(row.get(2)?),
+                &format_time(Some(row.get(3)?), timezone),
+                &row.get::<_, String>(4)?,
+            ],
+        )?;
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Write one CSV record (RFC 4180, CRLF line endings)
+fn write_row(out: &mut impl Write, fields: &[&str]) -> std::io::Result<()> {
+    let line = fields
+        .iter()
+        .map(|field| escape_field(field))
+        .collect::<Vec<_>>()
+        .join(",");
+
+    out.write_all(line.as_bytes())?;
+    out.write_all(b"\r\n")
+}
+
+fn escape_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\r', '\n']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Local time in a format spreadsheets parse as a date
+fn format_time(timestamp_ms: Option<i64>, timezone: Tz) -> String {
+    timestamp_ms
+        .and_then(DateTime::from_timestamp_millis)
+        .map(|dt| {
+            dt.with_timezone(&timezone)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn format_minutes(start_ms: i64, end_ms: Option<i64>) -> String {
+    end_ms
+        .map(|end| format!("{:.1}", (end - start_ms) as f64 / 60_000.0))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{sample_database, FRIEND_USER_ID};
+    use std::fs;
+
+    #[test]
+    fn test_escape_field() {
+        assert_eq!(escape_field("VRChat Home"), "VRChat Home");
+        assert_eq!(escape_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_field("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn test_export_csv_rows_per_kind() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let options = ExportOptions::default();
+
+        for (kind, rows) in [
+            (CsvKind::Instances, 1),
+            (CsvKind::Participants, 2),
+            (CsvKind::Screenshots, 1),
+        ] {
+            let path = dir.path().join("export.csv");
+            let written =
+                export_csv(database.connection(), &path, kind, &options, Tz::UTC).unwrap();
+            assert_eq!(written, rows, "{:?}", kind);
+
+            let bytes = fs::read(&path).unwrap();
+            assert!(bytes.starts_with(UTF8_BOM));
+            // Header plus one line per row
+            assert_eq!(String::from_utf8(bytes).unwrap().lines().count(), rows + 1);
+        }
+    }
+
+    #[test]
+    fn test_export_csv_applies_filter() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("instances.csv");
+        let mut options = ExportOptions::default();
+
+        options.filter.user_id = Some(FRIEND_USER_ID.to_string());
+        let written = export_csv(
+            database.connection(),
+            &path,
+            CsvKind::Instances,
+            &options,
+            Tz::UTC,
+        );
+        assert_eq!(written, Ok(1));
+
+        options.filter.world_name = Some("Other".to_string());
+        let written = export_csv(
+            database.connection(),
+            &path,
+            CsvKind::Instances,
+            &options,
+            Tz::UTC,
+        );
+        assert_eq!(written, Ok(0));
+    }
+
+    #[test]
+    fn test_export_csv_writes_times_in_the_given_zone() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("instances.csv");
+        let options = ExportOptions::default();
+
+        export_csv(
+            database.connection(),
+            &path,
+            CsvKind::Instances,
+            &options,
+            chrono_tz::Asia::Tokyo,
+        )
+        .unwrap();
+        let csv = fs::read_to_string(&path).unwrap();
+        assert!(
+            csv.contains(",2025-10-13 18:53:22,2025-10-14 09:30:00,"),
+            "{}",
+            csv
+        );
+    }
+}
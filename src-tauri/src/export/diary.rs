@@ -0,0 +1,720 @@
+use super::anonymize::{AnonymizeOptions, Anonymizer};
+use super::template::{escape_html, escape_markdown, Template};
+use super::write_atomically;
+use crate::db::operations::{InstanceFilter, EFFECTIVE_ENDED_AT_SQL, INSTANCE_FILTER_SQL};
+use crate::types::InstanceIdInfo;
+use chrono::{DateTime, NaiveDate};
+use chrono_tz::Tz;
+use rusqlite::{Connection, ToSql};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const ASSETS_DIR_NAME: &str = "assets";
+
+/// Built-in templates, overridden by files of the same name in the template directory
+const BUILTIN_TEMPLATES: [(&str, &str); 4] = [
+    ("entry.md", include_str!("templates/entry.md")),
+    ("entry.html", include_str!("templates/entry.html")),
+    ("index.md", include_str!("templates/index.md")),
+    ("index.html", include_str!("templates/index.html")),
+];
+
+/// How instances are split into diary files
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiaryGrouping {
+    #[default]
+    Day,
+    Session,
+}
+
+/// Options for the diary exporter
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DiaryOptions {
+    #[serde(flatten)]
+    pub filter: InstanceFilter,
+    pub group_by: DiaryGrouping,
+    pub html: bool,             // Also write static HTML next to the Markdown files
+    pub copy_screenshots: bool, // Copy screenshots into `assets/` instead of linking to them
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anonymize: Option<AnonymizeOptions>, // Pseudonyms for people, without notes and captions
+}
+
+/// Result of a diary export
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiarySummary {
+    pub entries: usize,
+    pub files: usize,
+    pub screenshots_copied: usize,
+}
+
+/// Data passed to the entry templates (one file per day or session)
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiaryEntry {
+    title: String,
+    date: String,
+    total_duration: String,
+    total_minutes: i64,
+    world_count: usize,
+    people_count: usize,
+    people: Vec<DiaryPerson>,
+    sessions: Vec<DiarySession>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiarySession {
+    world_id: String,
+    world_name: String,
+    instance_id: String,
+    access_type: &'static str,
+    region: Option<String>,
+    invite_url: String,
+    start_time: String,
+    end_time: String,
+    duration: String,
+    duration_minutes: i64,
+    note: Option<String>,
+    people: Vec<DiaryPerson>,
+    people_names: String,
+    screenshots: Vec<DiaryScreenshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiaryPerson {
+    user_id: String,
+    display_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiaryScreenshot {
+    file_name: String,
+    href: String, // Relative link from the diary file (URL-encoded)
+    taken_at: String,
+    caption: Option<String>,
+    favorite: bool,
+    cover: bool, // Chosen as cover of the instance
+}
+
+/// Data passed to the index templates
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiaryIndexItem {
+    title: String,
+    file_stem: String, // Entry file name without extension
+    total_duration: String,
+    world_count: usize,
+    people_count: usize,
+}
+
+/// Instance loaded from the database
+#[derive(Debug, Clone)]
+struct SessionRow {
+    id: i64,
+    my_account_id: i64,
+    world_id: String,
+    world_name: String,
+    instance_id: String,
+    started_at: i64,
+    ended_at: i64,
+    note: Option<String>,
+}
+
+/// Screenshot loaded from the database
+#[derive(Debug, Clone)]
+struct ScreenshotRow {
+    file_path: String,
+    taken_at: i64,
+    caption: Option<String>,
+    favorite: bool,
+    cover: bool,
+}
+
+/// Loaded templates, keyed by file name
+struct DiaryTemplates {
+    entry_md: Template,
+    entry_html: Template,
+    index_md: Template,
+    index_html: Template,
+}
+
+/// Write a Markdown (and optionally HTML) diary into `out_dir`, one file per day or session.
+/// Templates in `template_dir` replace the built-in ones with the same file name.
+/// Days and times are shown in `timezone`.
+pub fn export_diary(
+    conn: &Connection,
+    out_dir: &Path,
+    options: &DiaryOptions,
+    template_dir: Option<&Path>,
+    timezone: Tz,
+) -> Result<DiarySummary, String> {
+    let templates = load_templates(template_dir)?;
+    fs::create_dir_all(out_dir).map_err(|e| format!("Failed to create {:?}: {}", out_dir, e))?;
+
+    let sessions = get_sessions(conn, &options.filter)
+        .map_err(|e| format!("Failed to load sessions: {}", e))?;
+
+    let anonymizer = Anonymizer::new(options.anonymize.as_ref());
+    let mut summary = DiarySummary::default();
+    let mut index = Vec::new();
+
+    for (file_stem, group) in group_sessions(sessions, options.group_by, timezone) {
+        let entry = build_entry(
+            conn,
+            out_dir,
+            &group,
+            options,
+            &anonymizer,
+            timezone,
+            &mut summary,
+        )
+        .map_err(|e| format!("Failed to build diary entry {}: {}", file_stem, e))?;
+        let data = serde_json::to_value(&entry).map_err(|e| e.to_string())?;
+
+        write_rendered(
+            &out_dir.join(format!("{}.md", file_stem)),
+            &templates.entry_md,
+            &data,
+            escape_markdown,
+        )?;
+        summary.files += 1;
+        if options.html {
+            write_rendered(
+                &out_dir.join(format!("{}.html", file_stem)),
+                &templates.entry_html,
+                &data,
+                escape_html,
+            )?;
+            summary.files += 1;
+        }
+
+        index.push(DiaryIndexItem {
+            title: entry.title,
+            file_stem,
+            total_duration: entry.total_duration,
+            world_count: entry.world_count,
+            people_count: entry.people_count,
+        });
+        summary.entries += 1;
+    }
+
+    let data = serde_json::json!({ "entries": index });
+    write_rendered(
+        &out_dir.join("index.md"),
+        &templates.index_md,
+        &data,
+        escape_markdown,
+    )?;
+    summary.files += 1;
+    if options.html {
+        write_rendered(
+            &out_dir.join("index.html"),
+            &templates.index_html,
+            &data,
+            escape_html,
+        )?;
+        summary.files += 1;
+    }
+
+    Ok(summary)
+}
+
+fn load_templates(template_dir: Option<&Path>) -> Result<DiaryTemplates, String> {
+    let mut parsed = BTreeMap::new();
+
+    for (name, builtin) in BUILTIN_TEMPLATES {
+        let custom_path = template_dir
+            .map(|dir| dir.join(name))
+            .filter(|p| p.is_file());
+        let source = match &custom_path {
+            Some(path) => fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read template {:?}: {}", path, e))?,
+            None => builtin.to_string(),
+        };
+
+        let template =
+            Template::parse(&source).map_err(|e| format!("Invalid template {}: {}", name, e))?;
+        parsed.insert(name, template);
+    }
+
+    let mut take = |name: &str| parsed.remove(name).expect("built-in template");
+    Ok(DiaryTemplates {
+        entry_md: take("entry.md"),
+        entry_html: take("entry.html"),
+        index_md: take("index.md"),
+        index_html: take("index.html"),
+    })
+}
+
+fn write_rendered(
+    path: &Path,
+    template: &Template,
+    data: &serde_json::Value,
+    escape: fn(&str) -> String,
+) -> Result<(), String> {
+    let rendered = template.render(data, escape);
+    write_atomically(path, |out| {
+        std::io::Write::write_all(out, rendered.as_bytes())?;
+        Ok(())
+    })
+}
+
+fn get_sessions(conn: &Connection, filter: &InstanceFilter) -> rusqlite::Result<Vec<SessionRow>> {
+    let sql = format!(
+        "SELECT i.id, i.my_account_id, w.world_id, COALESCE(wnh.world_name, w.world_name),
+                i.instance_id, i.started_at, {}, i.note
+         FROM instances i
+         JOIN worlds w ON i.world_id = w.id
+         LEFT JOIN world_name_history wnh ON i.world_name_at_join_id = wnh.id
+         WHERE {}
+         ORDER BY i.started_at, i.id",
+        EFFECTIVE_ENDED_AT_SQL, INSTANCE_FILTER_SQL
+    );
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut params: Vec<(&str, &dyn ToSql)> = filter.params().to_vec();
+    params.push((":now", &now));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params.as_slice(), |row| {
+        Ok(SessionRow {
+            id: row.get(0)?,
+            my_account_id: row.get(1)?,
+            world_id: row.get(2)?,
+            world_name: row.get(3)?,
+            instance_id: row.get(4)?,
+            started_at: row.get(5)?,
+            ended_at: row.get(6)?,
+            note: row.get(7)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Split sessions into files, keyed by file stem (sorted chronologically)
+fn group_sessions(
+    sessions: Vec<SessionRow>,
+    grouping: DiaryGrouping,
+    timezone: Tz,
+) -> BTreeMap<String, Vec<SessionRow>> {
+    let mut groups: BTreeMap<String, Vec<SessionRow>> = BTreeMap::new();
+
+    for session in sessions {
+        let started_at = local_time(session.started_at, timezone);
+        let stem = match grouping {
+            DiaryGrouping::Day => started_at.format("%Y-%m-%d").to_string(),
+            DiaryGrouping::Session => {
+                format!("{}_{}", started_at.format("%Y-%m-%d_%H%M"), session.id)
+            }
+        };
+        groups.entry(stem).or_default().push(session);
+    }
+
+    groups
+}
+
+fn build_entry(
+    conn: &Connection,
+    out_dir: &Path,
+    group: &[SessionRow],
+    options: &DiaryOptions,
+    anonymizer: &Anonymizer,
+    timezone: Tz,
+    summary: &mut DiarySummary,
+) -> Result<DiaryEntry, Box<dyn std::error::Error>> {
+    let first = &group[0];
+    let date: NaiveDate = local_time(first.started_at, timezone).date_naive();
+
+    let mut sessions = Vec::with_capacity(group.len());
+    let mut people: Vec<DiaryPerson> = Vec::new();
+    let mut worlds = HashSet::new();
+    let mut total_minutes = 0;
+
+    for row in group {
+        let session_people: Vec<DiaryPerson> = get_people(conn, row)?
+            .into_iter()
+            .map(|person| DiaryPerson {
+                user_id: anonymizer.user_id(person.user_id),
+                display_name: anonymizer.display_name(person.display_name),
+            })
+            .collect();
+        for person in &session_people {
+            if !people.iter().any(|p| p.user_id == person.user_id) {
+                people.push(person.clone());
+            }
+        }
+        worlds.insert(row.world_id.clone());
+
+        let screenshots = get_screenshots(conn, row.id)?
+            .iter()
+            .map(|screenshot| {
+                link_screenshot(out_dir, screenshot, options, anonymizer, timezone, summary)
+            })
+            .collect();
+
+        let duration_minutes = (row.ended_at - row.started_at).max(0) / 60_000;
+        total_minutes += duration_minutes;
+
+        let info = InstanceIdInfo::parse(&row.instance_id);
+        let instance_id = anonymizer.instance_id(row.instance_id.clone());
+        sessions.push(DiarySession {
+            world_id: row.world_id.clone(),
+            world_name: row.world_name.clone(),
+            access_type: info.access_type.as_str(),
+            region: info.region,
+            invite_url: format!(
+                "https://vrchat.com/home/launch?worldId={}&instanceId={}",
+                row.world_id, instance_id
+            ),
+            instance_id,
+            start_time: local_time(row.started_at, timezone)
+                .format("%H:%M")
+                .to_string(),
+            end_time: local_time(row.ended_at, timezone)
+                .format("%H:%M")
+                .to_string(),
+            duration: format_duration(duration_minutes),
+            duration_minutes,
+            note: anonymizer.free_text(row.note.clone()),
+            people_names: session_people
+                .iter()
+                .map(|p| p.display_name.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            people: session_people,
+            screenshots,
+        });
+    }
+
+    let title = match options.group_by {
+        DiaryGrouping::Day => date.format("%Y-%m-%d").to_string(),
+        DiaryGrouping::Session => format!(
+            "{} {}",
+            local_time(first.started_at, timezone).format("%Y-%m-%d %H:%M"),
+            first.world_name
+        ),
+    };
+
+    Ok(DiaryEntry {
+        title,
+        date: date.format("%Y-%m-%d").to_string(),
+        total_duration: format_duration(total_minutes),
+        total_minutes,
+        world_count: worlds.len(),
+        people_count: people.len(),
+        people,
+        sessions,
+    })
+}
+
+/// Other users met in the instance, in order of first appearance (excluding the local account)
+fn get_people(conn: &Connection, row: &SessionRow) -> rusqlite::Result<Vec<DiaryPerson>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT u.user_id, unh.display_name
+         FROM instance_users iu
+         JOIN users u ON iu.user_id = u.id
+         JOIN user_name_history unh ON iu.display_name_at_join_id = unh.id
+         WHERE iu.instance_id = ?1
+           AND iu.user_id != (SELECT user_id FROM my_accounts WHERE id = ?2)
+         GROUP BY iu.user_id
+         ORDER BY MIN(iu.joined_at)",
+    )?;
+
+    let rows = stmt.query_map((row.id, row.my_account_id), |r| {
+        Ok(DiaryPerson {
+            user_id: r.get(0)?,
+            display_name: r.get(1)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+fn get_screenshots(conn: &Connection, instance_id: i64) -> rusqlite::Result<Vec<ScreenshotRow>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT s.file_path, s.taken_at, s.caption, s.favorite, s.id IS i.cover_screenshot_id
+         FROM screenshots s
+         JOIN instances i ON s.instance_id = i.id
+         WHERE s.instance_id = ?1 AND s.hidden = 0
+         ORDER BY s.taken_at, s.id",
+    )?;
+
+    let rows = stmt.query_map((instance_id,), |r| {
+        Ok(ScreenshotRow {
+            file_path: r.get(0)?,
+            taken_at: r.get(1)?,
+            caption: r.get(2)?,
+            favorite: r.get(3)?,
+            cover: r.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Copy the screenshot into `assets/` if requested, and link to it relative to `out_dir`
+fn link_screenshot(
+    out_dir: &Path,
+    screenshot: &ScreenshotRow,
+    options: &DiaryOptions,
+    anonymizer: &Anonymizer,
+    timezone: Tz,
+    summary: &mut DiarySummary,
+) -> DiaryScreenshot {
+    let file_path = screenshot.file_path.as_str();
+    let source = PathBuf::from(file_path);
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_path.to_string());
+
+    let mut target = source.clone();
+    if options.copy_screenshots {
+        match copy_to_assets(out_dir, &source, &file_name) {
+            Ok((copied, was_copied)) => {
+                if was_copied {
+                    summary.screenshots_copied += 1;
+                }
+                target = copied;
+            }
+            Err(e) => tracing::error!("Failed to copy screenshot {}: {}", file_path, e),
+        }
+    }
+
+    // A link to the original file would show where it is, so only its name is kept
+    let stripped_path = anonymizer.file_path(file_path.to_string());
+    let href = if target == source && stripped_path != file_path {
+        encode_href(&stripped_path)
+    } else {
+        match relative_path(out_dir, &target) {
+            Some(relative) => encode_href(&relative.to_string_lossy()),
+            None => format!("file:///{}", encode_href(file_path.trim_start_matches('/'))),
+        }
+    };
+
+    DiaryScreenshot {
+        file_name,
+        href,
+        taken_at: local_time(screenshot.taken_at, timezone)
+            .format("%H:%M")
+            .to_string(),
+        caption: anonymizer.free_text(screenshot.caption.clone()),
+        favorite: screenshot.favorite,
+        cover: screenshot.cover,
+    }
+}
+
+/// Copy a screenshot into `assets/`, returning its path there and whether it was copied now.
+/// A file with the same name is reused only if it has the same contents; other files with
+/// the same name (e.g. from another picture folder) get a numbered suffix.
+fn copy_to_assets(
+    out_dir: &Path,
+    source: &Path,
+    file_name: &str,
+) -> std::io::Result<(PathBuf, bool)> {
+    let assets_dir = out_dir.join(ASSETS_DIR_NAME);
+    fs::create_dir_all(&assets_dir)?;
+
+    let name = Path::new(file_name);
+    let stem = name.file_stem().map(|s| s.to_string_lossy().to_string());
+    let extension = name.extension().map(|e| e.to_string_lossy().to_string());
+    let contents = fs::read(source)?;
+
+    let mut n = 1;
+    loop {
+        let candidate = match (n, &stem, &extension) {
+            (1, _, _) => assets_dir.join(file_name),
+            (_, Some(stem), Some(extension)) => {
+                assets_dir.join(format!("{}-{}.{}", stem, n, extension))
+            }
+            _ => assets_dir.join(format!("{}-{}", file_name, n)),
+        };
+
+        if !candidate.exists() {
+            fs::write(&candidate, &contents)?;
+            return Ok((candidate, true));
+        }
+        if fs::read(&candidate)? == contents {
+            return Ok((candidate, false));
+        }
+        n += 1;
+    }
+}
+
+/// Path of `target` relative to `base`, or None if they share no root (e.g. another drive)
+fn relative_path(base: &Path, target: &Path) -> Option<PathBuf> {
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return None;
+    }
+
+    let mut relative = PathBuf::new();
+    for _ in common..base.len() {
+        relative.push("..");
+    }
+    for component in &target[common..] {
+        relative.push(component);
+    }
+    Some(relative)
+}
+
+/// Percent-encode characters that break Markdown and HTML links
+fn encode_href(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            '\\' => encoded.push('/'),
+            ' ' | '%' | '(' | ')' | '#' | '?' | '"' | '<' | '>' => {
+                encoded.push_str(&format!("%{:02X}", c as u32))
+            }
+            _ => encoded.push(c),
+        }
+    }
+    encoded
+}
+
+fn format_duration(minutes: i64) -> String {
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn local_time(timestamp_ms: i64, timezone: Tz) -> DateTime<Tz> {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .with_timezone(&timezone)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::operations;
+    use crate::test_support::{sample_database, MY_USER_ID};
+    use chrono_tz::Pacific::Honolulu;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path(
+                Path::new("/home/me/diary"),
+                Path::new("/home/me/Pictures/VRChat/a.png")
+            ),
+            Some(PathBuf::from("../Pictures/VRChat/a.png"))
+        );
+        assert_eq!(
+            relative_path(
+                Path::new("/home/me/diary"),
+                Path::new("/home/me/diary/assets/a.png")
+            ),
+            Some(PathBuf::from("assets/a.png"))
+        );
+        assert_eq!(
+            relative_path(Path::new("diary"), Path::new("pictures/a.png")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_encode_href() {
+        assert_eq!(
+            encode_href("..\\VRChat 2025\\a (1).png"),
+            "../VRChat%202025/a%20%281%29.png"
+        );
+    }
+
+    #[test]
+    fn test_export_diary_writes_day_entry() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let options = DiaryOptions {
+            html: true,
+            ..Default::default()
+        };
+
+        // 09:53 UTC is still the previous evening in Honolulu
+        let summary =
+            export_diary(database.connection(), dir.path(), &options, None, Honolulu).unwrap();
+        assert_eq!(summary.entries, 1);
+
+        let markdown = fs::read_to_string(dir.path().join("2025-10-12.md")).unwrap();
+        assert!(markdown.contains("## 23:53 - 14:30 Test World"));
+        assert!(dir.path().join("2025-10-12.html").exists());
+    }
+
+    #[test]
+    fn test_export_diary_escapes_notes() {
+        let database = sample_database();
+        operations::set_instance_note(database.connection(), 1, Some("<b>*fun*</b>")).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        export_diary(
+            database.connection(),
+            dir.path(),
+            &DiaryOptions::default(),
+            None,
+            Tz::UTC,
+        )
+        .unwrap();
+
+        let markdown = fs::read_to_string(dir.path().join("2025-10-13.md")).unwrap();
+        assert!(markdown.contains("\\<b\\>\\*fun\\*\\</b\\>"));
+    }
+
+    #[test]
+    fn test_export_diary_anonymized() {
+        let database = sample_database();
+        operations::set_instance_note(database.connection(), 1, Some("with Friend")).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let options = DiaryOptions {
+            anonymize: Some(AnonymizeOptions {
+                strip_screenshot_paths: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        export_diary(database.connection(), dir.path(), &options, None, Tz::UTC).unwrap();
+
+        let markdown = fs::read_to_string(dir.path().join("2025-10-13.md")).unwrap();
+        assert!(markdown.contains("- People: Player "));
+        assert!(!markdown.contains("Friend"));
+        assert!(!markdown.contains(MY_USER_ID));
+        assert!(markdown.contains("](VRChat_2025-10-13_10-00-00.000_1920x1080.png)"));
+    }
+
+    #[test]
+    fn test_copy_to_assets_keeps_files_with_the_same_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = dir.path().join("a").join("shot.png");
+        let second = dir.path().join("b").join("shot.png");
+        for (path, contents) in [(&first, b"first"), (&second, b"other")] {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        let out_dir = dir.path().join("diary");
+
+        let (copied, _) = copy_to_assets(&out_dir, &first, "shot.png").unwrap();
+        assert!(copied.ends_with("assets/shot.png"));
+        let (copied, was_copied) = copy_to_assets(&out_dir, &second, "shot.png").unwrap();
+        assert!(copied.ends_with("assets/shot-2.png"));
+        assert!(was_copied);
+
+        // Exporting again reuses the earlier copies
+        let (copied, was_copied) = copy_to_assets(&out_dir, &second, "shot.png").unwrap();
+        assert!(copied.ends_with("assets/shot-2.png"));
+        assert!(!was_copied);
+        assert_eq!(fs::read(out_dir.join("assets/shot.png")).unwrap(), b"first");
+    }
+}
@@ -0,0 +1,423 @@
+use super::anonymize::{AnonymizeOptions, Anonymizer};
+use super::write_atomically;
+use crate::db::operations::{InstanceFilter, EFFECTIVE_ENDED_AT_SQL, INSTANCE_FILTER_SQL};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use rusqlite::{Connection, ToSql};
+use sha2::{Digest, Sha256};
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+const PRODUCT_ID: &str = "-//VRCJournal//VRCJournal//EN";
+const UID_DOMAIN: &str = "vrcjournal";
+
+/// RFC 5545 recommends folding lines longer than 75 octets
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Options for the iCalendar exporter
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IcalOptions {
+    #[serde(flatten)]
+    pub filter: InstanceFilter,
+    pub merge_gap_minutes: Option<u32>, // Merge instances separated by at most this gap into one event
+    pub include_participants: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anonymize: Option<AnonymizeOptions>, // Pseudonyms for participants and instance owners
+}
+
+/// Instance row used to build events
+#[derive(Debug, Clone)]
+struct IcalInstance {
+    id: i64,
+    my_account_id: i64,
+    account_user_id: String, // usr_xxx of the local account
+    world_id: String,
+    world_name: String,
+    instance_id: String,
+    started_at: i64,
+    ended_at: i64,
+}
+
+/// Export instances as iCalendar events. Returns the number of events written.
+/// Descriptions of merged events list start times in `timezone`.
+///
+/// Each event's UID is derived from the identity of its first instance alone (account, world,
+/// instance ID and start time), so importing a newer export into a calendar updates events
+/// instead of duplicating them, even after the journal was imported into another database.
+pub fn export_ical(
+    conn: &Connection,
+    path: &Path,
+    options: &IcalOptions,
+    timezone: Tz,
+) -> Result<usize, String> {
+    let anonymizer = Anonymizer::new(options.anonymize.as_ref());
+
+    write_atomically(path, |out| {
+        let instances = get_instances(conn, &options.filter)?;
+        let groups = group_instances(instances, options.merge_gap_minutes);
+        let stamp = format_utc(Utc::now().timestamp_millis());
+
+        write_line(out, "BEGIN:VCALENDAR")?;
+        write_line(out, "VERSION:2.0")?;
+        write_line(out, &format!("PRODID:{}", PRODUCT_ID))?;
+        write_line(out, "CALSCALE:GREGORIAN")?;
+        write_line(out, "X-WR-CALNAME:VRChat")?;
+
+        for group in &groups {
+            let first = &group[0];
+            let last = &group[group.len() - 1];
+            let description = build_description(
+                conn,
+                group,
+                options.include_participants,
+                &anonymizer,
+                timezone,
+            )?;
+
+            write_line(out, "BEGIN:VEVENT")?;
+            write_line(out, &format!("UID:{}", event_uid(first)))?;
+            write_line(out, &format!("DTSTAMP:{}", stamp))?;
+            write_line(out, &format!("DTSTART:{}", format_utc(first.started_at)))?;
+            write_line(out, &format!("DTEND:{}", format_utc(last.ended_at)))?;
+            write_line(
+                out,
+                &format!("SUMMARY:{}", escape_text(&build_summary(group))),
+            )?;
+            write_line(out, &format!("DESCRIPTION:{}", escape_text(&description)))?;
+            if group.len() == 1 {
+                write_line(out, &format!("URL:{}", launch_url(first, &anonymizer)))?;
+            }
+            write_line(out, "END:VEVENT")?;
+        }
+
+        write_line(out, "END:VCALENDAR")?;
+
+        Ok(groups.len())
+    })
+}
+
+fn get_instances(
+    conn: &Connection,
+    filter: &InstanceFilter,
+) -> Result<Vec<IcalInstance>, rusqlite::Error> {
+    let sql = format!(
+        "SELECT i.id, i.my_account_id, au.user_id, w.world_id,
+                COALESCE(wnh.world_name, w.world_name), i.instance_id, i.started_at, {}
+         FROM instances i
+         JOIN my_accounts ma ON i.my_account_id = ma.id
+         JOIN users au ON ma.user_id = au.id
+         JOIN worlds w ON i.world_id = w.id
+         LEFT JOIN world_name_history wnh ON i.world_name_at_join_id = wnh.id
+         WHERE {}
+         ORDER BY i.my_account_id, i.started_at, i.id",
+        EFFECTIVE_ENDED_AT_SQL, INSTANCE_FILTER_SQL
+    );
+
+    let now = Utc::now().timestamp_millis();
+    let mut params: Vec<(&str, &dyn ToSql)> = filter.params().to_vec();
+    params.push((":now", &now));
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params.as_slice(), |row| {
+        Ok(IcalInstance {
+            id: row.get(0)?,
+            my_account_id: row.get(1)?,
+            account_user_id: row.get(2)?,
+            world_id: row.get(3)?,
+            world_name: row.get(4)?,
+            instance_id: row.get(5)?,
+            started_at: row.get(6)?,
+            ended_at: row.get(7)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Group consecutive instances of the same account whose gap is within `merge_gap_minutes`
+fn group_instances(
+    instances: Vec<IcalInstance>,
+    merge_gap_minutes: Option<u32>,
+) -> Vec<Vec<IcalInstance>> {
+    let max_gap_ms = merge_gap_minutes.map(|minutes| i64::from(minutes) * 60_000);
+    let mut groups: Vec<Vec<IcalInstance>> = Vec::new();
+
+    for instance in instances {
+        if let (Some(max_gap_ms), Some(group)) = (max_gap_ms, groups.last_mut()) {
+            let last = &group[group.len() - 1];
+            if last.my_account_id == instance.my_account_id
+                && instance.started_at - last.ended_at <= max_gap_ms
+            {
+                group.push(instance);
+                continue;
+            }
+        }
+        groups.push(vec![instance]);
+    }
+
+    groups
+}
+
+/// Stable event UID from the instance's natural key (row IDs differ between databases)
+fn event_uid(instance: &IcalInstance) -> String {
+    let digest = Sha256::new()
+        .chain_update(instance.account_user_id.as_bytes())
+        .chain_update([0])
+        .chain_update(instance.world_id.as_bytes())
+        .chain_update([0])
+        .chain_update(instance.instance_id.as_bytes())
+        .chain_update([0])
+        .chain_update(instance.started_at.to_be_bytes())
+        .finalize();
+    let hex: String = digest[..16].iter().map(|b| format!("{:02x}", b)).collect();
+
+    format!("instance-{}@{}", hex, UID_DOMAIN)
+}
+
+/// World name, or the distinct world names in visiting order for merged events
+fn build_summary(group: &[IcalInstance]) -> String {
+    let mut names: Vec<&str> = Vec::new();
+    for instance in group {
+        if !names.contains(&instance.world_name.as_str()) {
+            names.push(&instance.world_name);
+        }
+    }
+    names.join(" / ")
+}
+
+fn build_description(
+    conn: &Connection,
+    group: &[IcalInstance],
+    include_participants: bool,
+    anonymizer: &Anonymizer,
+    timezone: Tz,
+) -> Result<String, Box<dyn Error>> {
+    let mut sections = Vec::with_capacity(group.len());
+
+    for instance in group {
+        let mut section = String::new();
+        if group.len() > 1 {
+            section.push_str(&format!(
+                "{} {}\n",
+                format_local_time(instance.started_at, timezone),
+                instance.world_name
+            ));
+        }
+        section.push_str(&launch_url(instance, anonymizer));
+
+        if include_participants {
+            let names: Vec<String> = get_participant_names(conn, instance.id)?
+                .into_iter()
+                .map(|name| anonymizer.display_name(name))
+                .collect();
+            if !names.is_empty() {
+                section.push_str(&format!("\n\n{}", names.join(", ")));
+            }
+        }
+        sections.push(section);
+    }
+
+    Ok(sections.join("\n\n"))
+}
+
+/// Display names at join, in order of first appearance
+fn get_participant_names(conn: &Connection, instance_id: i64) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare(
+        "SELECT unh.display_name
+         FROM instance_users iu
+         JOIN user_name_history unh ON iu.display_name_at_join_id = unh.id
+         WHERE iu.instance_id = ?1
+         GROUP BY iu.user_id
+         ORDER BY MIN(iu.joined_at)",
+    )?;
+
+    let rows = stmt.query_map([instance_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// URL that opens the instance in VRChat
+fn launch_url(instance: &IcalInstance, anonymizer: &Anonymizer) -> String {
+    format!(
+        "https://vrchat.com/home/launch?worldId={}&instanceId={}",
+        instance.world_id,
+        anonymizer.instance_id(instance.instance_id.clone())
+    )
+}
+
+fn format_utc(timestamp_ms: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+fn format_local_time(timestamp_ms: i64, timezone: Tz) -> String {
+    DateTime::from_timestamp_millis(timestamp_ms)
+        .unwrap_or_default()
+        .with_timezone(&timezone)
+        .format("%H:%M")
+        .to_string()
+}
+
+/// Escape a TEXT property value
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Write a content line, folding it at character boundaries
+fn write_line(out: &mut impl Write, line: &str) -> std::io::Result<()> {
+    out.write_all(fold_line(line).as_bytes())?;
+    out.write_all(b"\r\n")
+}
+
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len() + line.len() / MAX_LINE_OCTETS * 3);
+    let mut line_octets = 0;
+
+    for c in line.chars() {
+        if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(c);
+        line_octets += c.len_utf8();
+    }
+
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{sample_database, MY_USER_ID};
+
+    fn instance(id: i64, started_at: i64, ended_at: i64) -> IcalInstance {
+        IcalInstance {
+            id,
+            my_account_id: 1,
+            account_user_id: "usr_1".to_string(),
+            world_id: "wrld_1".to_string(),
+            world_name: format!("World {}", id),
+            instance_id: "12345".to_string(),
+            started_at,
+            ended_at,
+        }
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+    }
+
+    #[test]
+    fn test_fold_line_keeps_multibyte_characters_intact() {
+        let line = format!("SUMMARY:{}", "ワールド".repeat(10));
+        let folded = fold_line(&line);
+
+        for part in folded.split("\r\n") {
+            assert!(part.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+
+    #[test]
+    fn test_group_instances_merges_within_gap() {
+        let minute = 60_000;
+        let instances = vec![
+            instance(1, 0, 10 * minute),
+            instance(2, 12 * minute, 20 * minute),
+            instance(3, 40 * minute, 50 * minute),
+        ];
+
+        let groups = group_instances(instances.clone(), Some(5));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].len(), 2);
+        assert_eq!(groups[1][0].id, 3);
+
+        assert_eq!(group_instances(instances, None).len(), 3);
+    }
+
+    #[test]
+    fn test_export_ical_event_per_instance() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.ics");
+        let options = IcalOptions {
+            include_participants: true,
+            ..Default::default()
+        };
+
+        let events = export_ical(database.connection(), &path, &options, Tz::UTC).unwrap();
+        assert_eq!(events, 1);
+
+        let calendar = std::fs::read_to_string(&path).unwrap();
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 1);
+        assert!(calendar.contains("SUMMARY:Test World"));
+        assert!(calendar.contains("Friend"));
+    }
+
+    #[test]
+    fn test_export_ical_anonymized() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.ics");
+        let options = IcalOptions {
+            include_participants: true,
+            anonymize: Some(AnonymizeOptions::default()),
+            ..Default::default()
+        };
+
+        export_ical(database.connection(), &path, &options, Tz::UTC).unwrap();
+
+        let calendar = std::fs::read_to_string(&path).unwrap().replace("\r\n ", "");
+        assert!(calendar.contains("Player "));
+        assert!(!calendar.contains("Friend"));
+        assert!(!calendar.contains(MY_USER_ID));
+    }
+
+    #[test]
+    fn test_event_uid_depends_on_instance_identity_only() {
+        let first = instance(1, 0, 10);
+        // The same instance under another row ID, as after an import
+        let imported = instance(7, 0, 10);
+        assert_eq!(event_uid(&first), event_uid(&imported));
+        assert_ne!(event_uid(&first), event_uid(&instance(1, 60_000, 70_000)));
+
+        // A merged event keeps the UID of its first instance
+        let merged = group_instances(vec![first.clone(), instance(2, 12, 20)], Some(5));
+        assert_eq!(merged.len(), 1);
+        assert_eq!(event_uid(&merged[0][0]), event_uid(&first));
+    }
+
+    #[test]
+    fn test_merged_description_uses_the_given_zone() {
+        let database = sample_database();
+        let group = [instance(1, 0, 10), instance(2, 60_000, 70_000)];
+
+        let description = build_description(
+            database.connection(),
+            &group,
+            false,
+            &Anonymizer::new(None),
+            chrono_tz::Asia::Tokyo,
+        )
+        .unwrap();
+        assert!(
+            description.starts_with("09:00 World 1\n"),
+            "{}",
+            description
+        );
+        assert!(
+            description.contains("\n\n09:01 World 2\n"),
+            "{}",
+            description
+        );
+    }
+}
@@ -0,0 +1,553 @@
+use super::anonymize::Anonymizer;
+use super::{write_atomically, ExportOptions};
+use crate::db::migrations;
+use crate::db::operations::INSTANCE_FILTER_SQL;
+use crate::types::ScreenshotSource;
+use chrono::Utc;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::io::Write;
+use std::path::Path;
+
+/// Identifies VRCJournal export documents
+pub const FORMAT_NAME: &str = "vrcjournal";
+
+/// Bumped whenever the document layout changes incompatibly
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Local account, referenced by instances through `user_id`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountRecord {
+    pub user_id: String, // usr_xxx
+    pub display_name: String,
+    pub first_authenticated_at: i64,
+    pub last_authenticated_at: i64,
+}
+
+/// Display name or world name valid during a period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NameHistoryRecord {
+    pub name: String,
+    pub first_seen_at: i64,
+    pub last_seen_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserRecord {
+    pub user_id: String, // usr_xxx
+    pub display_name: String,
+    pub first_seen_at: i64,
+    pub last_seen_at: i64,
+    pub name_history: Vec<NameHistoryRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorldRecord {
+    pub world_id: String, // wrld_xxx
+    pub world_name: String,
+    pub first_seen_at: i64,
+    pub last_seen_at: i64,
+    pub name_history: Vec<NameHistoryRecord>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cover_file_path: Option<String>, // Screenshot chosen as cover
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParticipantRecord {
+    pub user_id: String, // usr_xxx
+    pub display_name_at_join: String,
+    pub joined_at: i64,
+    pub left_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AvatarChangeRecord {
+    pub user_id: String, // usr_xxx
+    pub avatar_name: String,
+    pub avatar_id: Option<String>, // avtr_xxx (not available from logs yet)
+    pub changed_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotRecord {
+    pub file_path: String,
+    pub taken_at: i64,
+    #[serde(default)]
+    pub source: ScreenshotSource,
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub cover: bool, // Chosen as cover of the instance
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstanceRecord {
+    pub account_user_id: String, // usr_xxx of the local account
+    pub world_id: String,        // wrld_xxx
+    pub instance_id: String,
+    pub world_name_at_join: Option<String>,
+    pub started_at: i64,
+    pub ended_at: Option<i64>,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub participants: Vec<ParticipantRecord>,
+    #[serde(default)]
+    pub purged_participants: i64, // Participants erased by a privacy purge or retention rules
+    pub avatar_history: Vec<AvatarChangeRecord>,
+    pub screenshots: Vec<ScreenshotRecord>,
+}
+
+/// Complete document as read back by the importer
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JournalDocument {
+    pub format: String,
+    pub format_version: u32,
+    pub schema_version: i32,
+    pub exported_at: i64,
+    #[serde(default)]
+    pub anonymized: bool, // Identities are pseudonyms, so the document can't be merged
+    #[serde(default)]
+    pub accounts: Vec<AccountRecord>,
+    #[serde(default)]
+    pub users: Vec<UserRecord>,
+    #[serde(default)]
+    pub worlds: Vec<WorldRecord>,
+    #[serde(default)]
+    pub instances: Vec<InstanceRecord>,
+}
+
+/// Number of records written by an export
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportSummary {
+    pub accounts: usize,
+    pub users: usize,
+    pub worlds: usize,
+    pub instances: usize,
+    pub screenshots: usize,
+}
+
+/// Export the journal as a JSON document (see docs/export-format.md)
+pub fn export_json(
+    conn: &Connection,
+    path: &Path,
+    options: &ExportOptions,
+) -> Result<ExportSummary, String> {
+    write_atomically(path, |out| write_document(conn, out, options))
+}
+
+/// Stream the document record by record so memory use does not grow with the database
+fn write_document(
+    conn: &Connection,
+    out: &mut impl Write,
+    options: &ExportOptions,
+) -> Result<ExportSummary, Box<dyn Error>> {
+    let mut summary = ExportSummary::default();
+    let anonymizer = Anonymizer::new(options.anonymize.as_ref());
+
+    // The filter may name a participant
+    let mut written_options = options.clone();
+    written_options.filter.user_id = written_options
+        .filter
+        .user_id
+        .map(|user_id| anonymizer.user_id(user_id));
+
+    write!(
+        out,
+        "{{\"format\":{},\"formatVersion\":{},\"schemaVersion\":{},\"exportedAt\":{},\"anonymized\":{},\"options\":{}",
+        serde_json::to_string(FORMAT_NAME)?,
+        FORMAT_VERSION,
+        migrations::current_version(conn)?,
+        Utc::now().timestamp_millis(),
+        options.anonymize.is_some(),
+        serde_json::to_string(&written_options)?
+    )?;
+
+    summary.accounts = write_accounts(conn, out, options, &anonymizer)?;
+    summary.users = write_users(conn, out, options, &anonymizer)?;
+    summary.worlds = write_worlds(conn, out, options, &anonymizer)?;
+    (summary.instances, summary.screenshots) = write_instances(conn, out, options, &anonymizer)?;
+
+    writeln!(out, "}}")?;
+
+    Ok(summary)
+}
+
+/// Accounts to export (expects `my_accounts` aliased as `ma`).
+/// With a date range only accounts that have matching instances are kept.
+fn account_filter_sql() -> String {
+    format!(
+        "(:account IS NULL OR ma.id = :account)
+         AND ((:from IS NULL AND :to IS NULL)
+              OR ma.id IN (SELECT i.my_account_id FROM instances i WHERE {}))",
+        INSTANCE_FILTER_SQL
+    )
+}
+
+fn write_accounts(
+    conn: &Connection,
+    out: &mut impl Write,
+    options: &ExportOptions,
+    anonymizer: &Anonymizer,
+) -> Result<usize, Box<dyn Error>> {
+    let sql = format!(
+        "SELECT u.user_id, u.display_name, ma.first_authenticated_at, ma.last_authenticated_at
+         FROM my_accounts ma
+         JOIN users u ON ma.user_id = u.id
+         WHERE {}
+         ORDER BY ma.id",
+        account_filter_sql()
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query(&options.filter.params())?;
+    let mut array = JsonArray::begin(out, "accounts")?;
+
+    while let Some(row) = rows.next()? {
+        array.push(&AccountRecord {
+            user_id: anonymizer.user_id(row.get(0)?),
+            display_name: anonymizer.display_name(row.get(1)?),
+            first_authenticated_at: row.get(2)?,
+            last_authenticated_at: row.get(3)?,
+        })?;
+    }
+
+    array.end()
+}
+
+fn write_users(
+    conn: &Connection,
+    out: &mut impl Write,
+    options: &ExportOptions,
+    anonymizer: &Anonymizer,
+) -> Result<usize, Box<dyn Error>> {
+    let sql = format!(
+        "SELECT u.id, u.user_id, u.display_name, u.first_seen_at, u.last_seen_at
+         FROM users u
+         WHERE u.id IN (
+             SELECT iu.user_id FROM instance_users iu
+             JOIN instances i ON iu.instance_id = i.id
+             WHERE {filter}
+             UNION
+             SELECT ah.user_id FROM avatar_history ah
+             JOIN instances i ON ah.instance_id = i.id
+             WHERE {filter}
+             UNION
+             SELECT ma.user_id FROM my_accounts ma
+             WHERE {accounts}
+         )
+         ORDER BY u.id",
+        filter = INSTANCE_FILTER_SQL,
+        accounts = account_filter_sql()
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut history_stmt = conn.prepare(
+        "SELECT display_name, first_seen_at, last_seen_at
+         FROM user_name_history
+         WHERE user_id = ?1
+         ORDER BY first_seen_at",
+    )?;
+
+    let mut rows = stmt.query(&options.filter.params())?;
+    let mut array = JsonArray::begin(out, "users")?;
+
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let name_history = history_stmt
+            .query_map((id,), read_name_history)?
+            .map(|entry| {
+                entry.map(|entry| NameHistoryRecord {
+                    name: anonymizer.display_name(entry.name),
+                    ..entry
+                })
+            })
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        array.push(&UserRecord {
+            user_id: anonymizer.user_id(row.get(1)?),
+            display_name: anonymizer.display_name(row.get(2)?),
+            first_seen_at: row.get(3)?,
+            last_seen_at: row.get(4)?,
+            name_history,
+        })?;
+    }
+
+    array.end()
+}
+
+fn write_worlds(
+    conn: &Connection,
+    out: &mut impl Write,
+    options: &ExportOptions,
+    anonymizer: &Anonymizer,
+) -> Result<usize, Box<dyn Error>> {
+    let sql = format!(
+        "SELECT w.id, w.world_id, w.world_name, w.first_seen_at, w.last_seen_at,
+                (SELECT s.file_path FROM screenshots s WHERE s.id = w.cover_screenshot_id)
+         FROM worlds w
+         WHERE w.id IN (SELECT i.world_id FROM instances i WHERE {})
+         ORDER BY w.id",
+        INSTANCE_FILTER_SQL
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut history_stmt = conn.prepare(
+        "SELECT world_name, first_seen_at, last_seen_at
+         FROM world_name_history
+         WHERE world_id = ?1
+         ORDER BY first_seen_at",
+    )?;
+
+    let mut rows = stmt.query(&options.filter.params())?;
+    let mut array = JsonArray::begin(out, "worlds")?;
+
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+        let name_history = history_stmt
+            .query_map((id,), read_name_history)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        array.push(&WorldRecord {
+            world_id: row.get(1)?,
+            world_name: row.get(2)?,
+            first_seen_at: row.get(3)?,
+            last_seen_at: row.get(4)?,
+            name_history,
+            cover_file_path: row
+                .get::<_, Option<String>>(5)?
+                .map(|path| anonymizer.file_path(path)),
+        })?;
+    }
+
+    array.end()
+}
+
+/// Returns (instance count, screenshot count)
+fn write_instances(
+    conn: &Connection,
+    out: &mut impl Write,
+    options: &ExportOptions,
+    anonymizer: &Anonymizer,
+) -> Result<(usize, usize), Box<dyn Error>> {
+    let sql = format!(
+        "SELECT i.id, au.user_id, w.world_id, i.instance_id, wnh.world_name,
+                i.started_at, i.ended_at, i.status, i.note, i.purged_participants
+         FROM instances i
+         JOIN my_accounts ma ON i.my_account_id = ma.id
+         JOIN users au ON ma.user_id = au.id
+         JOIN worlds w ON i.world_id = w.id
+         LEFT JOIN world_name_history wnh ON i.world_name_at_join_id = wnh.id
+         WHERE {}
+         ORDER BY i.started_at, i.id",
+        INSTANCE_FILTER_SQL
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let mut participants_stmt = conn.prepare(
+        "SELECT u.user_id, unh.display_name, iu.joined_at, iu.left_at
+         FROM instance_users iu
+         JOIN users u ON iu.user_id = u.id
+         JOIN user_name_history unh ON iu.display_name_at_join_id = unh.id
+         WHERE iu.instance_id = ?1
+         ORDER BY iu.joined_at, iu.id",
+    )?;
+    let mut avatars_stmt = conn.prepare(
+        "SELECT u.user_id, a.avatar_name, a.avatar_id, ah.changed_at
+         FROM avatar_history ah
+         JOIN users u ON ah.user_id = u.id
+         JOIN avatars a ON ah.avatar_id = a.id
+         WHERE ah.instance_id = ?1
+         ORDER BY ah.changed_at, ah.id",
+    )?;
+    let mut screenshots_stmt = conn.prepare(
+        "SELECT s.file_path, s.taken_at, s.source, s.hidden, s.favorite, s.caption,
+                s.id IS i.cover_screenshot_id
+         FROM screenshots s
+         JOIN instances i ON s.instance_id = i.id
+         WHERE s.instance_id = ?1
+         ORDER BY s.taken_at, s.id",
+    )?;
+
+    let mut rows = stmt.query(&options.filter.params())?;
+    let mut array = JsonArray::begin(out, "instances")?;
+    let mut screenshot_count = 0;
+
+    while let Some(row) = rows.next()? {
+        let id: i64 = row.get(0)?;
+
+        let participants = participants_stmt
+            .query_map((id,), |r| {
+                Ok(ParticipantRecord {
+                    user_id: anonymizer.user_id(r.get(0)?),
+                    display_name_at_join: anonymizer.display_name(r.get(1)?),
+                    joined_at: r.get(2)?,
+                    left_at: r.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let avatar_history = avatars_stmt
+            .query_map((id,), |r| {
+                Ok(AvatarChangeRecord {
+                    user_id: anonymizer.user_id(r.get(0)?),
+                    avatar_name: r.get(1)?,
+                    avatar_id: r.get(2)?,
+                    changed_at: r.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let screenshots = screenshots_stmt
+            .query_map((id,), |r| {
+                Ok(ScreenshotRecord {
+                    file_path: anonymizer.file_path(r.get(0)?),
+                    taken_at: r.get(1)?,
+                    source: r.get(2)?,
+                    hidden: r.get(3)?,
+                    favorite: r.get(4)?,
+                    caption: anonymizer.free_text(r.get(5)?),
+                    cover: r.get(6)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        screenshot_count += screenshots.len();
+
+        array.push(&InstanceRecord {
+            account_user_id: anonymizer.user_id(row.get(1)?),
+            world_id: row.get(2)?,
+            instance_id: anonymizer.instance_id(row.get(3)?),
+            world_name_at_join: row.get(4)?,
+            started_at: row.get(5)?,
+            ended_at: row.get(6)?,
+            status: row.get(7)?,
+            note: anonymizer.free_text(row.get(8)?),
+            participants,
+            purged_participants: row.get(9)?,
+            avatar_history,
+            screenshots,
+        })?;
+    }
+
+    Ok((array.end()?, screenshot_count))
+}
+
+fn read_name_history(row: &rusqlite::Row) -> rusqlite::Result<NameHistoryRecord> {
+    Ok(NameHistoryRecord {
+        name: row.get(0)?,
+        first_seen_at: row.get(1)?,
+        last_seen_at: row.get(2)?,
+    })
+}
+
+/// Writes a keyed JSON array one element per line
+struct JsonArray<'a, W: Write> {
+    out: &'a mut W,
+    len: usize,
+}
+
+impl<'a, W: Write> JsonArray<'a, W> {
+    fn begin(out: &'a mut W, key: &str) -> Result<Self, Box<dyn Error>> {
+        write!(out, ",\n{}:[", serde_json::to_string(key)?)?;
+        Ok(Self { out, len: 0 })
+    }
+
+    fn push(&mut self, value: &impl Serialize) -> Result<(), Box<dyn Error>> {
+        if self.len > 0 {
+            self.out.write_all(b",")?;
+        }
+        self.out.write_all(b"\n")?;
+        serde_json::to_writer(&mut *self.out, value)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Close the array and return the number of elements written
+    fn end(self) -> Result<usize, Box<dyn Error>> {
+        self.out.write_all(b"\n]")?;
+        Ok(self.len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{sample_database, FRIEND_USER_ID, MY_USER_ID};
+    use std::fs;
+
+    fn read_document(path: &Path) -> JournalDocument {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_export_json_document() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.json");
+
+        let summary = export_json(database.connection(), &path, &ExportOptions::default()).unwrap();
+        assert_eq!(summary.accounts, 1);
+        assert_eq!(summary.instances, 1);
+        assert_eq!(summary.screenshots, 1);
+
+        let document = read_document(&path);
+        assert_eq!(document.format, FORMAT_NAME);
+        assert_eq!(document.schema_version, migrations::LATEST_VERSION);
+        assert!(!document.anonymized);
+        let instance = &document.instances[0];
+        assert_eq!(instance.world_name_at_join.as_deref(), Some("Test World"));
+        assert_eq!(instance.participants.len(), 2);
+        assert_eq!(instance.avatar_history.len(), 1);
+        assert!(document.users.iter().any(|u| u.user_id == FRIEND_USER_ID));
+    }
+
+    #[test]
+    fn test_export_json_filter_without_matches() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.json");
+        let mut options = ExportOptions::default();
+        options.filter.user_id = Some("usr_nobody".to_string());
+
+        let summary = export_json(database.connection(), &path, &options).unwrap();
+        assert_eq!(summary.instances, 0);
+
+        // Accounts are kept so the document can still be imported
+        let document = read_document(&path);
+        assert!(document.instances.is_empty());
+        assert_eq!(document.users.len(), 1);
+        assert_eq!(document.users[0].user_id, MY_USER_ID);
+        assert!(!path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn test_export_json_range_without_instances() {
+        let database = sample_database();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.json");
+        let mut options = ExportOptions::default();
+        options.filter.from = Some(0);
+        options.filter.to = Some(1);
+
+        let summary = export_json(database.connection(), &path, &options).unwrap();
+        assert_eq!(summary.accounts, 0);
+        assert_eq!(summary.users, 0);
+        assert_eq!(summary.instances, 0);
+    }
+}
@@ -0,0 +1,58 @@
+mod anonymize;
+mod csv;
+mod diary;
+mod ical;
+mod json;
+mod template;
+
+pub use anonymize::AnonymizeOptions;
+pub use csv::*;
+pub use diary::*;
+pub use ical::*;
+pub use json::*;
+
+use crate::db::operations::InstanceFilter;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// Options common to all exporters
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ExportOptions {
+    #[serde(flatten)]
+    pub filter: InstanceFilter,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anonymize: Option<AnonymizeOptions>, // Replace identities with pseudonyms
+}
+
+/// Write to a temporary file and move it into place once complete
+fn write_atomically<T>(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> Result<T, Box<dyn Error>>,
+) -> Result<T, String> {
+    let tmp_path = path.with_extension("tmp");
+
+    let result = File::create(&tmp_path)
+        .map_err(|e| format!("Failed to create {:?}: {}", tmp_path, e))
+        .and_then(|file| {
+            let mut out = BufWriter::new(file);
+            let value = write(&mut out).map_err(|e| format!("Failed to export: {}", e))?;
+            out.flush()
+                .map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+            Ok(value)
+        });
+
+    match result {
+        Ok(value) => {
+            fs::rename(&tmp_path, path)
+                .map_err(|e| format!("Failed to move export to {:?}: {}", path, e))?;
+            Ok(value)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use serde_json::Value;
+
+/// Minimal Mustache-style template rendered against JSON data.
+///
+/// - `{{name}}` inserts an escaped value, `{{{name}}}` or `{{& name}}` inserts it raw
+/// - `{{#name}}...{{/name}}` repeats for each array item, or renders once if the value is truthy
+/// - `{{^name}}...{{/name}}` renders if the value is missing, false, null, empty or `""`
+/// - `{{! comment }}` is ignored
+/// - Dotted names (`session.worldName`) look up nested fields and `.` is the current item
+///
+/// Section and comment tags on a line of their own do not leave a blank line behind.
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Text(String),
+    Variable {
+        name: String,
+        raw: bool,
+    },
+    Section {
+        name: String,
+        inverted: bool,
+        children: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TagKind {
+    Escaped,
+    Raw,
+    Section,
+    Inverted,
+    Close,
+    Comment,
+}
+
+impl TagKind {
+    /// Tags that produce no output of their own
+    fn is_standalone_candidate(self) -> bool {
+        matches!(
+            self,
+            Self::Section | Self::Inverted | Self::Close | Self::Comment
+        )
+    }
+}
+
+#[derive(Debug)]
+enum Token {
+    Text(String),
+    Tag { kind: TagKind, name: String },
+}
+
+impl Template {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut tokens = tokenize(source)?;
+        trim_standalone_tags(&mut tokens);
+
+        let mut tokens = tokens.into_iter();
+        let nodes = build_nodes(&mut tokens, None)?;
+        Ok(Self { nodes })
+    }
+
+    /// Render with `escape` applied to every non-raw variable
+    pub fn render(&self, data: &Value, escape: impl Fn(&str) -> String) -> String {
+        let mut out = String::new();
+        let mut stack = vec![data];
+        render_nodes(&self.nodes, &mut stack, &escape, &mut out);
+        out
+    }
+}
+
+/// Escape text for HTML content and attribute values
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape characters that Markdown would treat as formatting
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(Token::Text(rest[..start].to_string()));
+        }
+
+        let after_open = &rest[start + 2..];
+        let (triple, close) = match after_open.strip_prefix('{') {
+            Some(_) => (true, "}}}"),
+            None => (false, "}}"),
+        };
+        let body = if triple { &after_open[1..] } else { after_open };
+        let end = body
+            .find(close)
+            .ok_or_else(|| format!("Unclosed tag near: {}", &rest[start..]))?;
+        let tag = body[..end].trim();
+        rest = &body[end + close.len()..];
+
+        let (kind, name) = if triple {
+            (TagKind::Raw, tag)
+        } else {
+            match tag.chars().next() {
+                Some('#') => (TagKind::Section, tag[1..].trim()),
+                Some('^') => (TagKind::Inverted, tag[1..].trim()),
+                Some('/') => (TagKind::Close, tag[1..].trim()),
+                Some('&') => (TagKind::Raw, tag[1..].trim()),
+                Some('!') => (TagKind::Comment, ""),
+                _ => (TagKind::Escaped, tag),
+            }
+        };
+
+        if name.is_empty() && kind != TagKind::Comment {
+            return Err("Empty tag name".to_string());
+        }
+
+        tokens.push(Token::Tag {
+            kind,
+            name: name.to_string(),
+        });
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest.to_string()));
+    }
+
+    Ok(tokens)
+}
+
+/// Remove the indentation and line break around section tags that sit alone on a line
+fn trim_standalone_tags(tokens: &mut [Token]) {
+    let standalone: Vec<usize> = (0..tokens.len())
+        .filter(|&i| {
+            let Token::Tag { kind, .. } = &tokens[i] else {
+                return false;
+            };
+            if !kind.is_standalone_candidate() {
+                return false;
+            }
+
+            let starts_line = match i.checked_sub(1).map(|j| &tokens[j]) {
+                None => true,
+                Some(Token::Text(text)) => {
+                    let tail = text.rsplit('\n').next().unwrap_or_default();
+                    tail.trim().is_empty() && (text.contains('\n') || i == 1)
+                }
+                Some(Token::Tag { .. }) => false,
+            };
+            let ends_line = match tokens.get(i + 1) {
+                None => true,
+                Some(Token::Text(text)) => {
+                    let head = text.split('\n').next().unwrap_or_default();
+                    head.trim().is_empty() && (text.contains('\n') || i + 2 == tokens.len())
+                }
+                Some(Token::Tag { .. }) => false,
+            };
+
+            starts_line && ends_line
+        })
+        .collect();
+
+    for i in standalone {
+        if let Some(Token::Text(text)) = i.checked_sub(1).map(|j| &mut tokens[j]) {
+            let keep = text.rfind('\n').map_or(0, |pos| pos + 1);
+            text.truncate(keep);
+        }
+        if let Some(Token::Text(text)) = tokens.get_mut(i + 1) {
+            let skip = text.find('\n').map_or(text.len(), |pos| pos + 1);
+            text.drain(..skip);
+        }
+    }
+}
+
+fn build_nodes(
+    tokens: &mut std::vec::IntoIter<Token>,
+    closing: Option<&str>,
+) -> Result<Vec<Node>, String> {
+    let mut nodes = Vec::new();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Text(text) if text.is_empty() => {}
+            Token::Text(text) => nodes.push(Node::Text(text)),
+            Token::Tag { kind, name } => match kind {
+                TagKind::Escaped | TagKind::Raw => nodes.push(Node::Variable {
+                    name,
+                    raw: kind == TagKind::Raw,
+                }),
+                TagKind::Section | TagKind::Inverted => {
+                    let children = build_nodes(tokens, Some(&name))?;
+                    nodes.push(Node::Section {
+                        name,
+                        inverted: kind == TagKind::Inverted,
+                        children,
+                    });
+                }
+                TagKind::Close => {
+                    return match closing {
+                        Some(open) if open == name => Ok(nodes),
+                        Some(open) => Err(format!(
+                            "Expected {{{{/{}}}}}, found {{{{/{}}}}}",
+                            open, name
+                        )),
+                        None => Err(format!("Unexpected {{{{/{}}}}}", name)),
+                    };
+                }
+                TagKind::Comment => {}
+            },
+        }
+    }
+
+    match closing {
+        Some(open) => Err(format!("Unclosed section {{{{#{}}}}}", open)),
+        None => Ok(nodes),
+    }
+}
+
+fn render_nodes(
+    nodes: &[Node],
+    stack: &mut Vec<&Value>,
+    escape: &impl Fn(&str) -> String,
+    out: &mut String,
+) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Variable { name, raw } => {
+                let text = match lookup(stack, name) {
+                    Some(Value::String(s)) => s.clone(),
+                    Some(Value::Number(n)) => n.to_string(),
+                    Some(Value::Bool(b)) => b.to_string(),
+                    _ => String::new(),
+                };
+                if *raw {
+                    out.push_str(&text);
+                } else {
+                    out.push_str(&escape(&text));
+                }
+            }
+            Node::Section {
+                name,
+                inverted,
+                children,
+            } => {
+                let value = lookup(stack, name);
+                if *inverted {
+                    if !is_truthy(value) {
+                        render_nodes(children, stack, escape, out);
+                    }
+                    continue;
+                }
+
+                match value {
+                    Some(Value::Array(items)) => {
+                        for item in items {
+                            stack.push(item);
+                            render_nodes(children, stack, escape, out);
+                            stack.pop();
+                        }
+                    }
+                    Some(value) if is_truthy(Some(value)) => {
+                        stack.push(value);
+                        render_nodes(children, stack, escape, out);
+                        stack.pop();
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Resolve a (dotted) name against the context stack, innermost first
+fn lookup<'a>(stack: &[&'a Value], name: &str) -> Option<&'a Value> {
+    if name == "." {
+        return stack.last().copied();
+    }
+
+    let mut parts = name.split('.');
+    let first = parts.next()?;
+    let mut value = stack.iter().rev().find_map(|context| context.get(first))?;
+    for part in parts {
+        value = value.get(part)?;
+    }
+    Some(value)
+}
+
+fn is_truthy(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) | Some(Value::Bool(false)) => false,
+        Some(Value::Array(items)) => !items.is_empty(),
+        Some(Value::String(s)) => !s.is_empty(),
+        Some(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn render(source: &str, data: Value) -> String {
+        Template::parse(source).unwrap().render(&data, escape_html)
+    }
+
+    #[test]
+    fn test_variables_are_escaped_unless_raw() {
+        let data = json!({ "name": "<b>", "nested": { "count": 3 } });
+        assert_eq!(
+            render(
+                "{{name}} {{{name}}} {{& name}} {{nested.count}} {{missing}}",
+                data
+            ),
+            "&lt;b&gt; <b> <b> 3 "
+        );
+    }
+
+    #[test]
+    fn test_sections_loop_and_invert() {
+        let data = json!({ "items": [{ "n": 1 }, { "n": 2 }], "empty": [] });
+        assert_eq!(
+            render("{{#items}}[{{n}}]{{/items}}{{^empty}}none{{/empty}}", data),
+            "[1][2]none"
+        );
+    }
+
+    #[test]
+    fn test_standalone_section_lines_are_removed() {
+        let data = json!({ "items": ["a", "b"] });
+        assert_eq!(
+            render("list:\n{{#items}}\n- {{.}}\n{{/items}}\nend\n", data),
+            "list:\n- a\n- b\nend\n"
+        );
+    }
+
+    #[test]
+    fn test_unbalanced_sections_are_rejected() {
+        assert!(Template::parse("{{#a}}text").is_err());
+        assert!(Template::parse("{{#a}}{{/b}}").is_err());
+        assert!(Template::parse("{{/a}}").is_err());
+    }
+}
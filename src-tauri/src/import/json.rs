@@ -0,0 +1,633 @@
+use crate::db::{self, migrations, operations};
+use crate::export::{InstanceRecord, JournalDocument, FORMAT_NAME, FORMAT_VERSION};
+use crate::types::{InstanceStatus, MergeOutcome};
+use rusqlite::Connection;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+/// Row counts for one kind of record
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RowCounts {
+    pub inserted: usize,
+    pub merged: usize,
+    pub skipped: usize,
+}
+
+impl RowCounts {
+    fn record(&mut self, outcome: MergeOutcome) {
+        match outcome {
+            MergeOutcome::Inserted => self.inserted += 1,
+            MergeOutcome::Merged => self.merged += 1,
+            MergeOutcome::Unchanged => self.skipped += 1,
+        }
+    }
+}
+
+/// Result of importing a journal document
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    pub accounts: RowCounts,
+    pub users: RowCounts,
+    pub worlds: RowCounts,
+    pub instances: RowCounts,
+    pub participants: RowCounts,
+    pub avatar_changes: RowCounts,
+    pub screenshots: RowCounts,
+}
+
+/// Merge a JSON export into the database within a single transaction.
+///
+/// Conflict rules:
+/// - Users and worlds keep the earliest first-seen time and the most recently seen name
+/// - Name histories with the same name are widened to cover both periods
+/// - Instances are matched by (account, world, instance ID, start time); local values win
+///   and imported values only fill in what is missing (end time, final status, note)
+/// - Participants, avatar changes and screenshots are matched by their natural keys
+/// - Users on the privacy blocklist are left out and counted as purged participants
+/// - Imported covers are used only where no cover was chosen locally
+/// - New instances without an end time are closed as interrupted at their last recorded event,
+///   so they never become the live instance of this machine
+pub fn import_json(database: &mut db::Database, path: &Path) -> Result<ImportSummary, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    let document: JournalDocument = serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Failed to parse journal document: {}", e))?;
+
+    if document.format != FORMAT_NAME {
+        return Err(format!("Not a VRCJournal export: {}", document.format));
+    }
+    if document.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "Unsupported export format version {} (supported up to {})",
+            document.format_version, FORMAT_VERSION
+        ));
+    }
+    // Pseudonyms would be merged as new users next to the real ones
+    if document.anonymized {
+        return Err("Anonymized exports can't be imported".to_string());
+    }
+    if document.schema_version > migrations::LATEST_VERSION {
+        return Err(format!(
+            "Exported by a newer version of VRCJournal (schema version {}, supported up to {})",
+            document.schema_version,
+            migrations::LATEST_VERSION
+        ));
+    }
+
+    tracing::info!(
+        "Importing journal exported at {} (schema version {})",
+        document.exported_at, document.schema_version
+    );
+
+    let tx = database
+        .transaction()
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    let summary =
+        merge_document(&tx, &document).map_err(|e| format!("Failed to import: {}", e))?;
+
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    Ok(summary)
+}
+
+/// Maps VRChat IDs in the document to local row IDs
+#[derive(Default)]
+struct IdMap {
+    users: HashMap<String, i64>,       // usr_xxx -> users.id
+    worlds: HashMap<String, i64>,      // wrld_xxx -> worlds.id
+    my_accounts: HashMap<String, i64>, // usr_xxx -> my_accounts.id
+}
+
+fn merge_document(
+    conn: &Connection,
+    document: &JournalDocument,
+) -> Result<ImportSummary, Box<dyn Error>> {
+    let mut summary = ImportSummary::default();
+    let mut ids = IdMap::default();
+    let blocked: HashSet<String> = operations::get_blocked_users(conn)?
+        .into_iter()
+        .map(|blocked| blocked.user_id)
+        .collect();
+
+    for user in &document.users {
+        if blocked.contains(&user.user_id) {
+            summary.users.skipped += 1;
+            continue;
+        }
+        let (id, outcome) = operations::merge_user(
+            conn,
+            &user.user_id,
+            &user.display_name,
+            user.first_seen_at,
+            user.last_seen_at,
+        )?;
+        for entry in &user.name_history {
+            operations::merge_user_name_history(
+                conn,
+                id,
+                &entry.name,
+                entry.first_seen_at,
+                entry.last_seen_at,
+            )?;
+        }
+        ids.users.insert(user.user_id.clone(), id);
+        summary.users.record(outcome);
+    }
+
+    for account in &document.accounts {
+        let (user_id, _) = operations::merge_user(
+            conn,
+            &account.user_id,
+            &account.display_name,
+            account.first_authenticated_at,
+            account.last_authenticated_at,
+        )?;
+        let existed = operations::get_my_account_id(conn, user_id)?.is_some();
+        let id = operations::merge_my_account(
+            conn,
+            user_id,
+            account.first_authenticated_at,
+            account.last_authenticated_at,
+        )?;
+        ids.users.insert(account.user_id.clone(), user_id);
+        ids.my_accounts.insert(account.user_id.clone(), id);
+        summary.accounts.record(if existed {
+            MergeOutcome::Merged
+        } else {
+            MergeOutcome::Inserted
+        });
+    }
+
+    for world in &document.worlds {
+        let (id, outcome) = operations::merge_world(
+            conn,
+            &world.world_id,
+            &world.world_name,
+            world.first_seen_at,
+            world.last_seen_at,
+        )?;
+        for entry in &world.name_history {
+            operations::merge_world_name_history(
+                conn,
+                id,
+                &entry.name,
+                entry.first_seen_at,
+                entry.last_seen_at,
+            )?;
+        }
+        ids.worlds.insert(world.world_id.clone(), id);
+        summary.worlds.record(outcome);
+    }
+
+    for instance in &document.instances {
+        merge_instance(conn, &mut ids, &blocked, instance, &mut summary)?;
+    }
+
+    // World covers point to screenshots, which exist only after the instances are merged
+    for world in &document.worlds {
+        let Some(cover_file_path) = &world.cover_file_path else {
+            continue;
+        };
+        if has_chosen_cover(operations::get_world_cover(conn, &world.world_id)?) {
+            continue;
+        }
+        if let Some(screenshot_id) = operations::find_screenshot_by_path(conn, cover_file_path)? {
+            operations::set_world_cover(conn, &world.world_id, Some(screenshot_id))?;
+        }
+    }
+
+    Ok(summary)
+}
+
+fn merge_instance(
+    conn: &Connection,
+    ids: &mut IdMap,
+    blocked: &HashSet<String>,
+    record: &InstanceRecord,
+    summary: &mut ImportSummary,
+) -> Result<(), Box<dyn Error>> {
+    let (Some(&my_account_id), Ok(status)) = (
+        ids.my_accounts.get(&record.account_user_id),
+        InstanceStatus::from_str(&record.status),
+    ) else {
+        summary.instances.skipped += 1;
+        return Ok(());
+    };
+
+    let world_id = match ids.worlds.get(&record.world_id) {
+        Some(&id) => id,
+        None => {
+            let id = operations::upsert_world(conn, &record.world_id, record.started_at)?;
+            ids.worlds.insert(record.world_id.clone(), id);
+            id
+        }
+    };
+
+    let existing = operations::find_instance(
+        conn,
+        my_account_id,
+        world_id,
+        &record.instance_id,
+        record.started_at,
+    )?;
+
+    let (instance_id, mut outcome) = match existing {
+        Some((id, ended_at, current_status)) => {
+            let mut outcome = MergeOutcome::Unchanged;
+            if let (None, Some(imported_ended_at)) = (ended_at, record.ended_at) {
+                operations::end_instance(conn, id, imported_ended_at)?;
+                if current_status == InstanceStatus::Active.as_str() {
+                    operations::update_instance_status(conn, id, status)?;
+                }
+                outcome = MergeOutcome::Merged;
+            }
+            if let Some(note) = &record.note {
+                if operations::get_instance_note(conn, id)?.is_none() {
+                    operations::set_instance_note(conn, id, Some(note))?;
+                    outcome = MergeOutcome::Merged;
+                }
+            }
+            (id, outcome)
+        }
+        None => {
+            let id = operations::create_instance(
+                conn,
+                my_account_id,
+                world_id,
+                &record.instance_id,
+                record.started_at,
+            )?;
+            if let Some(world_name) = &record.world_name_at_join {
+                // The world's own name history usually lists the name already
+                let history_id =
+                    match operations::find_world_name_history(conn, world_id, world_name)? {
+                        Some(id) => id,
+                        None => operations::merge_world_name_history(
+                            conn,
+                            world_id,
+                            world_name,
+                            record.started_at,
+                            record.started_at,
+                        )?,
+                    };
+                operations::update_instance_world_name_history(conn, id, history_id)?;
+            }
+            if let Some(ended_at) = record.ended_at {
+                operations::end_instance(conn, id, ended_at)?;
+            }
+            operations::update_instance_status(conn, id, status)?;
+            if let Some(note) = &record.note {
+                operations::set_instance_note(conn, id, Some(note))?;
+            }
+            (id, MergeOutcome::Inserted)
+        }
+    };
+
+    let blocked_participants = record
+        .participants
+        .iter()
+        .filter(|participant| blocked.contains(&participant.user_id))
+        .count();
+    let purged_participants = record.purged_participants + blocked_participants as i64;
+    if purged_participants > 0
+        && operations::merge_purged_participants(conn, instance_id, purged_participants)?
+        && outcome == MergeOutcome::Unchanged
+    {
+        outcome = MergeOutcome::Merged;
+    }
+
+    for participant in &record.participants {
+        if blocked.contains(&participant.user_id) {
+            summary.participants.skipped += 1;
+            continue;
+        }
+        let user_id = resolve_user(
+            conn,
+            ids,
+            &participant.user_id,
+            &participant.display_name_at_join,
+            participant.joined_at,
+        )?;
+
+        let existing_participant =
+            operations::find_instance_user(conn, instance_id, user_id, participant.joined_at)?;
+
+        let child = match (existing_participant, participant.left_at) {
+            (Some((instance_user_id, None)), Some(left_at)) => {
+                operations::set_user_left_instance(conn, instance_user_id, left_at)?;
+                MergeOutcome::Merged
+            }
+            (Some(_), _) => MergeOutcome::Unchanged,
+            (None, _) => {
+                let name_history_id = operations::merge_user_name_history(
+                    conn,
+                    user_id,
+                    &participant.display_name_at_join,
+                    participant.joined_at,
+                    participant.joined_at,
+                )?;
+                let instance_user_id = operations::add_user_to_instance(
+                    conn,
+                    instance_id,
+                    user_id,
+                    name_history_id,
+                    participant.joined_at,
+                )?;
+                if let Some(left_at) = participant.left_at {
+                    operations::set_user_left_instance(conn, instance_user_id, left_at)?;
+                }
+                MergeOutcome::Inserted
+            }
+        };
+        summary.participants.record(child);
+        outcome = merged_with_child(outcome, child);
+    }
+
+    for change in &record.avatar_history {
+        if blocked.contains(&change.user_id) {
+            summary.avatar_changes.skipped += 1;
+            continue;
+        }
+        let display_name =
+            participant_name(record, &change.user_id, change.changed_at).unwrap_or(&change.user_id);
+        let user_id = resolve_user(conn, ids, &change.user_id, display_name, change.changed_at)?;
+        let avatar_id = operations::merge_avatar(
+            conn,
+            &change.avatar_name,
+            change.avatar_id.as_deref(),
+            change.changed_at,
+        )?;
+
+        let child = if operations::has_avatar_history(
+            conn,
+            instance_id,
+            user_id,
+            avatar_id,
+            change.changed_at,
+        )? {
+            MergeOutcome::Unchanged
+        } else {
+            operations::record_avatar_history(
+                conn,
+                instance_id,
+                user_id,
+                avatar_id,
+                change.changed_at,
+            )?;
+            MergeOutcome::Inserted
+        };
+        summary.avatar_changes.record(child);
+        outcome = merged_with_child(outcome, child);
+    }
+
+    for screenshot in &record.screenshots {
+        let child = if operations::find_screenshot(conn, instance_id, &screenshot.file_path)?
+            .is_some()
+        {
+            MergeOutcome::Unchanged
+        } else {
+            let screenshot_id = operations::record_screenshot(
+                conn,
+                instance_id,
+                &screenshot.file_path,
+                screenshot.taken_at,
+                screenshot.source,
+            )?;
+            if screenshot.hidden {
+                operations::set_screenshot_hidden(conn, screenshot_id, true)?;
+            }
+            if screenshot.favorite {
+                operations::set_screenshot_favorite(conn, screenshot_id, true)?;
+            }
+            if let Some(caption) = &screenshot.caption {
+                operations::set_screenshot_caption(conn, screenshot_id, Some(caption))?;
+            }
+            if screenshot.cover {
+                let local_cover = operations::get_instance_cover(conn, instance_id)?;
+                if !has_chosen_cover(local_cover) {
+                    operations::set_instance_cover(conn, instance_id, Some(screenshot_id))?;
+                }
+            }
+            MergeOutcome::Inserted
+        };
+        summary.screenshots.record(child);
+        outcome = merged_with_child(outcome, child);
+    }
+
+    if outcome == MergeOutcome::Inserted && record.ended_at.is_none() {
+        close_imported_instance(conn, instance_id, record)?;
+    }
+
+    summary.instances.record(outcome);
+    Ok(())
+}
+
+/// End an instance imported while it was still open at its last recorded event
+fn close_imported_instance(
+    conn: &Connection,
+    instance_id: i64,
+    record: &InstanceRecord,
+) -> Result<(), Box<dyn Error>> {
+    let participant_times = record
+        .participants
+        .iter()
+        .map(|participant| participant.left_at.unwrap_or(participant.joined_at));
+    let avatar_times = record.avatar_history.iter().map(|change| change.changed_at);
+    let screenshot_times = record
+        .screenshots
+        .iter()
+        .map(|screenshot| screenshot.taken_at);
+    let ended_at = participant_times
+        .chain(avatar_times)
+        .chain(screenshot_times)
+        .fold(record.started_at, i64::max);
+
+    operations::end_instance(conn, instance_id, ended_at)?;
+    operations::set_all_users_left_instance(conn, instance_id, ended_at)?;
+    if record.status == InstanceStatus::Active.as_str() {
+        operations::update_instance_status(conn, instance_id, InstanceStatus::Interrupted)?;
+    }
+    Ok(())
+}
+
+/// Display name a participant of the instance had when joining last before `at`
+fn participant_name<'a>(record: &'a InstanceRecord, user_id: &str, at: i64) -> Option<&'a str> {
+    let participants = record
+        .participants
+        .iter()
+        .filter(|participant| participant.user_id == user_id);
+
+    participants
+        .clone()
+        .filter(|participant| participant.joined_at <= at)
+        .max_by_key(|participant| participant.joined_at)
+        .or_else(|| participants.min_by_key(|participant| participant.joined_at))
+        .map(|participant| participant.display_name_at_join.as_str())
+}
+
+/// Resolve a VRChat user ID, creating the user if the document did not list it
+fn resolve_user(
+    conn: &Connection,
+    ids: &mut IdMap,
+    vrchat_user_id: &str,
+    display_name: &str,
+    seen_at: i64,
+) -> Result<i64, Box<dyn Error>> {
+    if let Some(&id) = ids.users.get(vrchat_user_id) {
+        return Ok(id);
+    }
+
+    let (id, _) = operations::merge_user(conn, vrchat_user_id, display_name, seen_at, seen_at)?;
+    ids.users.insert(vrchat_user_id.to_string(), id);
+    Ok(id)
+}
+
+/// Local cover choices win over imported ones
+fn has_chosen_cover(cover: Option<operations::CoverPhoto>) -> bool {
+    cover.is_some_and(|cover| cover.chosen)
+}
+
+/// An existing instance counts as merged once any of its children changed
+fn merged_with_child(parent: MergeOutcome, child: MergeOutcome) -> MergeOutcome {
+    match (parent, child) {
+        (MergeOutcome::Unchanged, MergeOutcome::Unchanged) => MergeOutcome::Unchanged,
+        (MergeOutcome::Unchanged, _) => MergeOutcome::Merged,
+        (parent, _) => parent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{export_json, AnonymizeOptions, ExportOptions};
+    use crate::test_support::{database_from_log, sample_database, SAMPLE_LOG};
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn export(database: &db::Database, dir: &Path, name: &str) -> PathBuf {
+        let path = dir.join(name);
+        export_json(database.connection(), &path, &ExportOptions::default()).unwrap();
+        path
+    }
+
+    fn read_value(path: &Path) -> serde_json::Value {
+        serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_round_trip_into_empty_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let exported = export(&sample_database(), dir.path(), "a.json");
+
+        let mut database = db::Database::open_in_memory().unwrap();
+        let summary = import_json(&mut database, &exported).unwrap();
+        assert_eq!(summary.accounts.inserted, 1);
+        assert_eq!(summary.instances.inserted, 1);
+        assert_eq!(summary.participants.inserted, 2);
+        assert_eq!(summary.avatar_changes.inserted, 1);
+        assert_eq!(summary.screenshots.inserted, 1);
+
+        let reexported = read_value(&export(&database, dir.path(), "b.json"));
+        let original = read_value(&exported);
+        for key in ["accounts", "users", "worlds", "instances"] {
+            assert_eq!(reexported[key], original[key], "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_import_into_database_with_data() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut database = sample_database();
+        let exported = export(&database, dir.path(), "a.json");
+
+        // Importing the same data again changes nothing
+        let summary = import_json(&mut database, &exported).unwrap();
+        assert_eq!(summary.instances.skipped, 1);
+        assert_eq!(summary.participants.skipped, 2);
+        assert_eq!(summary.avatar_changes.skipped, 1);
+        assert_eq!(summary.screenshots.skipped, 1);
+
+        // Another evening of the same account is added next to it
+        let other = database_from_log(&[
+            SAMPLE_LOG[0],
+            "2025.10.20 20:00:00 Debug      -  [Behaviour] Joining wrld_00000000-0000-0000-0000-00000000000b:99~region(jp)",
+            "2025.10.20 20:00:01 Debug      -  [Behaviour] Joining or Creating Room: Other World",
+            "2025.10.20 20:00:02 Debug      -  [Behaviour] OnPlayerJoined Me (usr_00000000-0000-0000-0000-000000000001)",
+            "2025.10.20 21:00:00 Debug      -  [Behaviour] Destroying Me",
+        ]);
+        let summary = import_json(&mut database, &export(&other, dir.path(), "b.json")).unwrap();
+        assert_eq!(summary.accounts.merged, 1);
+        assert_eq!(summary.instances.inserted, 1);
+
+        let instances = read_value(&export(&database, dir.path(), "c.json"))["instances"].clone();
+        assert_eq!(instances.as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_rejects_newer_schema_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = export(&sample_database(), dir.path(), "a.json");
+        let text = fs::read_to_string(&path).unwrap().replace(
+            &format!("\"schemaVersion\":{}", migrations::LATEST_VERSION),
+            &format!("\"schemaVersion\":{}", migrations::LATEST_VERSION + 1),
+        );
+        fs::write(&path, text).unwrap();
+
+        let mut database = db::Database::open_in_memory().unwrap();
+        assert!(import_json(&mut database, &path).is_err());
+    }
+
+    #[test]
+    fn test_rejects_anonymized_exports() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.json");
+        let options = ExportOptions {
+            anonymize: Some(AnonymizeOptions::default()),
+            ..Default::default()
+        };
+        export_json(sample_database().connection(), &path, &options).unwrap();
+        assert_eq!(read_value(&path)["anonymized"], true);
+
+        let mut database = db::Database::open_in_memory().unwrap();
+        assert!(import_json(&mut database, &path).is_err());
+        let users: i64 = database
+            .connection()
+            .query_row("SELECT COUNT(*) FROM users", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(users, 0);
+    }
+
+    #[test]
+    fn test_open_instance_is_closed_on_import() {
+        let dir = tempfile::tempdir().unwrap();
+        // The log stops while still in the instance
+        let source = database_from_log(&SAMPLE_LOG[..7]);
+        let path = export(&source, dir.path(), "a.json");
+
+        let mut database = db::Database::open_in_memory().unwrap();
+        import_json(&mut database, &path).unwrap();
+        let conn = database.connection();
+
+        let (ended_at, status): (Option<i64>, String) = conn
+            .query_row("SELECT ended_at, status FROM instances", (), |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        let screenshot_taken_at: i64 = conn
+            .query_row("SELECT taken_at FROM screenshots", (), |row| row.get(0))
+            .unwrap();
+        assert_eq!(ended_at, Some(screenshot_taken_at));
+        assert_eq!(status, InstanceStatus::Interrupted.as_str());
+        assert_eq!(
+            operations::get_latest_active_instance(conn, 1).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use crate::db;
+use crate::event_handler::EventHandler;
+use crate::log_reader::LogReader;
+use crate::settings;
+use crate::types::VRChatEvent;
+use chrono_tz::Tz;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Result of importing VRChat log files
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogImportSummary {
+    pub files: usize,     // Files with lines that were not imported before
+    pub events: usize,    // Log events read
+    pub instances: usize, // Instances created
+}
+
+/// Read VRChat logs from another machine or folder within a single transaction.
+///
+/// - `timezone` is the zone the logs were written in. When None, a zone saved earlier
+///   or detected from UTC hints is used, falling back to `default_timezone`
+/// - Given and detected zones are saved per file in `log_files`
+/// - Files imported before only add the lines written since
+/// - Instances already recorded from another copy of a log (same account, location and
+///   start time) are skipped
+/// - Imported logs are treated as finished: their end closes open instances and sessions
+pub fn import_logs(
+    database: &mut db::Database,
+    paths: &[PathBuf],
+    timezone: Option<Tz>,
+    default_timezone: Option<Tz>,
+) -> Result<LogImportSummary, String> {
+    let mut reader = LogReader::new();
+    reader.configure(Vec::new(), default_timezone);
+    // Imported logs do not continue the live log's instance or session
+    let mut handler = EventHandler::new();
+    handler.set_timezone(
+        timezone
+            .or(default_timezone)
+            .unwrap_or_else(settings::system_timezone),
+    );
+    let mut summary = LogImportSummary::default();
+
+    let tx = database
+        .transaction()
+        .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+    for path in paths {
+        if !path.is_file() {
+            return Err(format!("Log file not found: {:?}", path));
+        }
+
+        if let Some(timezone) = timezone {
+            db::operations::set_log_file_timezone(
+                &tx,
+                &path.to_string_lossy(),
+                Some(timezone.name()),
+            )
+            .map_err(|e| format!("Failed to save log time zone: {}", e))?;
+        }
+
+        let events = reader.read_finished_file(&tx, path)?;
+        if events.is_empty() {
+            continue;
+        }
+
+        summary.files += 1;
+        summary.events += events.len();
+        for event in events {
+            let processed = handler
+                .process_event(&tx, event)
+                .map_err(|e| format!("Failed to process event: {}", e))?;
+            if matches!(processed, Some(VRChatEvent::InstanceStarted { .. })) {
+                summary.instances += 1;
+            }
+        }
+    }
+
+    reader.save_file_states(&tx);
+    tx.commit()
+        .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+    tracing::info!(?summary, "Imported log files");
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{database_from_log, utc, SAMPLE_LOG};
+    use rusqlite::Connection;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::Path;
+
+    fn append_log(path: &Path, lines: &[&str]) {
+        let mut file = File::options()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+    }
+
+    fn instance_starts(conn: &Connection) -> Vec<i64> {
+        let mut stmt = conn
+            .prepare("SELECT started_at FROM instances ORDER BY started_at")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    fn saved_timezone(database: &db::Database, path: &Path) -> Option<String> {
+        db::operations::get_log_file_timezone(database.connection(), &path.to_string_lossy())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_import_in_the_given_timezone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("output_log_2025-10-13_09-53-00.txt");
+        append_log(&path, SAMPLE_LOG);
+        let mut database = db::Database::open_in_memory().unwrap();
+
+        let summary = import_logs(
+            &mut database,
+            std::slice::from_ref(&path),
+            Some(chrono_tz::Asia::Tokyo),
+            Some(Tz::UTC),
+        )
+        .unwrap();
+        assert_eq!(summary.files, 1);
+        assert_eq!(summary.instances, 1);
+
+        assert_eq!(
+            instance_starts(database.connection()),
+            vec![utc("2025-10-13T00:53:22Z").timestamp_millis()]
+        );
+        assert_eq!(
+            saved_timezone(&database, &path).as_deref(),
+            Some("Asia/Tokyo")
+        );
+    }
+
+    #[test]
+    fn test_reimport_reads_only_new_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("output_log_2025-10-13_09-53-00.txt");
+        append_log(&path, &SAMPLE_LOG[..6]);
+        let mut database = db::Database::open_in_memory().unwrap();
+        let paths = [path.clone()];
+
+        let summary = import_logs(&mut database, &paths, None, Some(Tz::UTC)).unwrap();
+        assert_eq!((summary.files, summary.instances), (1, 1));
+
+        // The screenshot and the two leaves, then the end of the log
+        append_log(&path, &SAMPLE_LOG[6..]);
+        let summary = import_logs(&mut database, &paths, None, Some(Tz::UTC)).unwrap();
+        assert_eq!(
+            (summary.files, summary.events, summary.instances),
+            (1, 4, 0)
+        );
+        assert_eq!(instance_starts(database.connection()).len(), 1);
+
+        let summary = import_logs(&mut database, &paths, None, Some(Tz::UTC)).unwrap();
+        assert_eq!((summary.files, summary.events), (0, 0));
+    }
+
+    #[test]
+    fn test_import_without_hints_uses_the_default_until_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("output_log_2025-10-13_09-53-00.txt");
+        append_log(&path, SAMPLE_LOG);
+        let mut database = db::Database::open_in_memory().unwrap();
+        let paths = [path.clone()];
+
+        import_logs(&mut database, &paths, None, Some(chrono_tz::Asia::Tokyo)).unwrap();
+        assert_eq!(
+            instance_starts(database.connection()),
+            vec![utc("2025-10-13T00:53:22Z").timestamp_millis()]
+        );
+        // The default is not saved, so later lines can still tell the zone
+        assert_eq!(saved_timezone(&database, &path), None);
+
+        append_log(
+            &path,
+            &["2025.10.14 01:00:00 Debug      -  [API] Response at 2025-10-14T01:00:00Z"],
+        );
+        import_logs(&mut database, &paths, None, Some(chrono_tz::Asia::Tokyo)).unwrap();
+        assert_eq!(saved_timezone(&database, &path).as_deref(), Some("UTC"));
+    }
+
+    #[test]
+    fn test_import_skips_instances_read_from_another_copy() {
+        let mut database = database_from_log(SAMPLE_LOG);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("output_log_2025-10-13_09-53-00.txt");
+        append_log(&path, SAMPLE_LOG);
+
+        let summary = import_logs(&mut database, &[path], Some(Tz::UTC), None).unwrap();
+        assert_eq!(summary.instances, 0);
+
+        let conn = database.connection();
+        assert_eq!(instance_starts(conn).len(), 1);
+        let participants: i64 = conn
+            .query_row("SELECT COUNT(*) FROM instance_users", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(participants, 2);
+    }
+}
@@ -0,0 +1,5 @@
+mod json;
+mod logs;
+
+pub use json::*;
+pub use logs::*;
@@ -2,9 +2,16 @@ mod app;
 mod commands;
 mod db;
 mod event_handler;
+mod export;
+mod import;
 mod log_monitor;
 mod log_parser;
 mod log_reader;
+mod logging;
+mod screenshot;
+mod settings;
+#[cfg(test)]
+mod test_support;
 mod types;
 
 use commands::*;
@@ -18,8 +25,64 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             open_invite_url,
             open_user_page,
-            open_screenshot_directory
+            open_screenshot_directory,
+            get_calendar_summary,
+            get_activity_heatmap,
+            export_json,
+            export_csv,
+            export_ical,
+            export_diary,
+            set_instance_note,
+            set_instance_cover,
+            get_instance_covers,
+            set_world_cover,
+            get_world_covers,
+            get_screenshot_metadata,
+            refresh_screenshot_metadata,
+            get_thumbnails,
+            attach_screenshot,
+            get_screenshot_folders,
+            add_screenshot_folder,
+            remove_screenshot_folder,
+            scan_screenshot_folders,
+            check_screenshot_files,
+            get_missing_screenshots,
+            relink_screenshots,
+            find_duplicate_screenshots,
+            mark_duplicate_screenshots,
+            set_screenshots_hidden,
+            set_screenshot_favorite,
+            set_screenshot_caption,
+            get_best_screenshots,
+            purge_user,
+            get_blocked_users,
+            unblock_user,
+            get_user_tags,
+            set_user_tags,
+            preview_retention,
+            apply_retention,
+            import_json,
+            import_logs,
+            get_settings,
+            update_settings,
+            create_backup,
+            list_backups,
+            restore_backup,
+            pause_monitor,
+            resume_monitor,
+            rescan_logs,
+            is_monitor_paused,
+            get_diagnostics,
+            export_app_logs,
+            get_game_sessions,
+            get_instance_game_session,
+            set_log_file_timezone
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            if let tauri::RunEvent::Exit = event {
+                app::shutdown(app_handle);
+            }
+        });
 }
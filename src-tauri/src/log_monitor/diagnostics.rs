@@ -0,0 +1,23 @@
+use crate::event_handler::HandlerState;
+use std::collections::BTreeMap;
+
+/// State of the monitor thread, published after each poll
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorDiagnostics {
+    pub log_dirs: Vec<String>,
+    pub files: Vec<TrackedFile>,
+    pub last_poll_at: Option<i64>,               // Unix milliseconds
+    pub events_processed: BTreeMap<String, u64>, // Event type -> count since startup
+    pub parse_misses: u64,                       // Event lines with an unreadable timestamp
+    pub handler: HandlerState,
+}
+
+/// Log file being read
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrackedFile {
+    pub path: String,
+    pub position: u64,     // Bytes read so far
+    pub size: Option<u64>, // Current file size (None if the file is gone)
+}
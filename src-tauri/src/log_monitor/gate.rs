@@ -0,0 +1,150 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// Pauses the monitor thread, either until resumed by the user or while an
+/// operation that replaces the database holds the gate
+#[derive(Default)]
+pub struct MonitorGate {
+    state: Mutex<GateState>,
+    changed: Condvar,
+    reload_requested: AtomicBool,
+}
+
+#[derive(Default)]
+struct GateState {
+    paused: bool,  // Paused from the UI until resumed
+    holds: usize,  // Operations currently holding the gate
+    polling: bool, // The monitor is between `enter` and the end of its poll
+}
+
+/// Keeps the monitor paused until dropped
+pub struct GateHold<'a> {
+    gate: &'a MonitorGate,
+}
+
+/// Marks the monitor as polling until dropped
+pub struct GatePass<'a> {
+    gate: &'a MonitorGate,
+}
+
+impl MonitorGate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pause the monitor until the hold is dropped, waiting for a poll in progress to finish
+    pub fn hold(&self) -> GateHold<'_> {
+        let mut state = self.lock();
+        state.holds += 1;
+        while state.polling {
+            state = self.wait(state);
+        }
+        GateHold { gate: self }
+    }
+
+    /// Stop polling until `resume` (a poll in progress still finishes)
+    pub fn pause(&self) {
+        self.lock().paused = true;
+    }
+
+    pub fn resume(&self) {
+        self.lock().paused = false;
+        self.changed.notify_all();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.lock().paused
+    }
+
+    /// Called by the monitor before polling. Waits while the gate is held and
+    /// returns None while paused.
+    pub fn enter(&self) -> Option<GatePass<'_>> {
+        let mut state = self.lock();
+        while state.holds > 0 {
+            state = self.wait(state);
+        }
+        if state.paused {
+            return None;
+        }
+
+        state.polling = true;
+        Some(GatePass { gate: self })
+    }
+
+    /// Ask the monitor to reload its state from the database before the next poll
+    pub fn request_reload(&self) {
+        self.reload_requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Consume a pending reload request
+    pub fn take_reload_request(&self) -> bool {
+        self.reload_requested.swap(false, Ordering::SeqCst)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, GateState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn wait<'a>(&self, state: MutexGuard<'a, GateState>) -> MutexGuard<'a, GateState> {
+        self.changed.wait(state).unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for GateHold<'_> {
+    fn drop(&mut self) {
+        self.gate.lock().holds -= 1;
+        self.gate.changed.notify_all();
+    }
+}
+
+impl Drop for GatePass<'_> {
+    fn drop(&mut self) {
+        self.gate.lock().polling = false;
+        self.gate.changed.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pause_skips_polls_until_resumed() {
+        let gate = MonitorGate::new();
+        assert!(gate.enter().is_some());
+
+        gate.pause();
+        assert!(gate.is_paused());
+        assert!(gate.enter().is_none());
+
+        gate.resume();
+        assert!(gate.enter().is_some());
+    }
+
+    #[test]
+    fn test_hold_waits_for_poll_and_blocks_the_next_one() {
+        let gate = MonitorGate::new();
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::scope(|scope| {
+            let pass = gate.enter().unwrap();
+            scope.spawn(|| {
+                let _hold = gate.hold();
+                sender.send("held").unwrap();
+                std::thread::sleep(Duration::from_millis(50));
+                sender.send("released").unwrap();
+            });
+
+            // The hold waits until the poll in progress is over
+            assert!(receiver.recv_timeout(Duration::from_millis(50)).is_err());
+            drop(pass);
+            assert_eq!(receiver.recv().unwrap(), "held");
+
+            // The next poll starts only after the hold is released
+            let _pass = gate.enter().unwrap();
+            assert_eq!(receiver.try_recv().unwrap(), "released");
+        });
+    }
+}
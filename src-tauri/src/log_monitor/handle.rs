@@ -0,0 +1,215 @@
+use super::{MonitorDiagnostics, MonitorGate};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Requests sent to the monitor thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonitorCommand {
+    Rescan,   // Look for log files again and catch up right away
+    Shutdown, // Save positions, close the database and stop the thread
+}
+
+/// Controls the monitor thread from commands and the exit hook
+pub struct MonitorHandle {
+    gate: Arc<MonitorGate>,
+    commands: Sender<MonitorCommand>,
+    wake: Sender<()>, // Interrupts the wait between polls
+    diagnostics: Arc<Mutex<MonitorDiagnostics>>,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+/// Receiving side of `MonitorHandle`, owned by the monitor thread
+pub struct MonitorControl {
+    commands: Receiver<MonitorCommand>,
+    diagnostics: Arc<Mutex<MonitorDiagnostics>>,
+    rescan_requested: bool,
+    shutdown_requested: bool,
+}
+
+impl MonitorHandle {
+    /// Start the monitor thread. `wake` must interrupt the thread's wait between polls.
+    pub fn spawn(
+        gate: Arc<MonitorGate>,
+        wake: Sender<()>,
+        run: impl FnOnce(MonitorControl) + Send + 'static,
+    ) -> Self {
+        let (commands, receiver) = mpsc::channel();
+        let diagnostics = Arc::new(Mutex::new(MonitorDiagnostics::default()));
+
+        let control = MonitorControl {
+            commands: receiver,
+            diagnostics: diagnostics.clone(),
+            rescan_requested: false,
+            shutdown_requested: false,
+        };
+        let thread = std::thread::spawn(move || run(control));
+
+        Self {
+            gate,
+            commands,
+            wake,
+            diagnostics,
+            thread: Mutex::new(Some(thread)),
+        }
+    }
+
+    /// Gate used to pause the monitor
+    pub fn gate(&self) -> &MonitorGate {
+        &self.gate
+    }
+
+    /// Stop reading logs until `resume`
+    pub fn pause(&self) {
+        self.gate.pause();
+    }
+
+    /// Resume reading logs, catching up right away
+    pub fn resume(&self) {
+        self.gate.resume();
+        let _ = self.wake.send(());
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.gate.is_paused()
+    }
+
+    /// Latest state published by the monitor thread
+    pub fn diagnostics(&self) -> MonitorDiagnostics {
+        self.diagnostics
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn send(&self, command: MonitorCommand) -> Result<(), String> {
+        self.commands
+            .send(command)
+            .map_err(|_| "Log monitor is not running".to_string())?;
+        let _ = self.wake.send(());
+        Ok(())
+    }
+
+    /// Stop the monitor and wait until it has saved its positions
+    pub fn shutdown(&self) {
+        let Some(thread) = self.thread.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+            return;
+        };
+
+        // The thread may already have stopped on its own
+        let _ = self.send(MonitorCommand::Shutdown);
+        if thread.join().is_err() {
+            tracing::error!("Log monitor thread panicked");
+        }
+    }
+}
+
+impl MonitorControl {
+    /// Apply commands sent since the last call
+    pub fn receive(&mut self) {
+        loop {
+            match self.commands.try_recv() {
+                Ok(command) => self.apply(command),
+                Err(TryRecvError::Empty) => break,
+                // Nobody can stop the monitor any more
+                Err(TryRecvError::Disconnected) => {
+                    self.shutdown_requested = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Share the monitor's current state with `MonitorHandle::diagnostics`
+    pub fn publish(&self, diagnostics: MonitorDiagnostics) {
+        *self.diagnostics.lock().unwrap_or_else(|e| e.into_inner()) = diagnostics;
+    }
+
+    pub fn take_rescan_request(&mut self) -> bool {
+        std::mem::take(&mut self.rescan_requested)
+    }
+
+    pub fn shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    fn apply(&mut self, command: MonitorCommand) {
+        match command {
+            MonitorCommand::Rescan => self.rescan_requested = true,
+            MonitorCommand::Shutdown => self.shutdown_requested = true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    /// Spawn a thread that records rescans until it is asked to stop
+    fn spawn_monitor(rescans: Sender<()>) -> (MonitorHandle, Receiver<()>) {
+        let (wake, woken) = mpsc::channel();
+        let handle = MonitorHandle::spawn(
+            Arc::new(MonitorGate::new()),
+            wake,
+            move |mut control| loop {
+                control.receive();
+                if control.take_rescan_request() {
+                    rescans.send(()).unwrap();
+                }
+                if control.shutdown_requested() {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(5));
+            },
+        );
+        (handle, woken)
+    }
+
+    #[test]
+    fn test_commands_reach_the_thread_and_shutdown_joins_it() {
+        let (rescans, rescanned) = mpsc::channel();
+        let (handle, woken) = spawn_monitor(rescans);
+
+        handle.send(MonitorCommand::Rescan).unwrap();
+        rescanned.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(woken.try_recv().is_ok());
+
+        handle.shutdown();
+        assert!(handle.send(MonitorCommand::Rescan).is_err());
+        // Safe to call again from another exit path
+        handle.shutdown();
+    }
+
+    #[test]
+    fn test_pause_and_resume_use_the_gate() {
+        let (rescans, _rescanned) = mpsc::channel();
+        let (handle, woken) = spawn_monitor(rescans);
+
+        handle.pause();
+        assert!(handle.is_paused());
+        assert!(handle.gate().enter().is_none());
+
+        handle.resume();
+        assert!(!handle.is_paused());
+        assert!(woken.try_recv().is_ok());
+        handle.shutdown();
+    }
+
+    #[test]
+    fn test_dropped_handle_stops_the_thread() {
+        let (commands, receiver) = mpsc::channel::<MonitorCommand>();
+        let mut control = MonitorControl {
+            commands: receiver,
+            diagnostics: Default::default(),
+            rescan_requested: false,
+            shutdown_requested: false,
+        };
+
+        control.receive();
+        assert!(!control.shutdown_requested());
+        drop(commands);
+        control.receive();
+        assert!(control.shutdown_requested());
+    }
+}
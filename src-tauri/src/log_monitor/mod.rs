@@ -1,3 +1,11 @@
+mod diagnostics;
+mod gate;
+mod handle;
 mod monitor;
+mod watcher;
 
+pub use diagnostics::{MonitorDiagnostics, TrackedFile};
+pub use gate::MonitorGate;
+pub use handle::{MonitorCommand, MonitorControl, MonitorHandle};
 pub use monitor::Monitor;
+pub use watcher::{LogWatcher, PollBackoff};
@@ -1,24 +1,48 @@
+use super::{MonitorDiagnostics, TrackedFile};
 use crate::{
-    db,
+    db::{
+        self,
+        operations::{self, PruneSummary, RetentionPolicy},
+    },
     event_handler::EventHandler,
     log_reader::LogReader,
+    settings::Settings,
     types::{LogEvent, VRChatEvent},
 };
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Minimum time between automatic runs of the retention rules
+const RETENTION_INTERVAL_MS: i64 = 24 * 60 * 60 * 1000;
 
 /// VRChat log monitoring service
 pub struct Monitor {
     reader: LogReader,
     handler: EventHandler,
     database: db::Database,
+    events_processed: BTreeMap<&'static str, u64>,
+    last_poll_at: Option<i64>,
 }
 
 impl Monitor {
-    pub fn new(database: db::Database) -> Self {
-        Self {
+    pub fn new(database: db::Database, settings: &Settings) -> Self {
+        let mut monitor = Self {
             reader: LogReader::new(),
             handler: EventHandler::new(),
             database,
-        }
+            events_processed: BTreeMap::new(),
+            last_poll_at: None,
+        };
+        monitor.configure(settings);
+        monitor
+    }
+
+    /// Set log folders and time zone for the next `initialize` (returns whether they changed)
+    pub fn configure(&mut self, settings: &Settings) -> bool {
+        self.handler.set_timezone(settings.local_timezone());
+        self.reader
+            .configure(settings.log_dirs(), settings.timezone())
     }
 
     /// Initialize monitor
@@ -31,6 +55,95 @@ impl Monitor {
         Ok(())
     }
 
+    /// Reload state after the database was replaced (e.g. restored from a backup).
+    /// Log lines after the restored file positions are processed again.
+    pub fn reload(&mut self) -> Result<(), String> {
+        let timezone = self.handler.timezone();
+        self.handler = EventHandler::new();
+        self.handler.set_timezone(timezone);
+
+        self.restore_state()?;
+        self.process_backlog()?;
+
+        Ok(())
+    }
+
+    /// Pick up changed log folders or time zone, rereading logs from the saved positions
+    pub fn apply_settings(&mut self, settings: &Settings) -> Result<(), String> {
+        if !self.configure(settings) {
+            return Ok(());
+        }
+
+        self.reader.initialize()?;
+        self.reload()
+    }
+
+    /// Look for log files again (e.g. copied-in old logs) and catch up from the saved positions
+    pub fn rescan(&mut self) -> Result<(), String> {
+        self.reader.initialize()?;
+        self.reload()
+    }
+
+    /// Save the final file positions and close the database
+    pub fn shutdown(mut self) -> Result<(), String> {
+        let tx = self
+            .database
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+        self.reader.save_file_states(&tx);
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+
+        self.database
+            .close()
+            .map_err(|e| format!("Failed to close database: {}", e))
+    }
+
+    /// Log directories being read
+    pub fn log_dirs(&self) -> &[PathBuf] {
+        self.reader.log_dirs()
+    }
+
+    /// Whether the last fetch found new log data (even lines without events)
+    pub fn found_new_data(&self) -> bool {
+        self.reader.found_new_data()
+    }
+
+    /// Whether the local player is in an instance
+    pub fn is_in_instance(&self) -> bool {
+        self.handler.current_instance_id().is_some()
+    }
+
+    /// Snapshot for the diagnostics command (file sizes are filled in by the caller)
+    pub fn diagnostics(&self) -> MonitorDiagnostics {
+        MonitorDiagnostics {
+            log_dirs: self
+                .reader
+                .log_dirs()
+                .iter()
+                .map(|dir| dir.to_string_lossy().to_string())
+                .collect(),
+            files: self
+                .reader
+                .file_positions()
+                .into_iter()
+                .map(|(path, position)| TrackedFile {
+                    path: path.to_string_lossy().to_string(),
+                    position,
+                    size: None,
+                })
+                .collect(),
+            last_poll_at: self.last_poll_at,
+            events_processed: self
+                .events_processed
+                .iter()
+                .map(|(kind, count)| (kind.to_string(), *count))
+                .collect(),
+            parse_misses: self.reader.parse_misses(),
+            handler: self.handler.state(),
+        }
+    }
+
     /// Restore previous state from database
     fn restore_state(&mut self) -> Result<(), String> {
         let conn = self.database.connection();
@@ -58,13 +171,15 @@ impl Monitor {
         }
 
         let processed = self.process_events(events)?;
-        println!("Processed {} backlog events", processed.len());
+        tracing::info!(events = processed.len(), "Processed backlog events");
 
         Ok(())
     }
 
     /// Fetch new events
     pub fn fetch_new_events(&mut self) -> Result<Vec<VRChatEvent>, String> {
+        self.last_poll_at = Some(Utc::now().timestamp_millis());
+
         let events = self
             .reader
             .read_new_events()
@@ -77,13 +192,39 @@ impl Monitor {
         self.process_events(events)
     }
 
+    /// Apply retention rules when they have not run for a day.
+    /// Returns None when nothing was due.
+    pub fn apply_retention_if_due(
+        &mut self,
+        policy: &RetentionPolicy,
+        now: i64,
+    ) -> Result<Option<PruneSummary>, String> {
+        let last_pruned_at = operations::get_last_pruned_at(self.database.connection())
+            .map_err(|e| format!("Failed to get last prune time: {}", e))?;
+
+        let due = last_pruned_at.is_none_or(|last| now - last >= RETENTION_INTERVAL_MS);
+        if !policy.is_enabled() || !due {
+            return Ok(None);
+        }
+
+        // Only ended instances are pruned, so handler state stays valid
+        self.database
+            .apply_retention(policy, now)
+            .map(Some)
+            .map_err(|e| format!("Failed to apply retention rules: {}", e))
+    }
+
     /// Process events within a single transaction
     fn process_events(&mut self, events: Vec<LogEvent>) -> Result<Vec<VRChatEvent>, String> {
+        self.handler.prefetch(&events);
+
         let tx = self
             .database
             .transaction()
             .map_err(|e| format!("Failed to begin transaction: {}", e))?;
 
+        let kinds: Vec<&'static str> = events.iter().map(LogEvent::kind).collect();
+
         let mut processed = Vec::new();
         for event in events {
             match self.handler.process_event(&tx, event) {
@@ -102,6 +243,10 @@ impl Monitor {
         tx.commit()
             .map_err(|e| format!("Failed to commit transaction: {}", e))?;
 
+        for kind in kinds {
+            *self.events_processed.entry(kind).or_default() += 1;
+        }
+
         Ok(processed)
     }
 }
@@ -0,0 +1,170 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::time::Duration;
+
+/// Shortest interval between polls while in an instance
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Longest interval between polls while no log is being written
+const MAX_IDLE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Consecutive polls without new log data before the interval doubles
+const IDLE_POLLS_PER_STEP: u32 = 30;
+
+/// Wakes the monitor when a log file changes, with polling as the fallback.
+/// VRChat buffers its writes, so file system events alone can miss new lines.
+pub struct LogWatcher {
+    watcher: Option<RecommendedWatcher>,
+    watched: Vec<PathBuf>,
+    wake: Sender<()>,
+    changes: Receiver<()>,
+}
+
+impl LogWatcher {
+    pub fn new() -> Self {
+        let (wake, changes) = mpsc::channel();
+        let sender = wake.clone();
+
+        let watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+            if let Ok(event) = result {
+                if event.paths.iter().any(|path| is_log_file(path)) {
+                    let _ = sender.send(());
+                }
+            }
+        });
+
+        let watcher = match watcher {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                tracing::error!("Failed to start file watcher, polling only: {}", e);
+                None
+            }
+        };
+
+        Self {
+            watcher,
+            watched: Vec::new(),
+            wake,
+            changes,
+        }
+    }
+
+    /// Sender that interrupts `wait` from another thread
+    pub fn waker(&self) -> Sender<()> {
+        self.wake.clone()
+    }
+
+    /// Watch the given log folders instead of the previous ones
+    pub fn watch(&mut self, log_dirs: &[PathBuf]) {
+        let Some(watcher) = self.watcher.as_mut() else {
+            return;
+        };
+        if self.watched == log_dirs {
+            return;
+        }
+
+        for dir in self.watched.drain(..) {
+            let _ = watcher.unwatch(&dir);
+        }
+        for dir in log_dirs {
+            match watcher.watch(dir, RecursiveMode::NonRecursive) {
+                Ok(()) => self.watched.push(dir.clone()),
+                Err(e) => tracing::error!("Failed to watch {:?}, polling only: {}", dir, e),
+            }
+        }
+    }
+
+    /// Sleep until a log file changes, `waker` is used or `timeout` passes
+    pub fn wait(&self, timeout: Duration) {
+        // Bursts of events are read in one poll
+        if self.changes.recv_timeout(timeout).is_ok() {
+            while self.changes.try_recv().is_ok() {}
+        }
+    }
+}
+
+/// Chooses the poll interval from recent log activity
+#[derive(Debug, Default)]
+pub struct PollBackoff {
+    idle_polls: u32,
+}
+
+impl PollBackoff {
+    /// Record whether the last poll found new log data
+    pub fn record(&mut self, found_data: bool) {
+        self.idle_polls = if found_data {
+            0
+        } else {
+            self.idle_polls.saturating_add(1)
+        };
+    }
+
+    /// Poll faster while in an instance; back off while no log is being written
+    pub fn interval(&self, base: Duration, in_instance: bool) -> Duration {
+        if in_instance {
+            return (base / 2).max(MIN_POLL_INTERVAL).min(base);
+        }
+
+        let steps = (self.idle_polls / IDLE_POLLS_PER_STEP).min(16);
+        (base * (1 << steps)).min(MAX_IDLE_INTERVAL.max(base))
+    }
+}
+
+fn is_log_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("output_log") && name.ends_with(".txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn test_backoff_while_idle() {
+        let mut backoff = PollBackoff::default();
+        assert_eq!(backoff.interval(BASE, false), BASE);
+
+        for _ in 0..IDLE_POLLS_PER_STEP {
+            backoff.record(false);
+        }
+        assert_eq!(backoff.interval(BASE, false), BASE * 2);
+
+        for _ in 0..IDLE_POLLS_PER_STEP * 20 {
+            backoff.record(false);
+        }
+        assert_eq!(backoff.interval(BASE, false), MAX_IDLE_INTERVAL);
+
+        backoff.record(true);
+        assert_eq!(backoff.interval(BASE, false), BASE);
+    }
+
+    #[test]
+    fn test_faster_in_instance() {
+        let mut backoff = PollBackoff::default();
+        for _ in 0..IDLE_POLLS_PER_STEP * 4 {
+            backoff.record(false);
+        }
+
+        assert_eq!(backoff.interval(BASE, true), BASE / 2);
+        assert_eq!(
+            backoff.interval(Duration::from_millis(150), true),
+            MIN_POLL_INTERVAL
+        );
+        assert_eq!(
+            backoff.interval(Duration::from_millis(100), true),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[test]
+    fn test_is_log_file() {
+        assert!(is_log_file(Path::new(
+            "VRChat/output_log_2025-10-13_09-53-16.txt"
+        )));
+        assert!(!is_log_file(Path::new("VRChat/Player.log")));
+    }
+}
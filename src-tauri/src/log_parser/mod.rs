@@ -1,3 +1,3 @@
 mod parser;
 
-pub use parser::LogParser;
+pub use parser::{LogClock, LogParser};
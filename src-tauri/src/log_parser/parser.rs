@@ -1,6 +1,35 @@
 use crate::types::LogEvent;
-use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+use chrono::{DateTime, LocalResult, NaiveDateTime, Offset, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
 use regex::Regex;
+use std::collections::BTreeMap;
+
+/// Length of the "2025.10.13 09:53:16" prefix of log lines
+const TIMESTAMP_LEN: usize = 19;
+
+const TIMESTAMP_FORMAT: &str = "%Y.%m.%d %H:%M:%S";
+
+/// How far a UTC hint may be from its line's local time on the 15-minute offset grid
+const UTC_HINT_TOLERANCE_SECS: i64 = 2 * 60;
+
+/// How far a line may be written before the previous one and still count as in order
+const OUT_OF_ORDER_TOLERANCE: TimeDelta = TimeDelta::minutes(1);
+
+/// Timestamp reading state of one log file
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LogClock {
+    pub timezone: Option<Tz>, // Time zone of the file (None uses the parser's)
+    pub last: Option<DateTime<Utc>>, // Previous timestamped line, for times repeated by DST
+}
+
+impl LogClock {
+    pub fn new(timezone: Option<Tz>) -> Self {
+        Self {
+            timezone,
+            last: None,
+        }
+    }
+}
 
 pub struct LogParser {
     auth_regex: Regex,
@@ -11,6 +40,11 @@ pub struct LogParser {
     screenshot_regex: Regex,
     leaving_instance_regex: Regex,
     event_sync_failed_regex: Regex,
+    build_regex: Regex,
+    vr_mode_regex: Regex,
+    application_quit_regex: Regex,
+    utc_hint_regex: Regex,
+    timezone: Tz, // Time zone of log files without their own
 }
 
 impl LogParser {
@@ -55,90 +89,290 @@ impl LogParser {
             event_sync_failed_regex: Regex::new(
                 r"(\d{4}\.\d{2}\.\d{2} \d{2}:\d{2}:\d{2}) .* \[Behaviour\] Master is not sending any events! Moving to a new instance\."
             ).unwrap(),
+
+            // 2025.10.13 09:53:05 Debug      -  [Always] VRChat Build: 2025.3.4p2-1677--Release
+            build_regex: Regex::new(
+                r"(\d{4}\.\d{2}\.\d{2} \d{2}:\d{2}:\d{2}) .* \[Always\] (?:VRChat )?Build: (.+)"
+            ).unwrap(),
+
+            // 2025.10.13 09:53:06 Debug      -  Initializing VRSDK.
+            // 2025.10.13 09:53:06 Debug      -  VR Disabled
+            vr_mode_regex: Regex::new(
+                r"(\d{4}\.\d{2}\.\d{2} \d{2}:\d{2}:\d{2}) .*  -  (Initializing VRSDK\.|VR Disabled)$"
+            ).unwrap(),
+
+            // 2025.10.13 23:59:59 Debug      -  VRCApplication: OnApplicationQuit at 50412.33
+            application_quit_regex: Regex::new(
+                r"(\d{4}\.\d{2}\.\d{2} \d{2}:\d{2}:\d{2}) .* VRCApplication: OnApplicationQuit at"
+            ).unwrap(),
+
+            // UTC time written on the same line as the local timestamp
+            // 2025.10.13 09:53:20 Debug      -  ... 2025-10-13T00:53:20.412Z ...
+            utc_hint_regex: Regex::new(
+                r"(\d{4}-\d{2}-\d{2})[T ](\d{2}:\d{2}:\d{2})(?:\.\d+)?(?:Z| UTC)"
+            ).unwrap(),
+
+            timezone: Tz::UTC,
         }
     }
 
+    /// Set the time zone of log files whose clock has none
+    pub fn set_timezone(&mut self, timezone: Tz) {
+        self.timezone = timezone;
+    }
+
+    pub fn timezone(&self) -> Tz {
+        self.timezone
+    }
+
+    #[cfg(test)]
     pub fn parse_line(&self, line: &str) -> Option<LogEvent> {
+        self.parse(line, &mut LogClock::default()).ok().flatten()
+    }
+
+    /// Parse a line, failing when it matches an event whose timestamp cannot be read.
+    /// Lines of one file must be passed in order with the same clock.
+    pub fn parse(&self, line: &str, clock: &mut LogClock) -> Result<Option<LogEvent>, String> {
         if let Some(caps) = self.auth_regex.captures(line) {
-            return Some(LogEvent::UserAuthenticated {
-                timestamp: parse_timestamp(&caps[1]).ok()?,
+            return Ok(Some(LogEvent::UserAuthenticated {
+                timestamp: self.timestamp(&caps[1], clock)?,
                 display_name: caps[2].to_string(),
                 user_id: caps[3].to_string(),
-            });
+            }));
         }
 
         if let Some(caps) = self.joining_regex.captures(line) {
-            return Some(LogEvent::JoiningWorld {
-                timestamp: parse_timestamp(&caps[1]).ok()?,
+            return Ok(Some(LogEvent::JoiningWorld {
+                timestamp: self.timestamp(&caps[1], clock)?,
                 world_id: caps[2].to_string(),
                 instance_id: caps[3].to_string(),
-            });
+            }));
         }
 
         if let Some(caps) = self.entering_room_regex.captures(line) {
-            return Some(LogEvent::EnteringRoom {
-                timestamp: parse_timestamp(&caps[1]).ok()?,
+            return Ok(Some(LogEvent::EnteringRoom {
+                timestamp: self.timestamp(&caps[1], clock)?,
                 world_name: caps[2].to_string(),
-            });
+            }));
         }
 
         if let Some(caps) = self.player_joined_regex.captures(line) {
-            return Some(LogEvent::PlayerJoined {
-                timestamp: parse_timestamp(&caps[1]).ok()?,
+            return Ok(Some(LogEvent::PlayerJoined {
+                timestamp: self.timestamp(&caps[1], clock)?,
                 display_name: caps[2].to_string(),
                 user_id: caps[3].to_string(),
-            });
+            }));
         }
 
         if let Some(caps) = self.avatar_changed_regex.captures(line) {
-            return Some(LogEvent::AvatarChanged {
-                timestamp: parse_timestamp(&caps[1]).ok()?,
+            return Ok(Some(LogEvent::AvatarChanged {
+                timestamp: self.timestamp(&caps[1], clock)?,
                 display_name: caps[2].to_string(),
                 avatar_name: caps[3].to_string(),
-            });
+            }));
         }
 
         if let Some(caps) = self.screenshot_regex.captures(line) {
-            return Some(LogEvent::ScreenshotTaken {
-                timestamp: parse_timestamp(&caps[1]).ok()?,
+            return Ok(Some(LogEvent::ScreenshotTaken {
+                timestamp: self.timestamp(&caps[1], clock)?,
                 file_path: caps[2].to_string(),
-            });
+            }));
         }
 
         if let Some(caps) = self.leaving_instance_regex.captures(line) {
-            return Some(LogEvent::DestroyingPlayer {
-                timestamp: parse_timestamp(&caps[1]).ok()?,
+            return Ok(Some(LogEvent::DestroyingPlayer {
+                timestamp: self.timestamp(&caps[1], clock)?,
                 display_name: caps[2].to_string(),
-            });
+            }));
         }
 
         if let Some(caps) = self.event_sync_failed_regex.captures(line) {
-            return Some(LogEvent::EventSyncFailed {
-                timestamp: parse_timestamp(&caps[1]).ok()?,
-            });
+            return Ok(Some(LogEvent::EventSyncFailed {
+                timestamp: self.timestamp(&caps[1], clock)?,
+            }));
         }
 
-        None
+        if let Some(caps) = self.build_regex.captures(line) {
+            return Ok(Some(LogEvent::ClientVersion {
+                timestamp: self.timestamp(&caps[1], clock)?,
+                version: caps[2].trim().to_string(),
+            }));
+        }
+
+        if let Some(caps) = self.vr_mode_regex.captures(line) {
+            return Ok(Some(LogEvent::VrModeDetected {
+                timestamp: self.timestamp(&caps[1], clock)?,
+                vr: &caps[2] != "VR Disabled",
+            }));
+        }
+
+        if let Some(caps) = self.application_quit_regex.captures(line) {
+            return Ok(Some(LogEvent::ApplicationQuit {
+                timestamp: self.timestamp(&caps[1], clock)?,
+            }));
+        }
+
+        // Lines without events still move the clock forward
+        self.line_timestamp(line, clock);
+        Ok(None)
+    }
+
+    /// Timestamp at the start of any log line (None for continuation lines)
+    pub fn line_timestamp(&self, line: &str, clock: &mut LogClock) -> Option<DateTime<Utc>> {
+        let prefix = line.get(..TIMESTAMP_LEN)?;
+        self.timestamp(prefix, clock).ok()
+    }
+
+    /// Time zone of a log from the UTC times some lines carry next to their local timestamp.
+    /// Returns the parser's zone when its offset matches, otherwise a fixed-offset zone
+    /// (None without hints or for offsets that are not whole hours).
+    pub fn detect_timezone(&self, content: &str) -> Option<Tz> {
+        let mut offsets: BTreeMap<i64, (usize, NaiveDateTime)> = BTreeMap::new();
+
+        for line in content.lines() {
+            let Some(caps) = self.utc_hint_regex.captures(line) else {
+                continue;
+            };
+            let local = line
+                .get(..TIMESTAMP_LEN)
+                .and_then(|prefix| NaiveDateTime::parse_from_str(prefix, TIMESTAMP_FORMAT).ok());
+            let utc = NaiveDateTime::parse_from_str(
+                &format!("{} {}", &caps[1], &caps[2]),
+                "%Y-%m-%d %H:%M:%S",
+            );
+            if let (Some(local), Ok(utc)) = (local, utc) {
+                if let Some(offset) = utc_offset(local - utc) {
+                    offsets.entry(offset).or_insert((0, utc)).0 += 1;
+                }
+            }
+        }
+
+        // The offset most lines agree on (other UTC times in a line are not "now")
+        let (offset, (_, at)) = offsets.into_iter().max_by_key(|(_, (count, _))| *count)?;
+
+        if i64::from(
+            self.timezone
+                .offset_from_utc_datetime(&at)
+                .fix()
+                .local_minus_utc(),
+        ) == offset
+        {
+            return Some(self.timezone);
+        }
+        if offset % 3600 != 0 {
+            return None;
+        }
+
+        // Etc zones count hours west of UTC, so their signs are reversed
+        match offset / 3600 {
+            0 => Some(Tz::UTC),
+            hours => format!("Etc/GMT{:+}", -hours).parse().ok(),
+        }
+    }
+
+    /// Convert a VRChat timestamp to UTC in the clock's time zone and advance the clock
+    fn timestamp(
+        &self,
+        timestamp_str: &str,
+        clock: &mut LogClock,
+    ) -> Result<DateTime<Utc>, String> {
+        let naive = NaiveDateTime::parse_from_str(timestamp_str, TIMESTAMP_FORMAT)
+            .map_err(|e| format!("Failed to parse timestamp: {}", e))?;
+
+        let utc = to_utc(clock.timezone.unwrap_or(self.timezone), naive, clock.last);
+
+        clock.last = Some(utc);
+        Ok(utc)
     }
 }
 
-/// Parse VRChat timestamp and convert to UTC
-fn parse_timestamp(timestamp_str: &str) -> Result<DateTime<Utc>, String> {
-    NaiveDateTime::parse_from_str(timestamp_str, "%Y.%m.%d %H:%M:%S")
-        .map_err(|e| format!("Failed to parse timestamp: {}", e))
-        .and_then(|naive| {
-            Local
-                .from_local_datetime(&naive)
-                .single()
-                .ok_or_else(|| "Ambiguous or invalid local datetime".to_string())
-        })
-        .map(|local| local.with_timezone(&Utc))
+/// UTC offset in seconds between a local and a UTC time written at the same moment
+/// (None when it is not near a real offset, i.e. a multiple of 15 minutes up to -12 h..+14 h)
+fn utc_offset(difference: TimeDelta) -> Option<i64> {
+    const STEP: i64 = 15 * 60;
+    let seconds = difference.num_seconds();
+    let offset = (seconds + STEP / 2).div_euclid(STEP) * STEP;
+
+    let near_grid = (seconds - offset).abs() <= UTC_HINT_TOLERANCE_SECS;
+    let in_range = (-12 * 3600..=14 * 3600).contains(&offset);
+    (near_grid && in_range).then_some(offset)
+}
+
+/// Convert a local log time to UTC.
+/// Times repeated when clocks fall back are the first occurrence until the log has moved past it;
+/// times skipped when clocks spring forward are read with the offset from before the change.
+fn to_utc(tz: Tz, naive: NaiveDateTime, last: Option<DateTime<Utc>>) -> DateTime<Utc> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt.with_timezone(&Utc),
+        LocalResult::Ambiguous(earliest, latest) => {
+            let earliest = earliest.with_timezone(&Utc);
+            match last {
+                Some(last) if earliest < last - OUT_OF_ORDER_TOLERANCE => {
+                    latest.with_timezone(&Utc)
+                }
+                _ => earliest,
+            }
+        }
+        LocalResult::None => {
+            let offset = tz
+                .offset_from_utc_datetime(&(naive - TimeDelta::days(1)))
+                .fix();
+            let utc = naive - TimeDelta::seconds(offset.local_minus_utc().into());
+            Utc.from_utc_datetime(&utc)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_game_session_lines() {
+        let parser = LogParser::new();
+
+        let line =
+            "2025.10.13 09:53:05 Debug      -  [Always] VRChat Build: 2025.3.4p2-1677--Release";
+        match parser.parse_line(line) {
+            Some(LogEvent::ClientVersion { version, .. }) => {
+                assert_eq!(version, "2025.3.4p2-1677--Release");
+            }
+            other => panic!("Expected ClientVersion event, got {:?}", other),
+        }
+
+        let vr = parser.parse_line("2025.10.13 09:53:06 Debug      -  Initializing VRSDK.");
+        assert!(matches!(
+            vr,
+            Some(LogEvent::VrModeDetected { vr: true, .. })
+        ));
+        let desktop = parser.parse_line("2025.10.13 09:53:06 Debug      -  VR Disabled");
+        assert!(matches!(
+            desktop,
+            Some(LogEvent::VrModeDetected { vr: false, .. })
+        ));
+
+        let line =
+            "2025.10.13 23:59:59 Debug      -  VRCApplication: OnApplicationQuit at 50412.33";
+        assert!(matches!(
+            parser.parse_line(line),
+            Some(LogEvent::ApplicationQuit { .. })
+        ));
+    }
+
+    #[test]
+    fn test_line_timestamp() {
+        let parser = LogParser::new();
+        let line = "2025.10.13 09:53:16 Debug      -  [Behaviour] Initialized PlayerAPI";
+
+        let mut clock = LogClock::default();
+
+        assert!(parser.line_timestamp(line, &mut clock).is_some());
+        assert!(parser
+            .line_timestamp("  at UnityEngine.Debug.Log", &mut clock)
+            .is_none());
+        assert!(parser.line_timestamp("", &mut clock).is_none());
+    }
+
     #[test]
     fn test_parse_user_authenticated() {
         let parser = LogParser::new();
@@ -329,4 +563,158 @@ mod tests {
         let event = parser.parse_line(line);
         assert!(event.is_none());
     }
+
+    #[test]
+    fn test_parse_with_timezone() {
+        let parser = LogParser::new();
+        let mut clock = LogClock::new(Some(chrono_tz::Asia::Tokyo));
+        let line = "2025.10.19 08:10:44 Error      -  [Behaviour] Master is not sending any events! Moving to a new instance.";
+
+        match parser.parse(line, &mut clock) {
+            Ok(Some(LogEvent::EventSyncFailed { timestamp })) => {
+                assert_eq!(timestamp.to_rfc3339(), "2025-10-18T23:10:44+00:00");
+            }
+            _ => panic!("Expected EventSyncFailed event"),
+        }
+    }
+
+    fn read_times(lines: &[&str]) -> Vec<String> {
+        let parser = LogParser::new();
+        let mut clock = LogClock::new(Some(chrono_tz::America::New_York));
+
+        lines
+            .iter()
+            .map(|line| {
+                let line = format!("{} Debug      -  [Behaviour] Destroying Friend", line);
+                match parser.parse(&line, &mut clock) {
+                    Ok(Some(LogEvent::DestroyingPlayer { timestamp, .. })) => {
+                        timestamp.to_rfc3339()
+                    }
+                    other => panic!("Expected DestroyingPlayer event, got {:?}", other),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fall_back_hour_follows_log_order() {
+        // 2025-11-02 01:00-02:00 happens twice in New York (EDT, then EST)
+        let times = read_times(&[
+            "2025.11.02 01:30:00",
+            "2025.11.02 01:59:59",
+            "2025.11.02 01:59:58", // Slightly out of order, still the first pass
+            "2025.11.02 01:00:05",
+            "2025.11.02 01:30:00",
+            "2025.11.02 02:00:00",
+        ]);
+
+        assert_eq!(
+            times,
+            [
+                "2025-11-02T05:30:00+00:00",
+                "2025-11-02T05:59:59+00:00",
+                "2025-11-02T05:59:58+00:00",
+                "2025-11-02T06:00:05+00:00",
+                "2025-11-02T06:30:00+00:00",
+                "2025-11-02T07:00:00+00:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fall_back_hour_after_quiet_lines() {
+        let parser = LogParser::new();
+        let mut clock = LogClock::new(Some(chrono_tz::America::New_York));
+
+        // Lines without events tell the parser the clock has already gone back
+        for line in [
+            "2025.11.02 01:50:00 Log        -  Something unrelated",
+            "2025.11.02 01:05:00 Log        -  Something unrelated",
+        ] {
+            assert!(parser.parse(line, &mut clock).unwrap().is_none());
+        }
+
+        let line = "2025.11.02 01:10:00 Debug      -  [Behaviour] Destroying Friend";
+        match parser.parse(line, &mut clock) {
+            Ok(Some(LogEvent::DestroyingPlayer { timestamp, .. })) => {
+                assert_eq!(timestamp.to_rfc3339(), "2025-11-02T06:10:00+00:00");
+            }
+            other => panic!("Expected DestroyingPlayer event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_spring_forward_hour() {
+        // 2025-03-09 02:00-03:00 does not exist in New York
+        let times = read_times(&[
+            "2025.03.09 01:59:59",
+            "2025.03.09 02:30:00",
+            "2025.03.09 03:00:00",
+        ]);
+
+        assert_eq!(
+            times,
+            [
+                "2025-03-09T06:59:59+00:00",
+                "2025-03-09T07:30:00+00:00", // Read as EST, like the lines before it
+                "2025-03-09T07:00:00+00:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_file_timezone_overrides_parser() {
+        let mut parser = LogParser::new();
+        parser.set_timezone(chrono_tz::Asia::Tokyo);
+        let line = "2025.10.19 08:10:44 Error      -  [Behaviour] Master is not sending any events! Moving to a new instance.";
+
+        let mut clock = LogClock::new(Some(chrono_tz::Europe::London));
+        match parser.parse(line, &mut clock) {
+            Ok(Some(LogEvent::EventSyncFailed { timestamp })) => {
+                assert_eq!(timestamp.to_rfc3339(), "2025-10-19T07:10:44+00:00");
+            }
+            other => panic!("Expected EventSyncFailed event, got {:?}", other),
+        }
+
+        // Files without their own zone use the parser's
+        match parser.parse(line, &mut LogClock::default()) {
+            Ok(Some(LogEvent::EventSyncFailed { timestamp })) => {
+                assert_eq!(timestamp.to_rfc3339(), "2025-10-18T23:10:44+00:00");
+            }
+            other => panic!("Expected EventSyncFailed event, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_detect_timezone() {
+        let mut parser = LogParser::new();
+        let content = [
+            "2025.10.13 09:53:05 Debug      -  [Always] VRChat Build: 2025.3.4p2-1677--Release",
+            "2025.10.13 09:53:20 Debug      -  [API] Response at 2025-10-13T00:53:19.512Z",
+            "2025.10.13 09:53:21 Debug      -  [API] World updated 2024-02-01T17:20:00Z",
+            "2025.10.13 09:54:02 Debug      -  [API] Response at 2025-10-13T00:54:02Z",
+        ]
+        .join("\n");
+
+        // Tokyo has the detected offset, so the parser's zone is kept
+        parser.set_timezone(chrono_tz::Asia::Tokyo);
+        assert_eq!(
+            parser.detect_timezone(&content),
+            Some(chrono_tz::Asia::Tokyo)
+        );
+        let utc = "2025.10.13 00:53:20 Debug      -  Session started 2025-10-13 00:53:20 UTC";
+        assert_eq!(parser.detect_timezone(utc), Some(Tz::UTC));
+
+        parser.set_timezone(chrono_tz::Europe::London);
+        assert_eq!(
+            parser.detect_timezone(&content),
+            Some(chrono_tz::Etc::GMTMinus9)
+        );
+        let india = "2025.10.13 06:23:20 Debug      -  [API] Response at 2025-10-13T00:53:20Z";
+        assert_eq!(parser.detect_timezone(india), None);
+        assert_eq!(
+            parser.detect_timezone("2025.10.13 09:53:05 Debug      -  No hints"),
+            None
+        );
+    }
 }
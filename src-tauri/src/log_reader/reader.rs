@@ -1,40 +1,111 @@
-use crate::{db, log_parser::LogParser, types::LogEvent};
-use chrono::Utc;
+use crate::{
+    db,
+    log_parser::{LogClock, LogParser},
+    settings,
+    types::LogEvent,
+};
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use rusqlite::Connection;
 use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::{Read, Seek, SeekFrom};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The newest log is treated as ended (VRChat is gone) when it has not been written for this long
+const STALE_LOG_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Bytes at the end of a log searched for its last timestamp
+const TAIL_BYTES: u64 = 64 * 1024;
 
 pub struct LogReader {
-    log_dir: PathBuf,
+    configured_dirs: Vec<PathBuf>, // Empty uses the default VRChat log folder
+    log_dirs: Vec<PathBuf>,
     file_states: HashMap<PathBuf, u64>,
+    clocks: HashMap<PathBuf, LogClock>, // Time zone and last timestamp of each file
+    new_timezones: HashMap<PathBuf, Tz>, // Time zones detected in files, not saved yet
     parser: LogParser,
+    found_new_data: bool, // Whether the last read_new_events found any file grown
+    parse_misses: u64,    // Event lines whose timestamp could not be read
+    ended_files: HashMap<PathBuf, Option<DateTime<Utc>>>, // Stale logs reported as ended -> end time
 }
 
 impl LogReader {
     pub fn new() -> Self {
         Self {
-            log_dir: PathBuf::new(),
+            configured_dirs: Vec::new(),
+            log_dirs: Vec::new(),
             file_states: HashMap::new(),
+            clocks: HashMap::new(),
+            new_timezones: HashMap::new(),
             parser: LogParser::new(),
+            found_new_data: false,
+            parse_misses: 0,
+            ended_files: HashMap::new(),
         }
     }
 
-    /// Validate and set log directory
-    pub fn initialize(&mut self) -> Result<(), String> {
-        self.log_dir = Self::get_vrchat_log_path()?;
+    /// Set the log folders and the time zone of log files without their own
+    /// (None uses the system's; returns whether anything changed).
+    /// Takes effect on the next `initialize`.
+    pub fn configure(&mut self, log_dirs: Vec<PathBuf>, timezone: Option<Tz>) -> bool {
+        let timezone = timezone.unwrap_or_else(settings::system_timezone);
+        let changed = self.configured_dirs != log_dirs || self.parser.timezone() != timezone;
+        self.configured_dirs = log_dirs;
+        self.parser.set_timezone(timezone);
+        changed
+    }
 
-        if !self.log_dir.exists() {
-            return Err(format!("Log directory not found: {:?}", self.log_dir));
+    /// Validate and set log directories
+    pub fn initialize(&mut self) -> Result<(), String> {
+        self.log_dirs = if self.configured_dirs.is_empty() {
+            vec![Self::get_vrchat_log_path()?]
+        } else {
+            self.configured_dirs.clone()
+        };
+
+        for log_dir in &self.log_dirs {
+            if !log_dir.exists() {
+                return Err(format!("Log directory not found: {:?}", log_dir));
+            }
         }
 
         Ok(())
     }
 
-    /// Restore file positions from database
+    /// Log directories in use since the last `initialize`
+    pub fn log_dirs(&self) -> &[PathBuf] {
+        &self.log_dirs
+    }
+
+    /// Whether the last `read_new_events` found new log data
+    pub fn found_new_data(&self) -> bool {
+        self.found_new_data
+    }
+
+    /// Number of event lines skipped because their timestamp could not be read
+    pub fn parse_misses(&self) -> u64 {
+        self.parse_misses
+    }
+
+    /// Tracked log files with their read positions, oldest first
+    pub fn file_positions(&self) -> Vec<(PathBuf, u64)> {
+        let mut files: Vec<(PathBuf, u64)> = self
+            .file_states
+            .iter()
+            .map(|(path, position)| (path.clone(), *position))
+            .collect();
+        files.sort_by(|(a, _), (b, _)| a.file_name().cmp(&b.file_name()).then_with(|| a.cmp(b)));
+        files
+    }
+
+    /// Restore file positions and time zones from database
     pub fn restore_file_positions(&mut self, conn: &Connection) -> Result<(), String> {
         let log_files = self.get_all_log_files()?;
+        self.file_states.clear();
+        self.clocks.clear();
+        self.new_timezones.clear();
 
         for log_file in log_files {
             let path_str = log_file.to_string_lossy().to_string();
@@ -44,26 +115,62 @@ impl LogReader {
                 .unwrap_or(Some(0))
                 .unwrap_or(0);
 
+            let timezone = stored_timezone(conn, &path_str);
             self.file_states.insert(log_file.clone(), position);
+            self.clocks.insert(log_file, LogClock::new(timezone));
         }
 
         Ok(())
     }
 
+    /// Read a whole log file outside the log folders (e.g. copied from another machine),
+    /// continuing from its saved position. The file is treated as finished,
+    /// so its end closes whatever is still open.
+    pub fn read_finished_file(
+        &mut self,
+        conn: &Connection,
+        file_path: &Path,
+    ) -> Result<Vec<LogEvent>, String> {
+        let file_path = file_path.to_path_buf();
+        let path_str = file_path.to_string_lossy().to_string();
+
+        let position = db::operations::get_log_file_position(conn, &path_str)
+            .map_err(|e| format!("Failed to get log file position: {}", e))?
+            .unwrap_or(0);
+        let timezone = stored_timezone(conn, &path_str);
+        self.clocks
+            .insert(file_path.clone(), LogClock::new(timezone));
+
+        let (mut events, final_position) = self.read_file_from_position(&file_path, position)?;
+        if final_position > position {
+            events.extend(
+                self.last_timestamp(&file_path)
+                    .map(|timestamp| LogEvent::LogEnded { timestamp }),
+            );
+        }
+
+        self.file_states.insert(file_path, final_position);
+        Ok(events)
+    }
+
     /// Read backlog events (from last position to current)
     pub fn read_backlog(&mut self) -> Result<Vec<LogEvent>, String> {
         let log_files = self.get_all_log_files()?;
         let mut all_events = Vec::new();
 
-        for log_file in log_files {
-            let start_position = self.file_states.get(&log_file).copied().unwrap_or(0);
+        for (index, log_file) in log_files.iter().enumerate() {
+            let start_position = self.file_states.get(log_file).copied().unwrap_or(0);
             let (events, final_position) =
-                self.read_file_from_position(&log_file, start_position)?;
+                self.read_file_from_position(log_file, start_position)?;
 
+            if start_position == 0 && final_position > 0 {
+                all_events.extend(self.end_of_previous_file(&log_files, index));
+            }
             self.file_states.insert(log_file.clone(), final_position);
             all_events.extend(events);
         }
 
+        all_events.extend(self.end_of_stale_file(&log_files));
         Ok(all_events)
     }
 
@@ -71,33 +178,37 @@ impl LogReader {
     pub fn read_new_events(&mut self) -> Result<Vec<LogEvent>, String> {
         let log_files = self.get_all_log_files()?;
         let mut all_events = Vec::new();
+        self.found_new_data = false;
 
-        for file_path in log_files {
-            let metadata = match fs::metadata(&file_path) {
+        for (index, file_path) in log_files.iter().enumerate() {
+            let metadata = match fs::metadata(file_path) {
                 Ok(m) => m,
                 Err(_) => continue,
             };
             let current_size = metadata.len();
 
-            let position = self.file_states.get(&file_path).copied().unwrap_or(0);
+            let position = self.file_states.get(file_path).copied().unwrap_or(0);
 
             if current_size > position {
                 if position == 0 {
-                    println!("New log file detected: {:?}", file_path);
+                    tracing::info!(path = ?file_path, "New log file detected");
+                    all_events.extend(self.end_of_previous_file(&log_files, index));
                 }
+                all_events.extend(self.resume_of_stale_file(&log_files, index));
 
-                let (events, final_position) =
-                    self.read_file_from_position(&file_path, position)?;
+                let (events, final_position) = self.read_file_from_position(file_path, position)?;
                 self.file_states.insert(file_path.clone(), final_position);
+                self.found_new_data = true;
                 all_events.extend(events);
             }
         }
 
+        all_events.extend(self.end_of_stale_file(&log_files));
         Ok(all_events)
     }
 
-    /// Save file states to database
-    pub fn save_file_states(&self, conn: &Connection) {
+    /// Save file states and newly detected time zones to database
+    pub fn save_file_states(&mut self, conn: &Connection) {
         for (path, position) in self.file_states.iter() {
             let path_str = path.to_string_lossy().to_string();
             if let Ok(metadata) = fs::metadata(path) {
@@ -110,10 +221,116 @@ impl LogReader {
                 }
             }
         }
+
+        for (path, timezone) in self.new_timezones.drain() {
+            let path_str = path.to_string_lossy().to_string();
+            let _ = db::operations::set_log_file_timezone(conn, &path_str, Some(timezone.name()));
+        }
     }
 
-    fn read_file_from_position(
+    /// End of the log before `log_files[index]` when that file starts a new VRChat session.
+    /// Old logs copied in later than newer ones end nothing.
+    fn end_of_previous_file(&self, log_files: &[PathBuf], index: usize) -> Option<LogEvent> {
+        let file_name = log_files[index].file_name();
+        let newer_file_read = self
+            .file_states
+            .iter()
+            .any(|(path, position)| *position > 0 && path.file_name() > file_name);
+        if index == 0 || newer_file_read {
+            return None;
+        }
+
+        let timestamp = self.last_timestamp(&log_files[index - 1])?;
+        Some(LogEvent::LogEnded { timestamp })
+    }
+
+    /// End of the newest log once VRChat has stopped writing to it (reported once until it grows)
+    fn end_of_stale_file(&mut self, log_files: &[PathBuf]) -> Option<LogEvent> {
+        let newest = log_files.last()?;
+        if self.ended_files.contains_key(newest) {
+            return None;
+        }
+
+        let modified = fs::metadata(newest).and_then(|m| m.modified()).ok()?;
+        if modified.elapsed().unwrap_or_default() < STALE_LOG_TIMEOUT {
+            return None;
+        }
+
+        let timestamp = self.last_timestamp(newest);
+        self.ended_files.insert(newest.clone(), timestamp);
+        Some(LogEvent::LogEnded {
+            timestamp: timestamp?,
+        })
+    }
+
+    /// Undo `end_of_stale_file` when VRChat writes to a log reported as ended
+    /// (it was only idle). Not once a newer log has started.
+    fn resume_of_stale_file(&mut self, log_files: &[PathBuf], index: usize) -> Option<LogEvent> {
+        let ended_at = self.ended_files.remove(&log_files[index])??;
+        if index + 1 < log_files.len() {
+            return None;
+        }
+
+        tracing::info!(path = ?log_files[index], "Log reported as ended was written again");
+        Some(LogEvent::LogResumed {
+            ended_at,
+            file_path: log_files[index].to_string_lossy().to_string(),
+        })
+    }
+
+    /// Reading state of a file (a fresh clock in the default time zone for new files)
+    fn clock(&self, file_path: &Path) -> LogClock {
+        self.clocks.get(file_path).copied().unwrap_or_default()
+    }
+
+    /// Time zone of a file without its own, from UTC hints in newly read lines.
+    /// Detected zones are saved with the file states; files without hints keep
+    /// the default zone and are checked again when they grow.
+    fn detect_timezone(&mut self, file_path: &Path, content: &str) -> Option<Tz> {
+        let timezone = self.parser.detect_timezone(content)?;
+        tracing::info!(path = ?file_path, %timezone, "Detected log time zone");
+
+        self.new_timezones.insert(file_path.to_path_buf(), timezone);
+        Some(timezone)
+    }
+
+    /// Time of the last line in a log file
+    fn last_timestamp(&self, file_path: &Path) -> Option<DateTime<Utc>> {
+        let mut file = File::open(file_path).ok()?;
+        let size = file.metadata().ok()?.len();
+        file.seek(SeekFrom::Start(size.saturating_sub(TAIL_BYTES)))
+            .ok()?;
+
+        let mut buffer = Vec::new();
+        file.read_to_end(&mut buffer).ok()?;
+
+        // Read forward so times repeated by DST follow the log order
+        let mut clock = LogClock::new(self.clock(file_path).timezone);
+        String::from_utf8_lossy(&buffer)
+            .lines()
+            .filter_map(|line| self.parser.line_timestamp(line, &mut clock))
+            .last()
+    }
+
+    /// Time of the first line of a new log (the file time until a timestamped line is written)
+    fn first_timestamp(
         &self,
+        file_path: &Path,
+        content: &str,
+        mut clock: LogClock,
+    ) -> DateTime<Utc> {
+        content
+            .lines()
+            .find_map(|line| self.parser.line_timestamp(line, &mut clock))
+            .or_else(|| {
+                let modified = fs::metadata(file_path).and_then(|m| m.modified()).ok()?;
+                Some(DateTime::<Utc>::from(modified))
+            })
+            .unwrap_or_else(Utc::now)
+    }
+
+    fn read_file_from_position(
+        &mut self,
         file_path: &PathBuf,
         start_position: u64,
     ) -> Result<(Vec<LogEvent>, u64), String> {
@@ -132,12 +349,30 @@ impl LogReader {
 
         let content = String::from_utf8_lossy(&buffer);
 
+        let mut clock = self.clock(file_path);
+        if clock.timezone.is_none() && bytes_read > 0 {
+            clock.timezone = self.detect_timezone(file_path, &content);
+        }
+
+        if start_position == 0 && bytes_read > 0 {
+            events.push(LogEvent::LogStarted {
+                timestamp: self.first_timestamp(file_path, &content, clock),
+                file_path: file_path.to_string_lossy().to_string(),
+            });
+        }
+
         for line in content.lines() {
-            if let Some(event) = self.parser.parse_line(line) {
-                events.push(event);
+            match self.parser.parse(line, &mut clock) {
+                Ok(Some(event)) => events.push(event),
+                Ok(None) => {}
+                Err(e) => {
+                    tracing::debug!(error = %e, line, "Skipping event line");
+                    self.parse_misses += 1;
+                }
             }
         }
 
+        self.clocks.insert(file_path.clone(), clock);
         let final_position = start_position + bytes_read as u64;
 
         Ok((events, final_position))
@@ -170,29 +405,164 @@ impl LogReader {
         }
     }
 
-    /// Get all log files in directory
+    /// Get all log files in the log directories, oldest first
     fn get_all_log_files(&self) -> Result<Vec<PathBuf>, String> {
-        let entries = fs::read_dir(&self.log_dir)
-            .map_err(|e| format!("Failed to read log directory: {}", e))?;
-
-        let mut log_files: Vec<PathBuf> = entries
-            .filter_map(|entry| entry.ok())
-            .map(|entry| entry.path())
-            .filter(|path| {
-                path.is_file()
-                    && path
-                        .file_name()
-                        .and_then(|n| n.to_str())
-                        .map(|n| n.starts_with("output_log") && n.ends_with(".txt"))
-                        .unwrap_or(false)
-            })
-            .collect();
+        let mut log_files = Vec::new();
+
+        for log_dir in &self.log_dirs {
+            let entries = fs::read_dir(log_dir)
+                .map_err(|e| format!("Failed to read log directory: {}", e))?;
+
+            log_files.extend(
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        path.is_file()
+                            && path
+                                .file_name()
+                                .and_then(|n| n.to_str())
+                                .map(|n| n.starts_with("output_log") && n.ends_with(".txt"))
+                                .unwrap_or(false)
+                    }),
+            );
+        }
 
         if log_files.is_empty() {
             return Err("No VRChat log files found".to_string());
         }
 
-        log_files.sort();
+        // File names carry the start time, so they order files across folders
+        log_files.sort_by(|a, b| a.file_name().cmp(&b.file_name()).then_with(|| a.cmp(b)));
         Ok(log_files)
     }
 }
+
+/// Time zone saved for a log file (None when unset or unknown)
+fn stored_timezone(conn: &Connection, file_path: &str) -> Option<Tz> {
+    let name = db::operations::get_log_file_timezone(conn, file_path).ok()??;
+    match name.parse() {
+        Ok(timezone) => Some(timezone),
+        Err(_) => {
+            tracing::warn!(path = file_path, timezone = %name, "Ignoring unknown log time zone");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{utc, SAMPLE_LOG};
+    use std::io::Write;
+    use std::time::SystemTime;
+
+    const FIRST_LOG: &str = "output_log_2025-10-13_09-53-00.txt";
+    const SECOND_LOG: &str = "output_log_2025-10-14_20-00-00.txt";
+
+    fn write_log(dir: &Path, name: &str, lines: &[&str]) -> PathBuf {
+        let path = dir.join(name);
+        let mut file = File::options()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+        path
+    }
+
+    /// Make a log look like VRChat stopped writing to it two hours ago
+    fn make_stale(path: &Path) {
+        File::options()
+            .append(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::now() - 2 * STALE_LOG_TIMEOUT)
+            .unwrap();
+    }
+
+    fn reader(dir: &Path) -> LogReader {
+        let mut reader = LogReader::new();
+        reader.configure(vec![dir.to_path_buf()], Some(Tz::UTC));
+        reader.initialize().unwrap();
+        reader
+    }
+
+    fn kinds(events: &[LogEvent]) -> Vec<&'static str> {
+        events.iter().map(LogEvent::kind).collect()
+    }
+
+    #[test]
+    fn test_new_log_file_ends_the_previous_one() {
+        let dir = tempfile::tempdir().unwrap();
+        write_log(dir.path(), FIRST_LOG, &SAMPLE_LOG[..6]);
+        let mut reader = reader(dir.path());
+        assert!(!kinds(&reader.read_new_events().unwrap()).contains(&"LogEnded"));
+
+        write_log(
+            dir.path(),
+            SECOND_LOG,
+            &["2025.10.14 20:00:00 Debug      -  Starting"],
+        );
+        let events = reader.read_new_events().unwrap();
+        assert_eq!(kinds(&events), ["LogEnded", "LogStarted"]);
+        assert!(matches!(
+            events[0],
+            LogEvent::LogEnded { timestamp } if timestamp == utc("2025-10-13T09:53:26Z")
+        ));
+
+        // An older log copied in afterwards ends nothing
+        write_log(
+            dir.path(),
+            "output_log_2025-10-01_10-00-00.txt",
+            &["2025.10.01 10:00:00 Debug      -  Starting"],
+        );
+        assert_eq!(kinds(&reader.read_new_events().unwrap()), ["LogStarted"]);
+    }
+
+    #[test]
+    fn test_stale_log_ends_once_and_resumes_when_written_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_log(dir.path(), FIRST_LOG, &SAMPLE_LOG[..6]);
+        make_stale(&path);
+        let mut reader = reader(dir.path());
+
+        let events = reader.read_backlog().unwrap();
+        assert_eq!(kinds(&events).last(), Some(&"LogEnded"));
+        assert!(reader.read_new_events().unwrap().is_empty());
+
+        // VRChat was only idle
+        write_log(dir.path(), FIRST_LOG, &SAMPLE_LOG[6..7]);
+        let events = reader.read_new_events().unwrap();
+        assert_eq!(kinds(&events), ["LogResumed", "ScreenshotTaken"]);
+        assert!(matches!(
+            &events[0],
+            LogEvent::LogResumed { ended_at, file_path }
+                if *ended_at == utc("2025-10-13T09:53:26Z") && *file_path == path.to_string_lossy()
+        ));
+
+        // Reported as ended again once it goes quiet
+        make_stale(&path);
+        assert_eq!(kinds(&reader.read_new_events().unwrap()), ["LogEnded"]);
+    }
+
+    #[test]
+    fn test_stale_log_does_not_resume_after_a_newer_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = write_log(dir.path(), FIRST_LOG, &SAMPLE_LOG[..6]);
+        make_stale(&path);
+        let mut reader = reader(dir.path());
+        reader.read_backlog().unwrap();
+
+        write_log(
+            dir.path(),
+            SECOND_LOG,
+            &["2025.10.14 20:00:00 Debug      -  Starting"],
+        );
+        write_log(dir.path(), FIRST_LOG, &SAMPLE_LOG[6..7]);
+        let events = reader.read_new_events().unwrap();
+        assert!(!kinds(&events).contains(&"LogResumed"));
+    }
+}
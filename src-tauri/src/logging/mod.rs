@@ -0,0 +1,155 @@
+use crate::settings::LoggingSettings;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{fmt, layer::SubscriberExt, reload, util::SubscriberInitExt};
+use tracing_subscriber::{EnvFilter, Registry};
+
+/// Modules whose level can be set separately
+pub const MODULES: &[&str] = &[
+    "app",
+    "commands",
+    "db",
+    "event_handler",
+    "export",
+    "import",
+    "log_monitor",
+    "log_parser",
+    "log_reader",
+    "screenshot",
+    "settings",
+];
+
+/// Log files are named `vrcjournal.<date>.log`
+const FILE_PREFIX: &str = "vrcjournal";
+const FILE_SUFFIX: &str = "log";
+
+/// Number of daily log files kept by rotation
+const KEEP_FILES: usize = 7;
+
+/// App logging to stdout and daily rotated files in the app log folder
+pub struct Logging {
+    dir: PathBuf,
+    filter: reload::Handle<EnvFilter, Registry>,
+}
+
+/// Result of exporting the app logs
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogExportSummary {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl Logging {
+    /// Install the global subscriber (call once at startup)
+    pub fn init(dir: &Path, settings: &LoggingSettings) -> Result<Self, String> {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create log folder: {}", e))?;
+        let appender = RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(FILE_PREFIX)
+            .filename_suffix(FILE_SUFFIX)
+            .max_log_files(KEEP_FILES)
+            .build(dir)
+            .map_err(|e| format!("Failed to open log file: {}", e))?;
+
+        let (filter, handle) = reload::Layer::new(build_filter(settings)?);
+        tracing_subscriber::registry()
+            .with(filter)
+            .with(fmt::layer())
+            .with(fmt::layer().with_ansi(false).with_writer(appender))
+            .try_init()
+            .map_err(|e| format!("Failed to initialize logging: {}", e))?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            filter: handle,
+        })
+    }
+
+    /// Apply changed levels without a restart
+    pub fn apply(&self, settings: &LoggingSettings) -> Result<(), String> {
+        self.filter
+            .reload(build_filter(settings)?)
+            .map_err(|e| format!("Failed to apply log levels: {}", e))
+    }
+
+    /// Folder the log files are written to
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+/// Filter with the default level and per-module overrides
+pub fn build_filter(settings: &LoggingSettings) -> Result<EnvFilter, String> {
+    let crate_name = env!("CARGO_CRATE_NAME");
+    let mut directives = vec![settings.level.clone()];
+    for (module, level) in &settings.modules {
+        directives.push(format!("{}::{}={}", crate_name, module, level));
+    }
+
+    EnvFilter::builder()
+        .parse(directives.join(","))
+        .map_err(|e| format!("Invalid log level: {}", e))
+}
+
+/// Concatenate the kept log files (oldest first) into one file for bug reports
+pub fn export_logs(dir: &Path, path: &Path) -> Result<LogExportSummary, String> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read log folder: {}", e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| is_app_log(path))
+        .collect();
+    // Dates in the file names sort chronologically
+    files.sort();
+
+    let mut output =
+        File::create(path).map_err(|e| format!("Failed to create log export: {}", e))?;
+    let mut summary = LogExportSummary::default();
+
+    for file in &files {
+        let content =
+            std::fs::read(file).map_err(|e| format!("Failed to read {:?}: {}", file, e))?;
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+
+        writeln!(output, "===== {} =====", name)
+            .and_then(|_| output.write_all(&content))
+            .map_err(|e| format!("Failed to write log export: {}", e))?;
+
+        summary.files += 1;
+        summary.bytes += content.len() as u64;
+    }
+
+    Ok(summary)
+}
+
+fn is_app_log(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| {
+            name.starts_with(FILE_PREFIX) && name.ends_with(&format!(".{}", FILE_SUFFIX))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_build_filter() {
+        let mut settings = LoggingSettings::default();
+        assert!(build_filter(&settings).is_ok());
+
+        settings.modules = BTreeMap::from([("log_parser".to_string(), "trace".to_string())]);
+        let filter = build_filter(&settings).unwrap().to_string();
+        assert!(filter.contains("::log_parser=trace"));
+    }
+
+    #[test]
+    fn test_is_app_log() {
+        assert!(is_app_log(Path::new("logs/vrcjournal.2025-10-13.log")));
+        assert!(!is_app_log(Path::new("logs/output_log_2025-10-13.txt")));
+    }
+}
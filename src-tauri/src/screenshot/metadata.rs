@@ -0,0 +1,462 @@
+use chrono::{NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::Path;
+use std::sync::LazyLock;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+/// Text chunks larger than this are skipped (metadata is a few KB at most)
+const MAX_TEXT_CHUNK_SIZE: u32 = 1024 * 1024;
+
+/// iTXt keyword used for XMP packets (VRChat 2024.x and later)
+const XMP_KEYWORD: &str = "XML:com.adobe.xmp";
+
+// VRChat_2025-10-13_10-00-00.000_1920x1080.png
+static FILE_NAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"VRChat_(\d{4}-\d{2}-\d{2}_\d{2}-\d{2}-\d{2}\.\d{3})_(\d+)x(\d+)").unwrap()
+});
+
+// VRChat_1920x1080_2021-01-01_12-00-00.000.png (before 2022)
+static LEGACY_FILE_NAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"VRChat_(\d+)x(\d+)_(\d{4}-\d{2}-\d{2}_\d{2}-\d{2}-\d{2}\.\d{3})").unwrap()
+});
+
+/// Where a metadata field came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataSource {
+    FileName, // Timestamp and resolution encoded in the file name
+    Png,      // IHDR chunk
+    Xmp,      // XMP packet written by VRChat
+    Vrcx,     // JSON description written by VRCX
+}
+
+/// Player listed in the screenshot metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotPlayer {
+    pub user_id: String, // usr_xxx
+    pub display_name: String,
+}
+
+/// Metadata read from a screenshot file
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScreenshotMetadata {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub taken_at: Option<i64>, // Unix timestamp in milliseconds (from file name)
+    pub world_id: Option<String>, // wrld_xxx
+    pub world_name: Option<String>,
+    pub instance_id: Option<String>, // Without the `wrld_xxx:` prefix
+    pub author_id: Option<String>,   // usr_xxx of the photographer
+    pub author_name: Option<String>,
+    pub players: Vec<ScreenshotPlayer>,
+    pub sources: Vec<MetadataSource>,
+}
+
+impl ScreenshotMetadata {
+    /// Drop the given users (usr_xxx) from the player list and author fields.
+    /// Returns whether anything was removed.
+    pub fn remove_users(&mut self, user_ids: &HashSet<String>) -> bool {
+        let before = self.players.len();
+        self.players.retain(|p| !user_ids.contains(&p.user_id));
+        let mut removed = self.players.len() != before;
+
+        if self
+            .author_id
+            .as_ref()
+            .is_some_and(|id| user_ids.contains(id))
+        {
+            self.author_id = None;
+            self.author_name = None;
+            removed = true;
+        }
+        removed
+    }
+}
+
+/// Description JSON written by VRCX
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VrcxDescription {
+    application: String,
+    author: Option<VrcxUser>,
+    world: Option<VrcxWorld>,
+    #[serde(default)]
+    players: Vec<VrcxUser>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VrcxUser {
+    id: String,
+    display_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VrcxWorld {
+    id: Option<String>,
+    name: Option<String>,
+    instance_id: Option<String>, // wrld_xxx:instance
+}
+
+/// Read metadata from the file name and, if the file is a readable PNG, its chunks
+pub fn read_metadata(path: &Path, timezone: Tz) -> Result<ScreenshotMetadata, String> {
+    let mut metadata = parse_file_name(path, timezone);
+
+    let file = File::open(path).map_err(|e| format!("Failed to open {:?}: {}", path, e))?;
+    read_png_chunks(&mut BufReader::new(file), &mut metadata)
+        .map_err(|e| format!("Failed to read PNG {:?}: {}", path, e))?;
+
+    Ok(metadata)
+}
+
+/// Metadata that can be derived from the file name alone.
+/// VRChat names files by the local time of the PC, read here in `timezone`.
+pub fn parse_file_name(path: &Path, timezone: Tz) -> ScreenshotMetadata {
+    let mut metadata = ScreenshotMetadata::default();
+    let Some(file_name) = path.file_name().and_then(|n| n.to_str()) else {
+        return metadata;
+    };
+
+    let (timestamp, width, height) = if let Some(caps) = FILE_NAME_REGEX.captures(file_name) {
+        (
+            caps[1].to_string(),
+            caps[2].parse().ok(),
+            caps[3].parse().ok(),
+        )
+    } else if let Some(caps) = LEGACY_FILE_NAME_REGEX.captures(file_name) {
+        (
+            caps[3].to_string(),
+            caps[1].parse().ok(),
+            caps[2].parse().ok(),
+        )
+    } else {
+        return metadata;
+    };
+
+    metadata.width = width;
+    metadata.height = height;
+    metadata.taken_at = NaiveDateTime::parse_from_str(&timestamp, "%Y-%m-%d_%H-%M-%S%.3f")
+        .ok()
+        .and_then(|naive| timezone.from_local_datetime(&naive).earliest())
+        .map(|local| local.timestamp_millis());
+    metadata.sources.push(MetadataSource::FileName);
+
+    metadata
+}
+
+fn read_png_chunks(
+    reader: &mut (impl Read + Seek),
+    metadata: &mut ScreenshotMetadata,
+) -> std::io::Result<()> {
+    let mut signature = [0u8; 8];
+    reader.read_exact(&mut signature)?;
+    if signature != PNG_SIGNATURE {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "not a PNG file",
+        ));
+    }
+
+    loop {
+        let mut header = [0u8; 8];
+        if reader.read_exact(&mut header).is_err() {
+            break; // Truncated file: keep what was read so far
+        }
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]);
+        let chunk_type = &header[4..8];
+
+        match chunk_type {
+            b"IEND" => break,
+            b"IHDR" | b"tEXt" | b"iTXt" if length <= MAX_TEXT_CHUNK_SIZE => {
+                let mut data = vec![0u8; length as usize];
+                reader.read_exact(&mut data)?;
+                reader.seek_relative(4)?; // CRC
+
+                match chunk_type {
+                    b"IHDR" if data.len() >= 8 => {
+                        metadata.width =
+                            Some(u32::from_be_bytes([data[0], data[1], data[2], data[3]]));
+                        metadata.height =
+                            Some(u32::from_be_bytes([data[4], data[5], data[6], data[7]]));
+                        metadata.sources.push(MetadataSource::Png);
+                    }
+                    b"tEXt" => {
+                        if let Some((keyword, text)) = parse_text_chunk(&data) {
+                            apply_text(metadata, &keyword, &text);
+                        }
+                    }
+                    b"iTXt" => {
+                        if let Some((keyword, text)) = parse_itxt_chunk(&data) {
+                            apply_text(metadata, &keyword, &text);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => reader.seek_relative(i64::from(length) + 4)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// tEXt: keyword, NUL, Latin-1 text
+fn parse_text_chunk(data: &[u8]) -> Option<(String, String)> {
+    let separator = data.iter().position(|&b| b == 0)?;
+    let keyword = latin1_to_string(&data[..separator]);
+    let text = latin1_to_string(&data[separator + 1..]);
+    Some((keyword, text))
+}
+
+/// iTXt: keyword, NUL, compression flag, method, language, NUL, translated keyword, NUL, UTF-8 text.
+/// Compressed text is not used by VRChat or VRCX and is skipped.
+fn parse_itxt_chunk(data: &[u8]) -> Option<(String, String)> {
+    let keyword_end = data.iter().position(|&b| b == 0)?;
+    let keyword = latin1_to_string(&data[..keyword_end]);
+
+    let rest = data.get(keyword_end + 1..)?;
+    let (&compressed, rest) = rest.split_first()?;
+    if compressed != 0 {
+        return None;
+    }
+    let rest = rest.get(1..)?; // Compression method
+
+    let language_end = rest.iter().position(|&b| b == 0)?;
+    let rest = &rest[language_end + 1..];
+    let translated_end = rest.iter().position(|&b| b == 0)?;
+    let text = String::from_utf8_lossy(&rest[translated_end + 1..]).to_string();
+
+    Some((keyword, text))
+}
+
+fn latin1_to_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+fn apply_text(metadata: &mut ScreenshotMetadata, keyword: &str, text: &str) {
+    if keyword == XMP_KEYWORD {
+        apply_xmp(metadata, text);
+    } else if text.trim_start().starts_with('{') {
+        apply_vrcx(metadata, text);
+    }
+}
+
+/// Fill fields from VRChat's XMP packet
+fn apply_xmp(metadata: &mut ScreenshotMetadata, xmp: &str) {
+    let world_id = xmp_value(xmp, "vrc:WorldID");
+    if world_id.is_none() {
+        return;
+    }
+
+    fill(&mut metadata.world_id, world_id);
+    fill(
+        &mut metadata.world_name,
+        xmp_value(xmp, "vrc:WorldDisplayName"),
+    );
+    fill(&mut metadata.author_id, xmp_value(xmp, "vrc:AuthorID"));
+    fill(&mut metadata.author_name, xmp_value(xmp, "xmp:Author"));
+    metadata.sources.push(MetadataSource::Xmp);
+}
+
+/// Fill fields from a VRCX description (also carries the instance and player list)
+fn apply_vrcx(metadata: &mut ScreenshotMetadata, json: &str) {
+    let Ok(description) = serde_json::from_str::<VrcxDescription>(json) else {
+        return;
+    };
+    if description.application != "VRCX" {
+        return;
+    }
+
+    if let Some(world) = description.world {
+        let instance_id = world.instance_id.map(|full| match full.split_once(':') {
+            Some((_, instance)) => instance.to_string(),
+            None => full,
+        });
+        fill(&mut metadata.world_id, world.id);
+        fill(&mut metadata.world_name, world.name);
+        fill(&mut metadata.instance_id, instance_id);
+    }
+    if let Some(author) = description.author {
+        fill(&mut metadata.author_id, Some(author.id));
+        fill(&mut metadata.author_name, Some(author.display_name));
+    }
+    if metadata.players.is_empty() {
+        metadata.players = description
+            .players
+            .into_iter()
+            .map(|p| ScreenshotPlayer {
+                user_id: p.id,
+                display_name: p.display_name,
+            })
+            .collect();
+    }
+    metadata.sources.push(MetadataSource::Vrcx);
+}
+
+/// Value of an XMP property written either as an element or as an attribute
+fn xmp_value(xmp: &str, name: &str) -> Option<String> {
+    let element = format!("<{}>", name);
+    let attribute = format!("{}=\"", name);
+
+    let value = if let Some(start) = xmp.find(&element) {
+        let rest = &xmp[start + element.len()..];
+        &rest[..rest.find('<')?]
+    } else if let Some(start) = xmp.find(&attribute) {
+        let rest = &xmp[start + attribute.len()..];
+        &rest[..rest.find('"')?]
+    } else {
+        return None;
+    };
+
+    let value = value
+        .trim()
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&");
+
+    (!value.is_empty()).then_some(value)
+}
+
+/// Set `field` only if it has no value yet
+fn fill(field: &mut Option<String>, value: Option<String>) {
+    if field.is_none() {
+        *field = value.filter(|v| !v.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::utc;
+    use chrono_tz::Asia::Tokyo;
+    use std::io::Cursor;
+
+    fn chunk(chunk_type: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut bytes = (data.len() as u32).to_be_bytes().to_vec();
+        bytes.extend_from_slice(chunk_type);
+        bytes.extend_from_slice(data);
+        bytes.extend_from_slice(&[0, 0, 0, 0]); // CRC is not checked
+        bytes
+    }
+
+    fn itxt(keyword: &str, text: &str) -> Vec<u8> {
+        let mut data = keyword.as_bytes().to_vec();
+        data.extend_from_slice(&[0, 0, 0, 0, 0]);
+        data.extend_from_slice(text.as_bytes());
+        chunk(b"iTXt", &data)
+    }
+
+    #[test]
+    fn test_parse_file_name() {
+        let metadata = parse_file_name(
+            Path::new("VRChat_2025-10-13_10-00-00.000_1920x1080.png"),
+            Tokyo,
+        );
+        assert_eq!(metadata.width, Some(1920));
+        assert_eq!(metadata.height, Some(1080));
+        assert_eq!(
+            metadata.taken_at,
+            Some(utc("2025-10-13T01:00:00Z").timestamp_millis())
+        );
+
+        let legacy = parse_file_name(
+            Path::new("VRChat_3840x2160_2021-05-01_21-30-15.123.png"),
+            Tz::UTC,
+        );
+        assert_eq!(legacy.width, Some(3840));
+        assert_eq!(legacy.height, Some(2160));
+        assert_eq!(
+            legacy.taken_at,
+            Some(utc("2021-05-01T21:30:15.123Z").timestamp_millis())
+        );
+
+        assert_eq!(
+            parse_file_name(Path::new("photo.png"), Tz::UTC),
+            ScreenshotMetadata::default()
+        );
+    }
+
+    #[test]
+    fn test_read_png_chunks() {
+        let mut ihdr = Vec::new();
+        ihdr.extend_from_slice(&1280u32.to_be_bytes());
+        ihdr.extend_from_slice(&720u32.to_be_bytes());
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+        let xmp = r#"<x:xmpmeta><rdf:Description xmlns:vrc="http://ns.vrchat.com/vrc/1.0/">
+            <xmp:Author>Me</xmp:Author>
+            <vrc:WorldID>wrld_1</vrc:WorldID>
+            <vrc:WorldDisplayName>Tea &amp; Cake</vrc:WorldDisplayName>
+            <vrc:AuthorID>usr_me</vrc:AuthorID>
+            </rdf:Description></x:xmpmeta>"#;
+        let vrcx = r#"{"application":"VRCX","version":1,
+            "author":{"id":"usr_me","displayName":"Me"},
+            "world":{"name":"Tea & Cake","id":"wrld_1","instanceId":"wrld_1:12345~region(jp)"},
+            "players":[{"id":"usr_friend","displayName":"Friend"}]}"#;
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(chunk(b"IHDR", &ihdr));
+        png.extend(itxt(XMP_KEYWORD, xmp));
+        png.extend(chunk(b"IDAT", &[0; 16]));
+        png.extend(itxt("Description", vrcx));
+        png.extend(chunk(b"IEND", &[]));
+
+        let mut metadata = ScreenshotMetadata::default();
+        read_png_chunks(&mut Cursor::new(png), &mut metadata).unwrap();
+
+        assert_eq!((metadata.width, metadata.height), (Some(1280), Some(720)));
+        assert_eq!(metadata.world_id.as_deref(), Some("wrld_1"));
+        assert_eq!(metadata.world_name.as_deref(), Some("Tea & Cake"));
+        assert_eq!(metadata.instance_id.as_deref(), Some("12345~region(jp)"));
+        assert_eq!(metadata.author_id.as_deref(), Some("usr_me"));
+        assert_eq!(metadata.players.len(), 1);
+        assert_eq!(
+            metadata.sources,
+            vec![
+                MetadataSource::Png,
+                MetadataSource::Xmp,
+                MetadataSource::Vrcx
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remove_users() {
+        let player = |id: &str| ScreenshotPlayer {
+            user_id: id.to_string(),
+            display_name: id.to_string(),
+        };
+        let mut metadata = ScreenshotMetadata {
+            author_id: Some("usr_a".to_string()),
+            author_name: Some("A".to_string()),
+            players: vec![player("usr_a"), player("usr_b")],
+            ..Default::default()
+        };
+
+        let blocked = HashSet::from(["usr_b".to_string()]);
+        assert!(metadata.remove_users(&blocked));
+        assert_eq!(metadata.players, vec![player("usr_a")]);
+        assert!(!metadata.remove_users(&blocked));
+
+        assert!(metadata.remove_users(&HashSet::from(["usr_a".to_string()])));
+        assert!(metadata.players.is_empty());
+        assert_eq!((metadata.author_id, metadata.author_name), (None, None));
+    }
+
+    #[test]
+    fn test_rejects_non_png() {
+        let mut metadata = ScreenshotMetadata::default();
+        assert!(read_png_chunks(&mut Cursor::new(b"GIF89a..".to_vec()), &mut metadata).is_err());
+    }
+}
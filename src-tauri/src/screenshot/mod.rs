@@ -0,0 +1,21 @@
+mod metadata;
+mod refresh;
+mod relocate;
+mod scanner;
+mod similarity;
+mod thumbnail;
+mod worker;
+
+pub use metadata::{read_metadata, MetadataSource, ScreenshotMetadata, ScreenshotPlayer};
+pub use refresh::{
+    apply_metadata, read_file_metadata, refresh_metadata, remove_user_from_metadata, LinkCheck,
+};
+pub use relocate::{
+    apply_file_checks, apply_relink, check_files, plan_relink, FileCheckSummary, RelinkSummary,
+};
+pub use scanner::{
+    attach_screenshot, read_scanned_files, AttachedScreenshot, FolderScanner, ScannedFile,
+};
+pub use similarity::{find_duplicate_groups, DuplicateGroup, DEFAULT_MAX_DISTANCE};
+pub use thumbnail::{cached_thumbnail, ThumbnailConfig, ThumbnailFormat};
+pub use worker::ThumbnailWorker;
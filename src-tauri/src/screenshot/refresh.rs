@@ -0,0 +1,166 @@
+use super::metadata::{self, ScreenshotMetadata};
+use crate::db::operations;
+use crate::types::ScreenshotSource;
+use chrono::Utc;
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Result of checking a screenshot's instance against its embedded world/instance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(tag = "result", rename_all = "snake_case")]
+pub enum LinkCheck {
+    Unverified, // No embedded world information
+    Consistent,
+    Relinked { from: i64, to: i64 },
+    Mismatch, // Embedded world differs but no matching instance was found
+}
+
+/// Read metadata from the screenshot file, store it and repair the instance link if needed.
+pub fn refresh_metadata(
+    conn: &Connection,
+    screenshot_id: i64,
+    timezone: Tz,
+) -> Result<Option<LinkCheck>, rusqlite::Error> {
+    let Some((_, file_path, _)) = operations::get_screenshot(conn, screenshot_id)? else {
+        return Ok(None);
+    };
+
+    apply_metadata(
+        conn,
+        screenshot_id,
+        read_file_metadata(Path::new(&file_path), timezone),
+    )
+}
+
+/// Read the metadata of a screenshot file without touching the database.
+/// Files that cannot be read fall back to what the file name encodes.
+/// Times in file names are read in `timezone`.
+pub fn read_file_metadata(path: &Path, timezone: Tz) -> ScreenshotMetadata {
+    metadata::read_metadata(path, timezone).unwrap_or_else(|e| {
+        tracing::warn!("Screenshot metadata unavailable: {}", e);
+        metadata::parse_file_name(path, timezone)
+    })
+}
+
+/// Store metadata read by `read_file_metadata` and repair the instance link if needed
+pub fn apply_metadata(
+    conn: &Connection,
+    screenshot_id: i64,
+    mut metadata: ScreenshotMetadata,
+) -> Result<Option<LinkCheck>, rusqlite::Error> {
+    let Some((instance_id, _, taken_at)) = operations::get_screenshot(conn, screenshot_id)? else {
+        return Ok(None);
+    };
+
+    metadata.remove_users(&blocked_user_ids(conn)?);
+
+    let json = serde_json::to_string(&metadata).unwrap_or_default();
+    operations::update_screenshot_metadata(
+        conn,
+        screenshot_id,
+        metadata.width,
+        metadata.height,
+        &json,
+        Utc::now().timestamp_millis(),
+    )?;
+
+    // Manually attached screenshots keep the instance chosen by the user
+    if operations::get_screenshot_source(conn, screenshot_id)? == ScreenshotSource::Manual {
+        return Ok(Some(LinkCheck::Unverified));
+    }
+
+    let check = check_instance_link(conn, screenshot_id, instance_id, taken_at, &metadata)?;
+    match check {
+        LinkCheck::Relinked { from, to } => tracing::info!(
+            "Screenshot {} moved from instance {} to {} based on embedded metadata",
+            screenshot_id,
+            from,
+            to
+        ),
+        LinkCheck::Mismatch => tracing::warn!(
+            "Screenshot {} was taken in {:?} but no matching instance was found",
+            screenshot_id,
+            metadata.world_id
+        ),
+        LinkCheck::Unverified | LinkCheck::Consistent => {}
+    }
+
+    Ok(Some(check))
+}
+
+/// Remove a user (usr_xxx) from the stored metadata of all screenshots.
+/// Returns the number of screenshots changed.
+pub fn remove_user_from_metadata(
+    conn: &Connection,
+    vrchat_user_id: &str,
+) -> Result<usize, rusqlite::Error> {
+    let user_ids = HashSet::from([vrchat_user_id.to_string()]);
+    let mut changed = 0;
+
+    for (screenshot_id, json) in
+        operations::find_screenshot_metadata_containing(conn, vrchat_user_id)?
+    {
+        let Ok(mut metadata) = serde_json::from_str::<ScreenshotMetadata>(&json) else {
+            continue;
+        };
+        if metadata.remove_users(&user_ids) {
+            let json = serde_json::to_string(&metadata).unwrap_or_default();
+            operations::set_screenshot_metadata(conn, screenshot_id, &json)?;
+            changed += 1;
+        }
+    }
+
+    Ok(changed)
+}
+
+fn blocked_user_ids(conn: &Connection) -> Result<HashSet<String>, rusqlite::Error> {
+    Ok(operations::get_blocked_users(conn)?
+        .into_iter()
+        .map(|blocked| blocked.user_id)
+        .collect())
+}
+
+fn check_instance_link(
+    conn: &Connection,
+    screenshot_id: i64,
+    instance_id: i64,
+    taken_at: i64,
+    metadata: &ScreenshotMetadata,
+) -> Result<LinkCheck, rusqlite::Error> {
+    let Some(embedded_world_id) = &metadata.world_id else {
+        return Ok(LinkCheck::Unverified);
+    };
+
+    let (my_account_id, world_id, vrchat_instance_id) =
+        operations::get_instance_location(conn, instance_id)?;
+
+    let same_instance = metadata
+        .instance_id
+        .as_ref()
+        .is_none_or(|id| *id == vrchat_instance_id);
+    if *embedded_world_id == world_id && same_instance {
+        return Ok(LinkCheck::Consistent);
+    }
+
+    let found = operations::find_instance_at(
+        conn,
+        my_account_id,
+        embedded_world_id,
+        metadata.instance_id.as_deref(),
+        taken_at,
+    )?;
+
+    match found {
+        Some(to) if to != instance_id => {
+            operations::set_screenshot_instance(conn, screenshot_id, to)?;
+            Ok(LinkCheck::Relinked {
+                from: instance_id,
+                to,
+            })
+        }
+        Some(_) => Ok(LinkCheck::Consistent),
+        None => Ok(LinkCheck::Mismatch),
+    }
+}
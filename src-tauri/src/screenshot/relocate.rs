@@ -0,0 +1,358 @@
+use super::{scanner, similarity};
+use crate::db::operations::{self, ScreenshotFile};
+use chrono::Utc;
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Subfolder levels searched below the new root when relinking
+const RELINK_MAX_DEPTH: usize = 4;
+
+/// Result of checking stored screenshot paths
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileCheckSummary {
+    pub checked: usize,
+    pub hashed: usize,
+    pub missing: usize,     // Newly flagged as missing
+    pub found_again: usize, // Flagged as missing earlier but back in place
+}
+
+/// State of one screenshot file on disk
+#[derive(Debug)]
+pub struct FileCheck {
+    screenshot_id: i64,
+    was_missing: bool,
+    exists: bool,
+    hashed: Option<(i64, String)>, // Size and hash of files that had none stored
+    perceptual_hash: Option<u64>,  // dHash of images that had none stored
+}
+
+/// Result of relinking screenshots under a new root
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelinkSummary {
+    pub relinked: usize,
+    pub ambiguous: usize, // Several files with the same name and no stored hash to tell them apart
+    pub not_found: usize,
+}
+
+/// New locations found for screenshots whose file no longer exists
+#[derive(Debug, Default)]
+pub struct RelinkPlan {
+    moves: Vec<(i64, PathBuf)>,
+    ambiguous: usize,
+    not_found: usize,
+}
+
+/// SHA-256 of the file contents as lowercase hex
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    io::copy(&mut reader, &mut hasher)?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Look up each stored file on disk, computing content and perceptual hashes not stored yet.
+/// Only touches the file system so it can run without holding the database connection.
+pub fn check_files(files: &[ScreenshotFile]) -> Vec<FileCheck> {
+    files
+        .iter()
+        .map(|file| {
+            let path = Path::new(&file.file_path);
+            let size = fs::metadata(path)
+                .ok()
+                .filter(|m| m.is_file())
+                .map(|m| m.len() as i64);
+
+            let hashed = match (size, &file.content_hash) {
+                (Some(size), None) => hash_file(path).ok().map(|hash| (size, hash)),
+                _ => None,
+            };
+
+            let perceptual_hash = match (size, file.perceptual_hash) {
+                (Some(_), None) => similarity::dhash_file(path)
+                    .map_err(|e| tracing::error!("Failed to compute perceptual hash: {}", e))
+                    .ok(),
+                _ => None,
+            };
+
+            FileCheck {
+                screenshot_id: file.screenshot_id,
+                was_missing: file.missing_since.is_some(),
+                exists: size.is_some(),
+                hashed,
+                perceptual_hash,
+            }
+        })
+        .collect()
+}
+
+/// Store the results of `check_files`
+pub fn apply_file_checks(
+    conn: &Connection,
+    checks: &[FileCheck],
+) -> Result<FileCheckSummary, rusqlite::Error> {
+    let now = Utc::now().timestamp_millis();
+    let mut summary = FileCheckSummary {
+        checked: checks.len(),
+        ..Default::default()
+    };
+
+    for check in checks {
+        if let Some((size, hash)) = &check.hashed {
+            operations::update_screenshot_file(conn, check.screenshot_id, *size, hash)?;
+            summary.hashed += 1;
+        }
+        if let Some(perceptual_hash) = check.perceptual_hash {
+            operations::update_screenshot_perceptual_hash(
+                conn,
+                check.screenshot_id,
+                perceptual_hash as i64,
+            )?;
+        }
+
+        match (check.exists, check.was_missing) {
+            (false, false) => {
+                operations::set_screenshot_missing(conn, check.screenshot_id, Some(now))?;
+                summary.missing += 1;
+            }
+            (true, true) => {
+                operations::set_screenshot_missing(conn, check.screenshot_id, None)?;
+                summary.found_again += 1;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Find new locations under `new_root` for screenshots whose file no longer exists.
+/// Files are matched by content hash where one is stored, otherwise by a unique file name.
+/// Only touches the file system so it can run without holding the database connection.
+pub fn plan_relink(files: &[ScreenshotFile], new_root: &Path) -> RelinkPlan {
+    let mut images = Vec::new();
+    scanner::collect_images(new_root, RELINK_MAX_DEPTH, &mut images);
+
+    let mut by_name: HashMap<String, Vec<PathBuf>> = HashMap::new();
+    for path in &images {
+        if let Some(name) = path.to_str().map(file_name_key) {
+            by_name.entry(name).or_default().push(path.clone());
+        }
+    }
+
+    // Hashes are computed on demand and only once per file
+    let mut hashes: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut hash_of = |path: &PathBuf| {
+        hashes
+            .entry(path.clone())
+            .or_insert_with(|| hash_file(path).ok())
+            .clone()
+    };
+
+    let mut plan = RelinkPlan::default();
+    for file in files {
+        if Path::new(&file.file_path).is_file() {
+            continue;
+        }
+
+        let same_name = by_name
+            .get(&file_name_key(&file.file_path))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let found = match &file.content_hash {
+            // Same name first, then any file with the same contents (renamed files)
+            Some(hash) => same_name
+                .iter()
+                .chain(&images)
+                .find(|path| hash_of(path).as_ref() == Some(hash)),
+            None if same_name.len() > 1 => {
+                plan.ambiguous += 1;
+                continue;
+            }
+            None => same_name.first(),
+        };
+
+        match found {
+            Some(path) => plan.moves.push((file.screenshot_id, path.clone())),
+            None => plan.not_found += 1,
+        }
+    }
+
+    plan
+}
+
+/// Store the new locations found by `plan_relink`
+pub fn apply_relink(
+    conn: &Connection,
+    plan: &RelinkPlan,
+) -> Result<RelinkSummary, rusqlite::Error> {
+    for (screenshot_id, path) in &plan.moves {
+        operations::set_screenshot_path(conn, *screenshot_id, &path.to_string_lossy())?;
+    }
+
+    Ok(RelinkSummary {
+        relinked: plan.moves.len(),
+        ambiguous: plan.ambiguous,
+        not_found: plan.not_found,
+    })
+}
+
+/// Case-insensitive file name of a stored path, which may use either separator
+fn file_name_key(file_path: &str) -> String {
+    file_path
+        .rsplit(['\\', '/'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::screenshot::{read_scanned_files, FolderScanner};
+    use crate::test_support::sample_database;
+    use chrono_tz::Tz;
+    use std::time::{Duration, SystemTime};
+
+    const FILE_NAME: &str = "VRChat_2025-10-13_10-00-00.000_1920x1080.png";
+
+    fn screenshot_file(conn: &Connection) -> ScreenshotFile {
+        operations::get_screenshot_files(conn, false)
+            .unwrap()
+            .remove(0)
+    }
+
+    fn write_file(path: &Path, contents: &[u8]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    /// Store the hash of `contents` for the sample screenshot, whose file is not on disk
+    fn store_hash(conn: &Connection, contents: &[u8]) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(FILE_NAME);
+        write_file(&path, contents);
+        let file = screenshot_file(conn);
+        let hash = hash_file(&path).unwrap();
+        operations::update_screenshot_file(conn, file.screenshot_id, contents.len() as i64, &hash)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_file_checks_flag_missing_and_returned_files() {
+        let database = sample_database();
+        let conn = database.connection();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(FILE_NAME);
+        let file = screenshot_file(conn);
+        operations::set_screenshot_path(conn, file.screenshot_id, &path.to_string_lossy()).unwrap();
+
+        let checks = check_files(&[screenshot_file(conn)]);
+        let summary = apply_file_checks(conn, &checks).unwrap();
+        assert_eq!((summary.missing, summary.hashed), (1, 0));
+        assert!(screenshot_file(conn).missing_since.is_some());
+
+        // Flagged only once
+        let checks = check_files(&[screenshot_file(conn)]);
+        assert_eq!(apply_file_checks(conn, &checks).unwrap().missing, 0);
+
+        // e.g. an external drive was plugged in again
+        write_file(&path, b"contents");
+        let checks = check_files(&[screenshot_file(conn)]);
+        let summary = apply_file_checks(conn, &checks).unwrap();
+        assert_eq!((summary.found_again, summary.hashed), (1, 1));
+
+        let file = screenshot_file(conn);
+        assert_eq!(file.missing_since, None);
+        assert_eq!(file.file_size, Some(8));
+        assert_eq!(file.content_hash, Some(hash_file(&path).unwrap()));
+    }
+
+    #[test]
+    fn test_relink_prefers_matching_contents_over_name() {
+        let database = sample_database();
+        let conn = database.connection();
+        store_hash(conn, b"original");
+
+        let root = tempfile::tempdir().unwrap();
+        write_file(&root.path().join("2025-10").join(FILE_NAME), b"other photo");
+        let renamed = root.path().join("2025-10").join("renamed.png");
+        write_file(&renamed, b"original");
+
+        let plan = plan_relink(&[screenshot_file(conn)], root.path());
+        let summary = apply_relink(conn, &plan).unwrap();
+        assert_eq!((summary.relinked, summary.not_found), (1, 0));
+        assert_eq!(screenshot_file(conn).file_path, renamed.to_string_lossy());
+    }
+
+    #[test]
+    fn test_relink_by_name_skips_ambiguous_files() {
+        let database = sample_database();
+        let conn = database.connection();
+        let original_path = screenshot_file(conn).file_path;
+
+        let root = tempfile::tempdir().unwrap();
+        write_file(&root.path().join("a").join(FILE_NAME), b"one");
+        write_file(&root.path().join("b").join(FILE_NAME), b"two");
+
+        let plan = plan_relink(&[screenshot_file(conn)], root.path());
+        let summary = apply_relink(conn, &plan).unwrap();
+        assert_eq!((summary.relinked, summary.ambiguous), (0, 1));
+        assert_eq!(screenshot_file(conn).file_path, original_path);
+
+        // A single file with the same name is taken without a stored hash
+        fs::remove_dir_all(root.path().join("b")).unwrap();
+        let plan = plan_relink(&[screenshot_file(conn)], root.path());
+        assert_eq!(apply_relink(conn, &plan).unwrap().relinked, 1);
+        assert_eq!(
+            screenshot_file(conn).file_path,
+            root.path().join("a").join(FILE_NAME).to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_folder_scanner_moves_missing_screenshot() {
+        let database = sample_database();
+        let conn = database.connection();
+        store_hash(conn, b"original");
+        let checks = check_files(&[screenshot_file(conn)]);
+        apply_file_checks(conn, &checks).unwrap();
+
+        let folder = tempfile::tempdir().unwrap();
+        let moved = folder.path().join("moved.png");
+        write_file(&moved, b"original");
+        File::options()
+            .append(true)
+            .open(&moved)
+            .unwrap()
+            .set_modified(SystemTime::now() - Duration::from_secs(120))
+            .unwrap();
+
+        let mut scanner = FolderScanner::new();
+        let files = scanner.collect_new_files(&[folder.path().to_path_buf()]);
+        let files = scanner.skip_recorded(conn, files).unwrap();
+        let attached = scanner
+            .link(conn, read_scanned_files(files, Tz::UTC))
+            .unwrap();
+
+        // Moved, not recorded as a new screenshot
+        assert!(attached.is_empty());
+        let file = screenshot_file(conn);
+        assert_eq!(file.file_path, moved.to_string_lossy());
+        assert_eq!(
+            operations::get_screenshot_files(conn, false).unwrap().len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use super::{metadata, refresh, relocate, ScreenshotMetadata};
+use crate::db::operations;
+use crate::types::ScreenshotSource;
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use regex::Regex;
+use rusqlite::Connection;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp"];
+
+/// Subfolder levels searched below each folder (VRChat stores photos in `YYYY-MM/`)
+const MAX_DEPTH: usize = 2;
+
+/// Files modified more recently than this may still be written, or about to be logged by VRChat
+const SETTLE_TIME: Duration = Duration::from_secs(30);
+
+/// Local date and time in file names of other capture tools:
+/// `20251013100000_1.png` (Steam), `2025-10-13 10-00-00.png` (OBS),
+/// `Screenshot 2025-10-13 100000.png` (Windows)
+static TIMESTAMP_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|\D)(\d{4})[-_.]?(\d{2})[-_.]?(\d{2})[ _T-]?(\d{2})[-_.:]?(\d{2})[-_.:]?(\d{2})(?:\D|$)")
+        .unwrap()
+});
+
+/// A screenshot linked by the folder scanner or attached by the user
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachedScreenshot {
+    pub screenshot_id: i64,
+    pub instance_id: i64,
+    pub file_path: String,
+    pub taken_at: i64,
+    pub source: ScreenshotSource,
+}
+
+/// Finds images in picture folders that never appeared in the log
+/// and links them to the instance open at their capture time
+#[derive(Debug, Default)]
+pub struct FolderScanner {
+    seen: HashSet<PathBuf>, // Files on disk already recorded or without a matching instance
+}
+
+impl FolderScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Image files in `folders` not handled by an earlier scan.
+    /// Does not touch the database so it can run without holding the connection.
+    pub fn collect_new_files(&mut self, folders: &[PathBuf]) -> Vec<PathBuf> {
+        let settled_before = SystemTime::now() - SETTLE_TIME;
+        let mut files = Vec::new();
+        for folder in folders {
+            collect_images(folder, MAX_DEPTH, &mut files);
+        }
+
+        // Forget files that were deleted or whose folder is no longer watched
+        let present: HashSet<&PathBuf> = files.iter().collect();
+        self.seen.retain(|path| present.contains(path));
+
+        files.retain(|path| {
+            !self.seen.contains(path)
+                && fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|modified| modified < settled_before)
+        });
+        files
+    }
+
+    /// Drop files that are already recorded, remembering them for later scans
+    pub fn skip_recorded(
+        &mut self,
+        conn: &Connection,
+        files: Vec<PathBuf>,
+    ) -> Result<Vec<PathBuf>, rusqlite::Error> {
+        let mut unrecorded = Vec::new();
+        for path in files {
+            if operations::find_screenshot_by_path(conn, &path.to_string_lossy())?.is_some() {
+                self.seen.insert(path);
+            } else {
+                unrecorded.push(path);
+            }
+        }
+        Ok(unrecorded)
+    }
+
+    /// Record files as auto-matched screenshots of the instances (of any account)
+    /// open at their capture time. Files without one are tried again by later scans,
+    /// since the backlog or an import may still record their instance.
+    pub fn link(
+        &mut self,
+        conn: &Connection,
+        files: Vec<ScannedFile>,
+    ) -> Result<Vec<AttachedScreenshot>, rusqlite::Error> {
+        let mut attached = Vec::new();
+
+        for file in files {
+            let file_path = file.path.to_string_lossy().to_string();
+            // The log may have recorded the file since it was read
+            if operations::find_screenshot_by_path(conn, &file_path)?.is_some() {
+                self.seen.insert(file.path);
+                continue;
+            }
+
+            // A recorded screenshot that was moved here keeps its instance
+            if let Some((_, hash)) = &file.content {
+                if let Some(moved_id) = operations::find_missing_screenshot_by_hash(conn, hash)? {
+                    operations::set_screenshot_path(conn, moved_id, &file_path)?;
+                    tracing::info!("Screenshot {} found at {}", moved_id, file_path);
+                    self.seen.insert(file.path);
+                    continue;
+                }
+            }
+
+            let Some(taken_at) = file.taken_at else {
+                continue;
+            };
+            let Some(instance_id) = operations::find_instance_at_time(conn, None, taken_at)? else {
+                continue;
+            };
+
+            let screenshot_id = operations::record_screenshot(
+                conn,
+                instance_id,
+                &file_path,
+                taken_at,
+                ScreenshotSource::AutoMatched,
+            )?;
+            if let Some((size, hash)) = &file.content {
+                operations::update_screenshot_file(conn, screenshot_id, *size as i64, hash)?;
+            }
+            let instance_id = match refresh::apply_metadata(conn, screenshot_id, file.metadata)? {
+                Some(refresh::LinkCheck::Relinked { to, .. }) => to,
+                _ => instance_id,
+            };
+
+            tracing::info!("Screenshot matched by capture time: {}", file_path);
+            self.seen.insert(file.path);
+            attached.push(AttachedScreenshot {
+                screenshot_id,
+                instance_id,
+                file_path,
+                taken_at,
+                source: ScreenshotSource::AutoMatched,
+            });
+        }
+
+        Ok(attached)
+    }
+}
+
+/// An unrecorded image with what the scanner needs from the file
+#[derive(Debug)]
+pub struct ScannedFile {
+    path: PathBuf,
+    taken_at: Option<i64>,
+    content: Option<(u64, String)>, // Size and SHA-256 (hex)
+    metadata: ScreenshotMetadata,
+}
+
+/// Hash and read the files left by `FolderScanner::skip_recorded`, reading times in file
+/// names in `timezone`. Does not touch the database so it can run without holding the connection.
+pub fn read_scanned_files(files: Vec<PathBuf>, timezone: Tz) -> Vec<ScannedFile> {
+    files
+        .into_iter()
+        .map(|path| ScannedFile {
+            taken_at: capture_time(&path, timezone),
+            content: fs::metadata(&path)
+                .and_then(|file| Ok((file.len(), relocate::hash_file(&path)?)))
+                .ok(),
+            metadata: refresh::read_file_metadata(&path, timezone),
+            path,
+        })
+        .collect()
+}
+
+/// Attach a screenshot file by hand, to `instance_id` or else to the instance of the signed-in
+/// account open at its capture time. A file that is already recorded is moved to that instance.
+/// Times in file names are read in `timezone`.
+pub fn attach_screenshot(
+    conn: &Connection,
+    path: &Path,
+    instance_id: Option<i64>,
+    timezone: Tz,
+) -> Result<AttachedScreenshot, String> {
+    if !path.is_file() {
+        return Err(format!("Screenshot not found: {:?}", path));
+    }
+    let taken_at = capture_time(path, timezone)
+        .ok_or_else(|| format!("Failed to determine capture time of {:?}", path))?;
+
+    let instance_id = match instance_id {
+        Some(id) => id,
+        None => operations::get_latest_authenticated_account(conn)
+            .and_then(|account| match account {
+                Some((my_account_id, _)) => {
+                    operations::find_instance_at_time(conn, Some(my_account_id), taken_at)
+                }
+                None => Ok(None),
+            })
+            .map_err(|e| format!("Failed to find instance: {}", e))?
+            .ok_or_else(|| format!("No instance was open when {:?} was taken", path))?,
+    };
+
+    let file_path = path.to_string_lossy().to_string();
+    let screenshot_id = operations::find_screenshot_by_path(conn, &file_path)
+        .and_then(|existing| match existing {
+            Some(id) => {
+                operations::set_screenshot_instance(conn, id, instance_id)?;
+                operations::set_screenshot_source(conn, id, ScreenshotSource::Manual)?;
+                Ok(id)
+            }
+            None => operations::record_screenshot(
+                conn,
+                instance_id,
+                &file_path,
+                taken_at,
+                ScreenshotSource::Manual,
+            ),
+        })
+        .and_then(|id| refresh::refresh_metadata(conn, id, timezone).map(|_| id))
+        .map_err(|e| format!("Failed to attach screenshot: {}", e))?;
+
+    Ok(AttachedScreenshot {
+        screenshot_id,
+        instance_id,
+        file_path,
+        taken_at,
+        source: ScreenshotSource::Manual,
+    })
+}
+
+/// Capture time from the file name (VRChat or other tools), falling back to the modification time
+fn capture_time(path: &Path, timezone: Tz) -> Option<i64> {
+    let file_name = path.file_name()?.to_str()?;
+
+    metadata::parse_file_name(path, timezone)
+        .taken_at
+        .or_else(|| time_from_file_name(file_name, timezone))
+        .or_else(|| {
+            let modified = fs::metadata(path).ok()?.modified().ok()?;
+            let since_epoch = modified.duration_since(UNIX_EPOCH).ok()?;
+            i64::try_from(since_epoch.as_millis()).ok()
+        })
+}
+
+fn time_from_file_name(file_name: &str, timezone: Tz) -> Option<i64> {
+    let caps = TIMESTAMP_REGEX.captures(file_name)?;
+    let part = |i: usize| caps[i].parse::<u32>().ok();
+
+    let naive = NaiveDate::from_ymd_opt(caps[1].parse().ok()?, part(2)?, part(3)?)?.and_hms_opt(
+        part(4)?,
+        part(5)?,
+        part(6)?,
+    )?;
+    timezone
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|local| local.timestamp_millis())
+}
+
+pub(super) fn collect_images(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+
+        if file_type.is_dir() {
+            if depth > 0 {
+                collect_images(&path, depth - 1, files);
+            }
+        } else if file_type.is_file() && is_image(&path) {
+            files.push(path);
+        }
+    }
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| {
+            IMAGE_EXTENSIONS
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_handler::EventHandler;
+    use crate::test_support::{database_from_log, replay_log, utc, SAMPLE_LOG};
+    use chrono_tz::Asia::Tokyo;
+
+    #[test]
+    fn test_time_from_file_name() {
+        // 10:00:05 in Tokyo
+        let expected = Some(utc("2025-10-13T01:00:05Z").timestamp_millis());
+        assert_eq!(time_from_file_name("20251013100005_1.png", Tokyo), expected);
+        assert_eq!(
+            time_from_file_name("2025-10-13 10-00-05.png", Tokyo),
+            expected
+        );
+        assert_eq!(
+            time_from_file_name("Screenshot 2025-10-13 100005.png", Tokyo),
+            expected
+        );
+
+        assert_eq!(time_from_file_name("20251399100005_1.png", Tokyo), None);
+        assert_eq!(time_from_file_name("IMG_0042.png", Tokyo), None);
+    }
+
+    #[test]
+    fn test_capture_time_reads_vrchat_names_in_the_given_zone() {
+        let path = Path::new("VRChat_2025-10-13_10-00-00.000_1920x1080.png");
+        assert_eq!(
+            capture_time(path, Tokyo),
+            Some(utc("2025-10-13T01:00:00Z").timestamp_millis())
+        );
+        assert_eq!(
+            capture_time(path, Tz::UTC),
+            Some(utc("2025-10-13T10:00:00Z").timestamp_millis())
+        );
+    }
+
+    fn scanned(path: &str, taken_at: &str) -> ScannedFile {
+        ScannedFile {
+            path: PathBuf::from(path),
+            taken_at: Some(utc(taken_at).timestamp_millis()),
+            content: None,
+            metadata: ScreenshotMetadata::default(),
+        }
+    }
+
+    #[test]
+    fn test_link_matches_any_account_and_retries_unmatched_files() {
+        let mut database = database_from_log(SAMPLE_LOG);
+        // Another account signed in after the instance was recorded
+        replay_log(
+            &mut database,
+            &mut EventHandler::new(),
+            &["2025.10.15 09:00:00 Debug      -  User Authenticated: Alt (usr_00000000-0000-0000-0000-000000000003)"],
+        );
+        let conn = database.connection();
+        let mut scanner = FolderScanner::new();
+
+        let attached = scanner
+            .link(
+                conn,
+                vec![
+                    scanned("C:\\pics\\a.png", "2025-10-13T10:30:00Z"),
+                    scanned("C:\\pics\\b.png", "2025-10-16T10:30:00Z"),
+                ],
+            )
+            .unwrap();
+        assert_eq!(attached.len(), 1);
+        assert_eq!(attached[0].file_path, "C:\\pics\\a.png");
+
+        // Only the linked file is skipped by later scans
+        assert!(scanner.seen.contains(Path::new("C:\\pics\\a.png")));
+        assert!(!scanner.seen.contains(Path::new("C:\\pics\\b.png")));
+    }
+
+    #[test]
+    fn test_deleted_files_are_forgotten() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("20251013100005_1.png");
+        fs::write(&path, b"png").unwrap();
+        let settled = SystemTime::now() - SETTLE_TIME * 2;
+        fs::File::options()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(settled)
+            .unwrap();
+
+        let folders = [dir.path().to_path_buf()];
+        let mut scanner = FolderScanner::new();
+        assert_eq!(scanner.collect_new_files(&folders), vec![path.clone()]);
+
+        scanner.seen.insert(path.clone());
+        assert!(scanner.collect_new_files(&folders).is_empty());
+
+        fs::remove_file(&path).unwrap();
+        scanner.collect_new_files(&folders);
+        assert!(scanner.seen.is_empty());
+    }
+}
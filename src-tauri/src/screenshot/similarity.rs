@@ -0,0 +1,141 @@
+use crate::db::operations::HashedScreenshot;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageReader};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Hamming distance up to which two dHashes count as near-duplicates
+pub const DEFAULT_MAX_DISTANCE: u32 = 6;
+
+/// Screenshots that look alike
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub screenshots: Vec<HashedScreenshot>, // Oldest first
+    pub instance_count: usize,              // More than 1 for copies across instances
+}
+
+/// 64-bit difference hash: each bit tells whether a pixel is brighter than its right neighbour
+/// in a 9x8 grayscale version of the image
+pub fn dhash(image: &DynamicImage) -> u64 {
+    let small = image.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash = 0u64;
+    for y in 0..8 {
+        for x in 0..8 {
+            let bit = small.get_pixel(x, y)[0] > small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(bit);
+        }
+    }
+    hash
+}
+
+/// Decode an image file and compute its dHash
+pub fn dhash_file(path: &Path) -> Result<u64, String> {
+    let image = ImageReader::open(path)
+        .map_err(|e| format!("Failed to open {:?}: {}", path, e))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode {:?}: {}", path, e))?;
+
+    Ok(dhash(&image))
+}
+
+/// Group screenshots whose hashes are within `max_distance` bits of each other
+/// (directly or through other members of the group). Singletons are left out.
+pub fn find_duplicate_groups(
+    screenshots: Vec<HashedScreenshot>,
+    max_distance: u32,
+) -> Vec<DuplicateGroup> {
+    let hashes: Vec<u64> = screenshots.iter().map(|s| s.perceptual_hash).collect();
+    let groups = group_indices(&hashes, max_distance);
+
+    let mut screenshots: Vec<Option<HashedScreenshot>> =
+        screenshots.into_iter().map(Some).collect();
+    groups
+        .into_iter()
+        .map(|indices| {
+            let members: Vec<HashedScreenshot> = indices
+                .into_iter()
+                .filter_map(|i| screenshots[i].take())
+                .collect();
+            let instance_count = members
+                .iter()
+                .map(|s| s.instance_id)
+                .collect::<HashSet<_>>()
+                .len();
+
+            DuplicateGroup {
+                screenshots: members,
+                instance_count,
+            }
+        })
+        .collect()
+}
+
+/// Connected components of the "within `max_distance`" relation, in order of first member
+fn group_indices(hashes: &[u64], max_distance: u32) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..hashes.len()).collect();
+
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..hashes.len() {
+        for j in i + 1..hashes.len() {
+            if (hashes[i] ^ hashes[j]).count_ones() <= max_distance {
+                let (a, b) = (root(&mut parent, i), root(&mut parent, j));
+                if a != b {
+                    parent[a.max(b)] = a.min(b);
+                }
+            }
+        }
+    }
+
+    let mut groups: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for i in 0..hashes.len() {
+        let r = root(&mut parent, i);
+        groups.entry(r).or_default().push(i);
+    }
+
+    groups
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    fn gradient(offset: u8, reversed: bool) -> DynamicImage {
+        let image = GrayImage::from_fn(90, 80, |x, y| {
+            let x = if reversed { 89 - x } else { x };
+            Luma([(x as u8 * 2 + (y as u8 % 7) * 3).saturating_add(offset)])
+        });
+        DynamicImage::ImageLuma8(image)
+    }
+
+    #[test]
+    fn test_dhash_ignores_brightness_but_not_content() {
+        let original = dhash(&gradient(0, false));
+        let brighter = dhash(&gradient(20, false));
+        let mirrored = dhash(&gradient(0, true));
+
+        assert!((original ^ brighter).count_ones() <= DEFAULT_MAX_DISTANCE);
+        assert!((original ^ mirrored).count_ones() > DEFAULT_MAX_DISTANCE);
+    }
+
+    #[test]
+    fn test_group_indices_is_transitive() {
+        let hashes = [0b0000, 0xFFFF_0000, 0b0011, 0b1111, 0xFFFF_0001];
+        assert_eq!(group_indices(&hashes, 2), vec![vec![0, 2, 3], vec![1, 4]]);
+        assert!(group_indices(&hashes, 0).is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::ImageReader;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Encoding of cached thumbnails
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailFormat {
+    #[default]
+    Jpeg, // Lossy, smallest files
+    Webp, // Lossless
+}
+
+impl ThumbnailFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Jpeg => "jpg",
+            Self::Webp => "webp",
+        }
+    }
+}
+
+/// Thumbnail cache location and limits
+#[derive(Debug, Clone)]
+pub struct ThumbnailConfig {
+    pub dir: PathBuf,
+    pub max_edge: u32,        // Longest edge of a thumbnail in pixels
+    pub max_cache_bytes: u64, // Least recently used thumbnails are evicted beyond this
+    pub format: ThumbnailFormat,
+    pub jpeg_quality: u8,
+}
+
+impl ThumbnailConfig {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_edge: 480,
+            max_cache_bytes: 512 * 1024 * 1024,
+            format: ThumbnailFormat::Jpeg,
+            jpeg_quality: 80,
+        }
+    }
+}
+
+/// Cached thumbnail for a screenshot if it is up to date with the source file.
+/// Marks the thumbnail as recently used.
+pub fn cached_thumbnail(
+    config: &ThumbnailConfig,
+    screenshot_id: i64,
+    source: &Path,
+) -> Option<PathBuf> {
+    let path = thumbnail_path(config, screenshot_id, source).ok()?;
+    if !path.is_file() {
+        return None;
+    }
+
+    // Modification time doubles as the last-used time for eviction
+    if let Ok(file) = File::options().append(true).open(&path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+    Some(path)
+}
+
+/// Create a thumbnail for a screenshot, replacing thumbnails of older versions of the source
+pub fn generate_thumbnail(
+    config: &ThumbnailConfig,
+    screenshot_id: i64,
+    source: &Path,
+) -> Result<PathBuf, String> {
+    let path = thumbnail_path(config, screenshot_id, source)?;
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    fs::create_dir_all(&config.dir)
+        .map_err(|e| format!("Failed to create thumbnail directory: {}", e))?;
+
+    let image = ImageReader::open(source)
+        .map_err(|e| format!("Failed to open {:?}: {}", source, e))?
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read {:?}: {}", source, e))?
+        .decode()
+        .map_err(|e| format!("Failed to decode {:?}: {}", source, e))?;
+    let thumbnail = image.thumbnail(config.max_edge, config.max_edge).to_rgb8();
+
+    let tmp_path = path.with_extension("tmp");
+    let result = File::create(&tmp_path)
+        .map_err(|e| e.to_string())
+        .and_then(|file| {
+            let mut out = BufWriter::new(file);
+            match config.format {
+                ThumbnailFormat::Jpeg => thumbnail.write_with_encoder(
+                    JpegEncoder::new_with_quality(&mut out, config.jpeg_quality),
+                ),
+                ThumbnailFormat::Webp => {
+                    thumbnail.write_with_encoder(WebPEncoder::new_lossless(&mut out))
+                }
+            }
+            .map_err(|e| e.to_string())
+        })
+        .and_then(|()| fs::rename(&tmp_path, &path).map_err(|e| e.to_string()));
+
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(format!("Failed to write thumbnail {:?}: {}", path, e));
+    }
+
+    remove_stale_thumbnails(config, screenshot_id, &path);
+    Ok(path)
+}
+
+/// Delete least recently used thumbnails until the cache fits `max_cache_bytes`.
+/// Returns the number of deleted files.
+pub fn evict_thumbnails(config: &ThumbnailConfig) -> Result<usize, String> {
+    if !config.dir.exists() {
+        return Ok(0);
+    }
+
+    let entries = fs::read_dir(&config.dir)
+        .map_err(|e| format!("Failed to read thumbnail directory: {}", e))?;
+
+    let mut files: Vec<(PathBuf, u64, SystemTime)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            let used_at = metadata.modified().ok()?;
+            metadata
+                .is_file()
+                .then(|| (entry.path(), metadata.len(), used_at))
+        })
+        .collect();
+
+    let mut total: u64 = files.iter().map(|(_, size, _)| size).sum();
+    if total <= config.max_cache_bytes {
+        return Ok(0);
+    }
+
+    files.sort_by_key(|(_, _, used_at)| *used_at);
+
+    let mut deleted = 0;
+    for (path, size, _) in files {
+        if total <= config.max_cache_bytes {
+            break;
+        }
+        match fs::remove_file(&path) {
+            Ok(()) => {
+                total -= size;
+                deleted += 1;
+            }
+            Err(e) => tracing::error!("Failed to evict thumbnail {:?}: {}", path, e),
+        }
+    }
+
+    Ok(deleted)
+}
+
+/// `<dir>/<screenshot id>-<source size>-<source mtime>-<max edge>.<ext>`.
+/// The name changes whenever the source file is modified, invalidating the old thumbnail.
+fn thumbnail_path(
+    config: &ThumbnailConfig,
+    screenshot_id: i64,
+    source: &Path,
+) -> Result<PathBuf, String> {
+    let metadata =
+        fs::metadata(source).map_err(|e| format!("Screenshot not found {:?}: {}", source, e))?;
+
+    let modified_ms = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+
+    Ok(config.dir.join(format!(
+        "{}-{:x}-{:x}-{}.{}",
+        screenshot_id,
+        metadata.len(),
+        modified_ms,
+        config.max_edge,
+        config.format.extension()
+    )))
+}
+
+/// Remove other thumbnails of the same screenshot (older source versions or settings)
+fn remove_stale_thumbnails(config: &ThumbnailConfig, screenshot_id: i64, current: &Path) {
+    let prefix = format!("{}-", screenshot_id);
+    let Ok(entries) = fs::read_dir(&config.dir) else {
+        return;
+    };
+
+    for path in entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+    {
+        let is_same_screenshot = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .is_some_and(|n| n.starts_with(&prefix));
+
+        if is_same_screenshot && path != current {
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+    use std::time::Duration;
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        RgbImage::from_pixel(width, height, Rgb([40, 120, 200]))
+            .save(path)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_thumbnail_is_downscaled_and_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir
+            .path()
+            .join("VRChat_2025-10-13_10-00-00.000_1000x500.png");
+        write_png(&source, 1000, 500);
+        let config = ThumbnailConfig::new(dir.path().join("thumbnails"));
+
+        assert_eq!(cached_thumbnail(&config, 1, &source), None);
+        let path = generate_thumbnail(&config, 1, &source).unwrap();
+
+        let thumbnail = image::open(&path).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (480, 240));
+        assert_eq!(cached_thumbnail(&config, 1, &source), Some(path));
+    }
+
+    #[test]
+    fn test_changed_source_invalidates_thumbnail() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("screenshot.png");
+        write_png(&source, 1000, 500);
+        let config = ThumbnailConfig::new(dir.path().join("thumbnails"));
+        let old_path = generate_thumbnail(&config, 1, &source).unwrap();
+
+        // e.g. the screenshot was cropped in an image editor
+        write_png(&source, 600, 600);
+        assert_eq!(cached_thumbnail(&config, 1, &source), None);
+
+        let new_path = generate_thumbnail(&config, 1, &source).unwrap();
+        assert_ne!(new_path, old_path);
+        assert!(!old_path.exists());
+        let thumbnail = image::open(&new_path).unwrap();
+        assert_eq!((thumbnail.width(), thumbnail.height()), (480, 480));
+    }
+
+    #[test]
+    fn test_eviction_removes_least_recently_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut config = ThumbnailConfig::new(dir.path().to_path_buf());
+        config.max_cache_bytes = 250;
+
+        let now = SystemTime::now();
+        let files: Vec<PathBuf> = (1..=3)
+            .map(|id| {
+                let path = dir.path().join(format!("{}-0-0-480.jpg", id));
+                fs::write(&path, [0u8; 100]).unwrap();
+                let used_at = now - Duration::from_secs(60 * (4 - id));
+                File::options()
+                    .append(true)
+                    .open(&path)
+                    .unwrap()
+                    .set_modified(used_at)
+                    .unwrap();
+                path
+            })
+            .collect();
+
+        assert_eq!(evict_thumbnails(&config).unwrap(), 1);
+        assert!(!files[0].exists());
+        assert!(files[1].exists() && files[2].exists());
+
+        // Within the limit nothing more is deleted
+        assert_eq!(evict_thumbnails(&config).unwrap(), 0);
+    }
+}
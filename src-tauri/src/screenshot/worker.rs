@@ -0,0 +1,136 @@
+use super::thumbnail::{self, ThumbnailConfig};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+/// Evict the cache after this many generated thumbnails (and whenever the queue drains)
+const EVICT_EVERY: usize = 50;
+
+struct ThumbnailJob {
+    screenshot_id: i64,
+    source: PathBuf,
+}
+
+enum WorkerMessage {
+    Generate(ThumbnailJob),
+    Stop, // Jobs still queued are dropped; thumbnails are generated on demand later
+}
+
+/// Generates thumbnails on a background thread
+#[derive(Clone)]
+pub struct ThumbnailWorker {
+    sender: Sender<WorkerMessage>,
+    config: Arc<ThumbnailConfig>,
+    thread: Arc<Mutex<Option<JoinHandle<()>>>>,
+}
+
+impl ThumbnailWorker {
+    /// Start the worker thread. `on_ready` is called for each generated thumbnail.
+    pub fn spawn(config: ThumbnailConfig, on_ready: impl Fn(i64, &Path) + Send + 'static) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let config = Arc::new(config);
+
+        let worker_config = config.clone();
+        let thread = std::thread::spawn(move || run(&worker_config, receiver, on_ready));
+
+        Self {
+            sender,
+            config,
+            thread: Arc::new(Mutex::new(Some(thread))),
+        }
+    }
+
+    pub fn config(&self) -> &ThumbnailConfig {
+        &self.config
+    }
+
+    /// Queue a screenshot for thumbnail generation
+    pub fn enqueue(&self, screenshot_id: i64, source: PathBuf) {
+        let job = ThumbnailJob {
+            screenshot_id,
+            source,
+        };
+        if self.sender.send(WorkerMessage::Generate(job)).is_err() {
+            tracing::warn!("Thumbnail worker has stopped");
+        }
+    }
+
+    /// Stop the worker after the thumbnail in progress and wait for it
+    pub fn shutdown(&self) {
+        let Some(thread) = self.thread.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+            return;
+        };
+
+        let _ = self.sender.send(WorkerMessage::Stop);
+        if thread.join().is_err() {
+            tracing::error!("Thumbnail worker thread panicked");
+        }
+    }
+}
+
+fn run(config: &ThumbnailConfig, receiver: Receiver<WorkerMessage>, on_ready: impl Fn(i64, &Path)) {
+    let mut generated = 0;
+    let mut next = None;
+
+    loop {
+        let job = match next.take() {
+            Some(job) => job,
+            None => match receiver.recv() {
+                Ok(WorkerMessage::Generate(job)) => job,
+                Ok(WorkerMessage::Stop) | Err(_) => break, // Err: all senders dropped
+            },
+        };
+
+        match thumbnail::generate_thumbnail(config, job.screenshot_id, &job.source) {
+            Ok(path) => {
+                on_ready(job.screenshot_id, &path);
+                generated += 1;
+            }
+            Err(e) => tracing::error!("Failed to generate thumbnail: {}", e),
+        }
+
+        next = match receiver.try_recv() {
+            Ok(WorkerMessage::Generate(job)) => Some(job),
+            Ok(WorkerMessage::Stop) => break,
+            Err(_) => None,
+        };
+        if generated >= EVICT_EVERY || (generated > 0 && next.is_none()) {
+            if let Err(e) = thumbnail::evict_thumbnails(config) {
+                tracing::error!("Failed to evict thumbnails: {}", e);
+            }
+            generated = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+    use std::time::Duration;
+
+    #[test]
+    fn test_worker_generates_thumbnails_until_shut_down() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("screenshot.png");
+        RgbImage::from_pixel(100, 50, Rgb([40, 120, 200]))
+            .save(&source)
+            .unwrap();
+
+        let (ready, generated) = mpsc::channel();
+        let worker = ThumbnailWorker::spawn(
+            ThumbnailConfig::new(dir.path().join("thumbnails")),
+            move |screenshot_id, _| ready.send(screenshot_id).unwrap(),
+        );
+
+        worker.enqueue(1, source.clone());
+        assert_eq!(generated.recv_timeout(Duration::from_secs(10)), Ok(1));
+
+        worker.shutdown();
+        // Jobs queued after the shutdown are dropped
+        worker.enqueue(2, source);
+        assert!(generated.recv_timeout(Duration::from_millis(100)).is_err());
+        worker.shutdown();
+    }
+}
@@ -0,0 +1,296 @@
+mod store;
+
+pub use store::SettingsStore;
+
+use crate::db::{
+    operations::{self, RetentionPolicy},
+    BackupConfig,
+};
+use crate::export::AnonymizeOptions;
+use crate::logging;
+use chrono_tz::Tz;
+use rusqlite::Connection;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// User settings (stored in the `settings` table, one JSON value per field)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    pub poll_interval_ms: u64,    // How often the monitor checks the logs
+    pub log_dirs: Vec<String>,    // VRChat log folders (empty uses the default one)
+    pub timezone: Option<String>, // IANA zone of logs without their own or UTC hints (None: system's)
+    pub backup: BackupSettings,
+    pub retention: RetentionPolicy,
+    pub privacy: PrivacySettings,
+    pub logging: LoggingSettings,
+}
+
+/// Scheduled backup settings
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupSettings {
+    pub dir: Option<String>, // Backup folder (None uses "backups" in app data)
+    pub keep: usize,         // Number of backups kept by rotation
+    pub interval_hours: u32,
+}
+
+/// Defaults for exports that don't choose their own anonymization
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PrivacySettings {
+    pub anonymize_exports: bool, // Replace identities with pseudonyms in exports
+    pub strip_instance_owners: bool,
+    pub strip_screenshot_paths: bool,
+}
+
+/// App log levels: error, warn, info, debug or trace
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LoggingSettings {
+    pub level: String,                     // Level of modules not listed below
+    pub modules: BTreeMap<String, String>, // Module (e.g. "log_parser") -> level
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 1000,
+            log_dirs: Vec::new(),
+            timezone: None,
+            backup: BackupSettings::default(),
+            retention: RetentionPolicy::default(),
+            privacy: PrivacySettings::default(),
+            logging: LoggingSettings::default(),
+        }
+    }
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            keep: 7,
+            interval_hours: 24,
+        }
+    }
+}
+
+impl Default for LoggingSettings {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            modules: BTreeMap::new(),
+        }
+    }
+}
+
+impl PrivacySettings {
+    /// Anonymization applied to exports without their own options
+    pub fn anonymize_options(&self) -> Option<AnonymizeOptions> {
+        self.anonymize_exports.then_some(AnonymizeOptions {
+            salt: None,
+            strip_instance_owners: self.strip_instance_owners,
+            strip_screenshot_paths: self.strip_screenshot_paths,
+        })
+    }
+}
+
+impl Settings {
+    /// Check values before they are saved
+    pub fn validate(&self) -> Result<(), String> {
+        if !(100..=60_000).contains(&self.poll_interval_ms) {
+            return Err("Poll interval must be between 100 ms and 60 s".to_string());
+        }
+
+        for log_dir in &self.log_dirs {
+            if !Path::new(log_dir).is_dir() {
+                return Err(format!("Log folder not found: {}", log_dir));
+            }
+        }
+
+        if let Some(timezone) = &self.timezone {
+            timezone
+                .parse::<Tz>()
+                .map_err(|_| format!("Unknown time zone: {}", timezone))?;
+        }
+
+        if let Some(dir) = &self.backup.dir {
+            if !Path::new(dir).is_absolute() {
+                return Err(format!("Backup folder must be an absolute path: {}", dir));
+            }
+        }
+        if !(1..=365).contains(&self.backup.keep) {
+            return Err("Number of kept backups must be between 1 and 365".to_string());
+        }
+        if !(1..=24 * 30).contains(&self.backup.interval_hours) {
+            return Err("Backup interval must be between 1 hour and 30 days".to_string());
+        }
+
+        let retention_days = [
+            self.retention.participant_days,
+            self.retention.avatar_history_days,
+        ];
+        if retention_days.contains(&Some(0)) {
+            return Err("Retention periods must be at least one day".to_string());
+        }
+
+        for level in std::iter::once(&self.logging.level).chain(self.logging.modules.values()) {
+            level
+                .parse::<tracing::level_filters::LevelFilter>()
+                .map_err(|_| format!("Unknown log level: {}", level))?;
+        }
+        for module in self.logging.modules.keys() {
+            if !logging::MODULES.contains(&module.as_str()) {
+                return Err(format!("Unknown log module: {}", module));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+
+    pub fn log_dirs(&self) -> Vec<PathBuf> {
+        self.log_dirs.iter().map(PathBuf::from).collect()
+    }
+
+    /// Time zone of new logs (None uses the system's; unknown names were rejected by `validate`)
+    pub fn timezone(&self) -> Option<Tz> {
+        self.timezone.as_deref().and_then(|name| name.parse().ok())
+    }
+
+    /// Zone used to show local times: the configured one, or the system's
+    pub fn local_timezone(&self) -> Tz {
+        self.timezone().unwrap_or_else(system_timezone)
+    }
+
+    pub fn backup_config(&self, default_dir: &Path) -> BackupConfig {
+        BackupConfig {
+            dir: self
+                .backup
+                .dir
+                .as_ref()
+                .map_or_else(|| default_dir.to_path_buf(), PathBuf::from),
+            keep: self.backup.keep,
+            interval: Duration::from_secs(u64::from(self.backup.interval_hours) * 60 * 60),
+            timezone: self.local_timezone(),
+        }
+    }
+}
+
+/// IANA time zone of this machine (UTC when it cannot be read)
+pub fn system_timezone() -> Tz {
+    let name = match iana_time_zone::get_timezone() {
+        Ok(name) => name,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to read the system time zone, using UTC");
+            return Tz::UTC;
+        }
+    };
+
+    name.parse().unwrap_or_else(|_| {
+        tracing::warn!(timezone = %name, "Unknown system time zone, using UTC");
+        Tz::UTC
+    })
+}
+
+/// Load settings from the database (missing fields use defaults)
+pub fn load(conn: &Connection) -> Result<Settings, String> {
+    let values = operations::get_setting_values(conn)
+        .map_err(|e| format!("Failed to load settings: {}", e))?;
+
+    let mut fields = serde_json::Map::new();
+    for (key, value) in values {
+        match serde_json::from_str(&value) {
+            Ok(value) => {
+                fields.insert(key, value);
+            }
+            Err(e) => tracing::warn!("Ignoring invalid setting {}: {}", key, e),
+        }
+    }
+
+    serde_json::from_value(serde_json::Value::Object(fields))
+        .map_err(|e| format!("Failed to read settings: {}", e))
+}
+
+/// Save every field of the settings
+pub fn save(conn: &Connection, settings: &Settings) -> Result<(), String> {
+    let serde_json::Value::Object(fields) = serde_json::to_value(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?
+    else {
+        return Err("Settings must serialize to an object".to_string());
+    };
+
+    for (key, value) in fields {
+        operations::set_setting_value(conn, &key, &value.to_string())
+            .map_err(|e| format!("Failed to save setting {}: {}", key, e))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_fields_use_defaults() {
+        let settings: Settings =
+            serde_json::from_str(r#"{"pollIntervalMs":500,"backup":{"keep":3}}"#).unwrap();
+
+        assert_eq!(settings.poll_interval_ms, 500);
+        assert_eq!(settings.backup.keep, 3);
+        assert_eq!(settings.backup.interval_hours, 24);
+        assert!(settings.log_dirs.is_empty());
+        assert!(settings.privacy.anonymize_options().is_none());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(Settings::default().validate().is_ok());
+
+        let mut settings = Settings {
+            timezone: Some("Asia/Tokyo".to_string()),
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.timezone(), Some(chrono_tz::Asia::Tokyo));
+
+        settings.timezone = Some("Mars/Olympus".to_string());
+        assert!(settings.validate().is_err());
+
+        let settings = Settings {
+            poll_interval_ms: 10,
+            ..Settings::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.retention.participant_days = Some(0);
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings.backup.dir = Some("backups".to_string());
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings
+            .logging
+            .modules
+            .insert("log_parser".to_string(), "debug".to_string());
+        assert!(settings.validate().is_ok());
+        settings.logging.level = "loud".to_string();
+        assert!(settings.validate().is_err());
+
+        let mut settings = Settings::default();
+        settings
+            .logging
+            .modules
+            .insert("vrchat".to_string(), "debug".to_string());
+        assert!(settings.validate().is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use super::Settings;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Current settings shared between commands and background threads.
+/// Every change bumps a version so threads can tell when to pick up new values.
+pub struct SettingsStore {
+    current: Mutex<(u64, Settings)>,
+    changed: Condvar,
+}
+
+impl SettingsStore {
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            current: Mutex::new((0, settings)),
+            changed: Condvar::new(),
+        }
+    }
+
+    pub fn get(&self) -> Settings {
+        self.lock().1.clone()
+    }
+
+    /// Version of the current settings
+    pub fn version(&self) -> u64 {
+        self.lock().0
+    }
+
+    /// Replace the settings and wake threads waiting for a change
+    pub fn replace(&self, settings: Settings) {
+        let mut current = self.lock();
+        current.0 += 1;
+        current.1 = settings;
+        self.changed.notify_all();
+    }
+
+    /// Sleep for `timeout` unless the settings change from `version` first.
+    /// Returns whether they changed.
+    pub fn wait_for_change(&self, version: u64, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        let mut current = self.lock();
+
+        while current.0 == version {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return false;
+            }
+            current = self
+                .changed
+                .wait_timeout(current, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+
+        true
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (u64, Settings)> {
+        self.current.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,105 @@
+use crate::db::Database;
+use crate::event_handler::EventHandler;
+use crate::log_parser::LogParser;
+use crate::types::{LogEvent, VRChatEvent};
+use chrono::{DateTime, Utc};
+use rusqlite::Connection;
+
+/// VRChat user ID of the local account in `SAMPLE_LOG`
+pub const MY_USER_ID: &str = "usr_00000000-0000-0000-0000-000000000001";
+
+/// VRChat user ID of the friend in `SAMPLE_LOG`
+pub const FRIEND_USER_ID: &str = "usr_00000000-0000-0000-0000-000000000002";
+
+/// One evening in a friends instance with a friend, an avatar change and a screenshot (UTC)
+pub const SAMPLE_LOG: &[&str] = &[
+    "2025.10.13 09:53:16 Debug      -  User Authenticated: Me (usr_00000000-0000-0000-0000-000000000001)",
+    "2025.10.13 09:53:22 Debug      -  [Behaviour] Joining wrld_00000000-0000-0000-0000-00000000000a:1234~friends(usr_00000000-0000-0000-0000-000000000001)~region(jp)",
+    "2025.10.13 09:53:23 Debug      -  [Behaviour] Joining or Creating Room: Test World",
+    "2025.10.13 09:53:24 Debug      -  [Behaviour] OnPlayerJoined Me (usr_00000000-0000-0000-0000-000000000001)",
+    "2025.10.13 09:53:25 Debug      -  [Behaviour] Switching Friend to avatar Cool Avatar",
+    "2025.10.13 09:53:26 Debug      -  [Behaviour] OnPlayerJoined Friend (usr_00000000-0000-0000-0000-000000000002)",
+    "2025.10.13 10:00:00 Debug      -  [VRC Camera] Took screenshot to: C:\\pics\\VRChat_2025-10-13_10-00-00.000_1920x1080.png",
+    "2025.10.13 23:30:00 Debug      -  [Behaviour] Destroying Friend",
+    "2025.10.14 00:30:00 Debug      -  [Behaviour] Destroying Me",
+];
+
+/// Log file of the game session started by `database_in_instance`
+pub const SAMPLE_LOG_FILE: &str = "C:\\logs\\output_log_2025-10-13_09-53-00.txt";
+
+/// Migrated in-memory database with the events of `lines` recorded
+pub fn database_from_log(lines: &[&str]) -> Database {
+    let mut database = Database::open_in_memory().unwrap();
+    replay_log(&mut database, &mut EventHandler::new(), lines);
+    database
+}
+
+/// Record log lines through an existing handler, as the monitor does for new lines
+pub fn replay_log(database: &mut Database, handler: &mut EventHandler, lines: &[&str]) {
+    let parser = LogParser::new();
+    let tx = database.transaction().unwrap();
+    for line in lines {
+        let event = parser.parse_line(line).expect(line);
+        handler.process_event(&tx, event).unwrap();
+    }
+    tx.commit().unwrap();
+}
+
+/// Handle events through an existing handler in one transaction, returning what it emitted
+pub fn process_events(
+    database: &mut Database,
+    handler: &mut EventHandler,
+    events: Vec<LogEvent>,
+) -> Vec<VRChatEvent> {
+    let tx = database.transaction().unwrap();
+    let emitted = events
+        .into_iter()
+        .filter_map(|event| handler.process_event(&tx, event).unwrap())
+        .collect();
+    tx.commit().unwrap();
+    emitted
+}
+
+/// Parse an RFC 3339 time, e.g. "2025-10-13T10:00:00Z"
+pub fn utc(timestamp: &str) -> DateTime<Utc> {
+    timestamp.parse().unwrap()
+}
+
+/// Handler still in the `SAMPLE_LOG` instance with the friend (before the screenshot),
+/// in a game session of `SAMPLE_LOG_FILE`
+pub fn database_in_instance() -> (Database, EventHandler) {
+    let mut database = Database::open_in_memory().unwrap();
+    let mut handler = EventHandler::new();
+    let log_started = LogEvent::LogStarted {
+        timestamp: utc("2025-10-13T09:53:00Z"),
+        file_path: SAMPLE_LOG_FILE.to_string(),
+    };
+    process_events(&mut database, &mut handler, vec![log_started]);
+    replay_log(&mut database, &mut handler, &SAMPLE_LOG[..6]);
+    (database, handler)
+}
+
+/// Status and end time of an instance
+pub fn instance_end(conn: &Connection, instance_id: i64) -> (String, Option<i64>) {
+    conn.query_row(
+        "SELECT status, ended_at FROM instances WHERE id = ?1",
+        (instance_id,),
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .unwrap()
+}
+
+/// Players not marked as left from an instance
+pub fn users_present(conn: &Connection, instance_id: i64) -> i64 {
+    conn.query_row(
+        "SELECT COUNT(*) FROM instance_users WHERE instance_id = ?1 AND left_at IS NULL",
+        (instance_id,),
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+/// Database holding `SAMPLE_LOG`
+pub fn sample_database() -> Database {
+    database_from_log(SAMPLE_LOG)
+}
@@ -0,0 +1,137 @@
+/// Instance access type encoded in the instance ID tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessType {
+    Public,
+    FriendsPlus,
+    Friends,
+    InvitePlus,
+    Invite,
+    GroupPublic,
+    GroupPlus,
+    Group,
+}
+
+impl AccessType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::FriendsPlus => "friends_plus",
+            Self::Friends => "friends",
+            Self::InvitePlus => "invite_plus",
+            Self::Invite => "invite",
+            Self::GroupPublic => "group_public",
+            Self::GroupPlus => "group_plus",
+            Self::Group => "group",
+        }
+    }
+}
+
+/// Parsed VRChat instance ID (e.g. `12345~friends(usr_xxx)~region(jp)`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceIdInfo {
+    pub name: String,
+    pub access_type: AccessType,
+    pub owner_id: Option<String>, // usr_xxx or grp_xxx
+    pub region: Option<String>,
+}
+
+impl InstanceIdInfo {
+    pub fn parse(instance_id: &str) -> Self {
+        let mut parts = instance_id.split('~');
+        let name = parts.next().unwrap_or_default().to_string();
+
+        let mut access_type = AccessType::Public;
+        let mut owner_id = None;
+        let mut region = None;
+        let mut can_request_invite = false;
+        let mut group_access_type = None;
+
+        for part in parts {
+            let (tag, value) = match part.split_once('(') {
+                Some((tag, rest)) => (tag, Some(rest.trim_end_matches(')').to_string())),
+                None => (part, None),
+            };
+
+            match tag {
+                "hidden" => {
+                    access_type = AccessType::FriendsPlus;
+                    owner_id = value;
+                }
+                "friends" => {
+                    access_type = AccessType::Friends;
+                    owner_id = value;
+                }
+                "private" => {
+                    access_type = AccessType::Invite;
+                    owner_id = value;
+                }
+                "group" => {
+                    access_type = AccessType::Group;
+                    owner_id = value;
+                }
+                "canRequestInvite" => can_request_invite = true,
+                "groupAccessType" => group_access_type = value,
+                "region" => region = value,
+                _ => {}
+            }
+        }
+
+        if access_type == AccessType::Invite && can_request_invite {
+            access_type = AccessType::InvitePlus;
+        }
+        if access_type == AccessType::Group {
+            access_type = match group_access_type.as_deref() {
+                Some("public") => AccessType::GroupPublic,
+                Some("plus") => AccessType::GroupPlus,
+                _ => AccessType::Group,
+            };
+        }
+
+        Self {
+            name,
+            access_type,
+            owner_id,
+            region,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_public_instance() {
+        let info = InstanceIdInfo::parse("84455~region(jp)");
+
+        assert_eq!(info.name, "84455");
+        assert_eq!(info.access_type, AccessType::Public);
+        assert_eq!(info.owner_id, None);
+        assert_eq!(info.region.as_deref(), Some("jp"));
+    }
+
+    #[test]
+    fn test_parse_friends_instance() {
+        let info = InstanceIdInfo::parse("11859~friends(usr_xxx)~region(jp)");
+
+        assert_eq!(info.access_type, AccessType::Friends);
+        assert_eq!(info.owner_id.as_deref(), Some("usr_xxx"));
+    }
+
+    #[test]
+    fn test_parse_invite_plus_instance() {
+        let info =
+            InstanceIdInfo::parse("12345~private(usr_xxx)~canRequestInvite~region(us)~nonce(abc)");
+
+        assert_eq!(info.access_type, AccessType::InvitePlus);
+        assert_eq!(info.region.as_deref(), Some("us"));
+    }
+
+    #[test]
+    fn test_parse_group_plus_instance() {
+        let info = InstanceIdInfo::parse("67890~group(grp_xxx)~groupAccessType(plus)~region(eu)");
+
+        assert_eq!(info.access_type, AccessType::GroupPlus);
+        assert_eq!(info.owner_id.as_deref(), Some("grp_xxx"));
+    }
+}
@@ -38,4 +38,51 @@ pub enum LogEvent {
     EventSyncFailed {
         timestamp: DateTime<Utc>,
     },
+    ClientVersion {
+        timestamp: DateTime<Utc>,
+        version: String,
+    },
+    VrModeDetected {
+        timestamp: DateTime<Utc>,
+        vr: bool, // false when VRChat started in desktop mode
+    },
+    ApplicationQuit {
+        timestamp: DateTime<Utc>,
+    },
+    /// Not a log line: a new log file (VRChat launch) was found
+    LogStarted {
+        timestamp: DateTime<Utc>, // First line of the file
+        file_path: String,
+    },
+    /// Not a log line: the log file ended (VRChat exited or crashed) after its last line
+    LogEnded {
+        timestamp: DateTime<Utc>,
+    },
+    /// Not a log line: a log reported as ended because it stopped growing was written to again
+    LogResumed {
+        ended_at: DateTime<Utc>, // Time the log was reported as ended at
+        file_path: String,
+    },
+}
+
+impl LogEvent {
+    /// Name of the event type (used for diagnostics)
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LogEvent::UserAuthenticated { .. } => "UserAuthenticated",
+            LogEvent::JoiningWorld { .. } => "JoiningWorld",
+            LogEvent::EnteringRoom { .. } => "EnteringRoom",
+            LogEvent::PlayerJoined { .. } => "PlayerJoined",
+            LogEvent::AvatarChanged { .. } => "AvatarChanged",
+            LogEvent::ScreenshotTaken { .. } => "ScreenshotTaken",
+            LogEvent::DestroyingPlayer { .. } => "DestroyingPlayer",
+            LogEvent::EventSyncFailed { .. } => "EventSyncFailed",
+            LogEvent::ClientVersion { .. } => "ClientVersion",
+            LogEvent::VrModeDetected { .. } => "VrModeDetected",
+            LogEvent::ApplicationQuit { .. } => "ApplicationQuit",
+            LogEvent::LogStarted { .. } => "LogStarted",
+            LogEvent::LogEnded { .. } => "LogEnded",
+            LogEvent::LogResumed { .. } => "LogResumed",
+        }
+    }
 }
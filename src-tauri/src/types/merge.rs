@@ -0,0 +1,7 @@
+/// Result of merging an imported record into the database
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeOutcome {
+    Inserted,
+    Merged,
+    Unchanged,
+}
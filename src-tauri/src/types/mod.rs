@@ -1,7 +1,13 @@
+mod instance_id;
 mod log_event;
+mod merge;
+mod screenshot_source;
 mod status;
 mod vrchat_event;
 
+pub use instance_id::{AccessType, InstanceIdInfo};
 pub use log_event::LogEvent;
+pub use merge::MergeOutcome;
+pub use screenshot_source::ScreenshotSource;
 pub use status::InstanceStatus;
 pub use vrchat_event::VRChatEvent;
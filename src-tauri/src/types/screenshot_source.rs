@@ -0,0 +1,45 @@
+/// How a screenshot was linked to its instance
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenshotSource {
+    #[default]
+    Log, // `[VRC Camera] Took screenshot` line
+    AutoMatched, // Found by the folder scanner and matched by capture time
+    Manual,      // Attached by the user
+}
+
+impl ScreenshotSource {
+    /// Convert to database string
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Log => "log",
+            Self::AutoMatched => "auto_matched",
+            Self::Manual => "manual",
+        }
+    }
+
+    /// Parse from database string
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "log" => Ok(Self::Log),
+            "auto_matched" => Ok(Self::AutoMatched),
+            "manual" => Ok(Self::Manual),
+            _ => Err(format!("Unknown screenshot source: {}", s)),
+        }
+    }
+}
+
+// Bind as database string
+impl rusqlite::ToSql for ScreenshotSource {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+// Read from database string
+impl rusqlite::types::FromSql for ScreenshotSource {
+    fn column_result(value: rusqlite::types::ValueRef<'_>) -> rusqlite::types::FromSqlResult<Self> {
+        let s = value.as_str()?;
+        Self::from_str(s).map_err(|e| rusqlite::types::FromSqlError::Other(e.into()))
+    }
+}
@@ -5,6 +5,7 @@ pub enum InstanceStatus {
     Completed,
     Interrupted,
     SyncFailed,
+    Crashed, // VRChat stopped writing the log while in the instance
 }
 
 impl InstanceStatus {
@@ -15,6 +16,7 @@ impl InstanceStatus {
             Self::Completed => "completed",
             Self::Interrupted => "interrupted",
             Self::SyncFailed => "sync_failed",
+            Self::Crashed => "crashed",
         }
     }
 
@@ -25,6 +27,7 @@ impl InstanceStatus {
             "completed" => Ok(Self::Completed),
             "interrupted" => Ok(Self::Interrupted),
             "sync_failed" => Ok(Self::SyncFailed),
+            "crashed" => Ok(Self::Crashed),
             _ => Err(format!("Unknown instance status: {}", s)),
         }
     }
@@ -46,6 +49,13 @@ impl serde::Serialize for InstanceStatus {
     }
 }
 
+// Bind as database string
+impl rusqlite::ToSql for InstanceStatus {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
 // Deserialize for receiving from frontend (if needed)
 impl<'de> serde::Deserialize<'de> for InstanceStatus {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -26,6 +26,12 @@ pub enum VRChatEvent {
         status: InstanceStatus,
     },
 
+    /// An instance closed as crashed turned out to be still running (the log resumed)
+    InstanceReopened {
+        instance_id: i64,
+        status: InstanceStatus,
+    },
+
     UserJoined {
         instance_id: i64,
         instance_user_id: i64,
@@ -6,7 +6,7 @@ export interface BackendEventHandlers {
   onBackendReady?: () => void | Promise<void>;
   onLocalPlayerUpdated?: () => void;
   onInstanceCreated?: () => void;
-  onInstanceEnded?: (instanceId: number, endedAt: string) => void;
+  onInstanceEnded?: (instanceId: number, endedAt: string, status: string) => void;
   onPlayerJoined?: () => void;
   onPlayerLeft?: () => void;
 }
@@ -49,7 +49,11 @@ export function useBackendEvents(handlers: BackendEventHandlers) {
           break;
 
         case 'InstanceEnded':
-          handlers.onInstanceEnded?.(processedEvent.instance_id, processedEvent.ended_at);
+          handlers.onInstanceEnded?.(
+            processedEvent.instance_id,
+            processedEvent.ended_at,
+            processedEvent.status,
+          );
           break;
 
         case 'PlayerJoined':
@@ -47,11 +47,11 @@ export function useInstances() {
     }
   }
 
-  function updateInstanceEnd(instanceId: number, endedAt: string) {
+  function updateInstanceEnd(instanceId: number, endedAt: string, status: string) {
     const instance = instances.value.find(s => s.id === instanceId);
     if (instance) {
       instance.endedAt = endedAt;
-      instance.status = 'completed';
+      instance.status = status;
     }
   }
 
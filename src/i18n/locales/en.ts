@@ -56,6 +56,7 @@ export default {
     ongoing: 'Ongoing',
     unknown: 'Unknown',
     interruptedWarning: 'VRChat may have exited unexpectedly',
+    crashedWarning: 'VRChat exited unexpectedly. The end time is the last time in the log.',
     connectionError: 'Connection Error',
     eventSyncFailedWarning: 'VRChat failed to sync instance events. Player information was not recorded.',
     durationHours: '{hours}h {minutes}m',
@@ -56,6 +56,7 @@ export default {
     ongoing: '進行中',
     unknown: '不明',
     interruptedWarning: 'VRChatが予期せず終了した可能性があります',
+    crashedWarning: 'VRChatが予期せず終了しました。終了時刻はログの最後の時刻です',
     connectionError: '接続エラー',
     eventSyncFailedWarning: 'VRChatがインスタンスのイベント同期に失敗しました。プレイヤー情報は記録されていません。',
     durationHours: '{hours}時間{minutes}分',